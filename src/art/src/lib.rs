@@ -31,19 +31,69 @@ pub fn make_square_render() -> Packet {
 
 pub mod layers {
     pub const WIRES: u8 = 0;
-    pub const EMPTY: u8 = 5;
+    pub const GATES: u8 = 1;
+    pub const TEXT: u8 = 2;
 }
 
 pub mod wires {
+    use utils::Delta;
+
     pub const NAME: &'static str = "wires.png";
     pub const SIZE: [f32; 2] = [32.0, 32.0];
     pub const RECT: [f32; 4] = [0.0, 0.0, 32.0, 32.0];
     pub const DEFAULT_TINT: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+    //level ports pulse between the plain wire and the middle of it, which shows the band twice as thick
+    pub const PULSE_NAME: &'static str = "port pulse";
+    pub const PULSE_RECT: [f32; 4] = [0.0, 8.0, 32.0, 16.0];
+    pub const PULSE_FRAME_DURATION: Delta = 0.5;
+}
+
+pub mod grid {
+    pub const CELL_COLOR: [f32; 4] = [0.9, 0.9, 0.9, 1.0];
+    pub const LINE_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
+    pub const OUTSIDE_COLOR: [f32; 4] = [0.7, 0.7, 0.7, 1.0];
+    pub const SELECTED_COLOR: [f32; 4] = [1.0, 0.9, 0.5, 1.0];
+    //the main camera's view drawn on the minimap
+    pub const OUTLINE_COLOR: [f32; 4] = [0.8, 0.2, 0.2, 1.0];
 }
 
-pub mod empty {
-    pub const NAME: &'static str = "empty.png";
+pub mod camera {
+    use utils::Coord;
+
+    //viewports are fractions of the window from the bottom left as x0, y0, x1, y1
+    pub const MINIMAP_VIEWPORT: [Coord; 4] = [0.76, 0.02, 0.98, 0.32];
+    pub const MINIMAP_TARGET_SIZE: (u16, u16) = (320, 240);
+    //the split view sits in the right half with the main camera squeezed into the left
+    pub const SPLIT_MAIN_VIEWPORT: [Coord; 4] = [0.0, 0.0, 0.5, 1.0];
+    pub const SPLIT_VIEWPORT: [Coord; 4] = [0.5, 0.0, 1.0, 1.0];
+    pub const FULL_VIEWPORT: [Coord; 4] = [0.0, 0.0, 1.0, 1.0];
+    pub const CLEAR_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    //board images are drawn a tile no bigger than this each frame and refused past the max size on either side,
+    //which keeps the whole image under 64MB
+    pub const EXPORT_PIXELS_PER_TILE: u32 = 32;
+    pub const EXPORT_TILE_SIZE: u32 = 1024;
+    pub const EXPORT_MAX_SIZE: u32 = 4096;
+}
+
+pub mod text {
+    pub const HUD_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+    //in overlay units, the line height and room for the longest line
+    pub const HUD_SIZE: f32 = 16.0;
+    pub const HUD_WIDTH: f32 = 160.0;
+    pub const HUD_MARGIN: f32 = 8.0;
+    //the mode line is wider to fit a route waiting for its end
+    pub const MODE_WIDTH: f32 = 320.0;
+    //the console hangs from the top left, output lines above the input line
+    pub const CONSOLE_WIDTH: f32 = 480.0;
+    pub const CONSOLE_LINES: usize = 12;
+    //line height in tiles for labels on the board
+    pub const LABEL_SIZE: f32 = 0.4;
+    pub const LABEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+}
+
+pub mod gates {
+    pub const NAME: &'static str = "wires.png";
     pub const SIZE: [f32; 2] = [32.0, 32.0];
     pub const RECT: [f32; 4] = [0.0, 0.0, 32.0, 32.0];
-    pub const DEFAULT_TINT: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    pub const DEFAULT_TINT: [f32; 4] = [0.2, 0.4, 0.8, 1.0];
 }
@@ -0,0 +1,146 @@
+use specs::{self, VecStorage};
+
+use graphics::atlas::{Atlas};
+
+use utils::{Delta};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Loop,
+    PingPong,
+    Once,
+}
+
+#[derive(Debug)]
+pub struct Component {
+    name: String,
+    frames: Vec<[f32; 4]>,
+    frame_duration: Delta,
+    mode: Mode,
+    speed: Delta,
+    elapsed: Delta,
+    current: usize,
+    playing: bool,
+    started: bool,
+}
+
+impl specs::Component for Component {
+    type Storage = VecStorage<Component>;
+}
+
+impl Component {
+    pub fn new(name: &str, frames: Vec<[f32; 4]>, frame_duration: Delta, mode: Mode) -> Component {
+        Component {
+            name: name.to_string(),
+            frames: frames,
+            frame_duration: frame_duration,
+            mode: mode,
+            speed: 1.0,
+            elapsed: 0.0,
+            current: 0,
+            playing: true,
+            started: false,
+        }
+    }
+
+    //every frame in the atlas starting with prefix, in name order
+    pub fn from_atlas(atlas: &Atlas, prefix: &str, frame_duration: Delta, mode: Mode) -> Component {
+        let frames = atlas.get_sequence(prefix).iter().map(|&(_, frame)| frame.get_rect()).collect::<Vec<_>>();
+        if frames.is_empty() {
+            warn!("animation {} has no frames in the atlas", prefix);
+        }
+        Component::new(prefix, frames, frame_duration, mode)
+    }
+
+    pub fn set_speed(&mut self, speed: Delta) {
+        self.speed = speed;
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    pub fn set_playing(&mut self, playing: bool) {
+        self.playing = playing;
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.current = 0;
+        self.playing = true;
+        self.started = false;
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_speed(&self) -> Delta {
+        self.speed
+    }
+
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn get_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn get_current(&self) -> usize {
+        self.current
+    }
+
+    pub fn get_rect(&self) -> Option<[f32; 4]> {
+        self.frames.get(self.current).cloned()
+    }
+
+    //a once animation stops on its last frame
+    pub fn is_finished(&self) -> bool {
+        self.mode == Mode::Once && self.current + 1 >= self.frames.len()
+    }
+
+    //moves time forward and returns true when the shown frame changed,
+    //the first call after new or restart always does so the first frame gets shown
+    pub fn advance(&mut self, delta: Delta) -> bool {
+        let starting = !self.started && !self.frames.is_empty();
+        self.started = true;
+
+        if !self.playing || self.frames.len() < 2 || self.frame_duration <= 0.0 {
+            return starting;
+        }
+
+        self.elapsed += delta * self.speed;
+        if self.elapsed < 0.0 {
+            self.elapsed = 0.0;
+        }
+
+        let count = self.frames.len();
+        let step = (self.elapsed / self.frame_duration) as usize;
+
+        let next = match self.mode {
+            Mode::Loop => step % count,
+            Mode::PingPong => {
+                let period = count * 2 - 2;
+                let position = step % period;
+                if position < count {
+                    position
+                } else {
+                    period - position
+                }
+            },
+            Mode::Once => {
+                if step + 1 >= count {
+                    self.playing = false;
+                    count - 1
+                } else {
+                    step
+                }
+            },
+        };
+
+        let changed = next != self.current;
+        self.current = next;
+        starting || changed
+    }
+}
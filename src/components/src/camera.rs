@@ -1,10 +1,19 @@
-use nalgebra::{self, Isometry3, ToHomogeneous, OrthographicMatrix3};
+use nalgebra::{self, Isometry3, Matrix4, ToHomogeneous, OrthographicMatrix3};
 
 use specs::{self, VecStorage};
 
-use utils::{GfxCoord, Coord};
+use utils::{Delta, GfxCoord, Coord};
 
-use math::{OrthographicHelper, Point2};
+use math::{OrthographicHelper, Point2, Rect, ViewProjection};
+
+use ::render_id::Component as RenderId;
+
+//anything further out or in stops being useful on a tile board
+pub const MIN_ZOOM: GfxCoord = 0.05;
+pub const MAX_ZOOM: GfxCoord = 16.0;
+
+//how quickly an animated move closes in on its target, per second
+const MOVE_RATE: Delta = 6.0;
 
 #[derive(Debug)]
 pub struct Component {
@@ -13,7 +22,14 @@ pub struct Component {
     up: nalgebra::Vector3<GfxCoord>,
     proj: OrthographicMatrix3<GfxCoord>,
     aspect_ratio: GfxCoord,
+    ortho_helper: Option<OrthographicHelper>,
+    zoom: GfxCoord,
+    move_target: Option<(Point2, GfxCoord)>,
+    viewport: Rect,
+    render_target: Option<RenderId>,
     is_main: bool,
+    is_minimap: bool,
+    visible: bool,
     dirty: bool,
     dirty_2: bool,
 }
@@ -33,7 +49,14 @@ impl Component {
             up: up,
             proj: proj,
             aspect_ratio: aspect_ratio,
+            ortho_helper: None,
+            zoom: 1.0,
+            move_target: None,
+            viewport: Rect::new_from_coords(0.0, 0.0, 1.0, 1.0),
+            render_target: None,
             is_main: is_main,
+            is_minimap: false,
+            visible: true,
             dirty: true,
             dirty_2: true,
         }
@@ -49,7 +72,7 @@ impl Component {
         far: GfxCoord,
         is_main: bool
     ) -> Component {
-        Component::new(eye, target, up, OrthographicMatrix3::from_fov(aspect_ratio, fov, near, far), aspect_ratio, is_main)
+        Component::new_from_ortho_helper(eye, target, up, &OrthographicHelper::new(aspect_ratio, fov, near, far), is_main)
     }
 
     pub fn new_from_ortho_helper(
@@ -59,7 +82,9 @@ impl Component {
         ortho_helper: &OrthographicHelper,
         is_main: bool
     ) -> Component {
-        Component::new(eye, target, up, ortho_helper.build_matrix(), ortho_helper.get_aspect_ratio(), is_main)
+        let mut camera = Component::new(eye, target, up, ortho_helper.build_matrix(), ortho_helper.get_aspect_ratio(), is_main);
+        camera.set_proj(ortho_helper);
+        camera
     }
 
     pub fn set_offset(&mut self, offset: Point2) {
@@ -76,10 +101,152 @@ impl Component {
         self.target = target;
     }
 
+    //the helper holds the window's aspect ratio, the projection is built for the viewport's
     pub fn set_proj(&mut self, ortho_helper: &OrthographicHelper) {
+        let mut ortho_helper = ortho_helper.clone();
+        ortho_helper.set_zoom(self.zoom);
+        self.ortho_helper = Some(ortho_helper);
+        self.rebuild_proj();
+    }
+
+    fn rebuild_proj(&mut self) {
+        let mut ortho_helper = match self.ortho_helper {
+            Some(ref ortho_helper) => ortho_helper.clone(),
+            None => return,
+        };
+        let size = self.viewport.get_top_right() - self.viewport.get_bot_left();
+        let aspect_ratio = ortho_helper.get_aspect_ratio() * (size.get_x() / size.get_y()) as GfxCoord;
+        ortho_helper.set_aspect_ratio(aspect_ratio);
         self.proj = ortho_helper.build_matrix();
-        self.aspect_ratio = ortho_helper.get_aspect_ratio();
-        self.dirty = true;
+        self.aspect_ratio = aspect_ratio;
+        self.set_dirty();
+    }
+
+    pub fn set_zoom(&mut self, zoom: GfxCoord) {
+        let zoom = zoom.max(MIN_ZOOM).min(MAX_ZOOM);
+        self.zoom = zoom;
+        match self.ortho_helper {
+            Some(ref mut ortho_helper) => ortho_helper.set_zoom(zoom),
+            None => {
+                warn!("set zoom on a camera without an ortho helper");
+                return;
+            },
+        }
+        self.rebuild_proj();
+    }
+
+    //where the camera is shown as a fraction of the window, from the bottom left
+    pub fn set_viewport(&mut self, viewport: Rect) {
+        self.viewport = viewport;
+        self.rebuild_proj();
+    }
+
+    pub fn get_viewport(&self) -> &Rect {
+        &self.viewport
+    }
+
+    //cameras with a render target draw into their own texture which is then shown in the viewport,
+    //the rest draw straight into the window
+    pub fn set_render_target(&mut self, render_target: Option<RenderId>) {
+        self.render_target = render_target;
+    }
+
+    pub fn get_render_target(&self) -> Option<RenderId> {
+        self.render_target
+    }
+
+    pub fn set_minimap(&mut self, is_minimap: bool) {
+        self.is_minimap = is_minimap;
+    }
+
+    pub fn is_minimap(&self) -> bool {
+        self.is_minimap
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+        self.set_dirty();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    //screen points are normalized with (0, 0) at the top left, like the mouse location
+    pub fn contains_screen_point(&self, screen_point: &Point2) -> bool {
+        self.visible && self.viewport.check_collide_point(Point2::new(screen_point.get_x(), 1.0 - screen_point.get_y()))
+    }
+
+    pub fn get_zoom(&self) -> GfxCoord {
+        self.zoom
+    }
+
+    //zooms while keeping the world point under screen_point at the same place on screen
+    pub fn zoom_at(&mut self, zoom: GfxCoord, screen_point: Point2) {
+        let before = self.screen_to_world_point(screen_point.clone());
+        self.set_zoom(zoom);
+        let after = self.screen_to_world_point(screen_point);
+        let offset = self.get_offset() + before - after;
+        self.set_offset(offset);
+    }
+
+    //centres on rect and zooms so all of it is visible
+    pub fn zoom_to_fit(&mut self, rect: &Rect) {
+        let (offset, zoom) = self.get_fit(rect);
+        self.set_zoom(zoom);
+        self.set_offset(offset);
+    }
+
+    //the offset and zoom that would frame rect
+    pub fn get_fit(&self, rect: &Rect) -> (Point2, GfxCoord) {
+        let view = self.get_view_rect();
+        let view_size = view.get_top_right() - view.get_bot_left();
+        let size = rect.get_top_right() - rect.get_bot_left();
+        let fit = (view_size.get_x() / size.get_x().max(1.0)).min(view_size.get_y() / size.get_y().max(1.0));
+        (
+            (rect.get_bot_left() + rect.get_top_right()) / 2.0,
+            self.zoom * fit as GfxCoord
+        )
+    }
+
+    //starts an eased move, step_move carries it out a frame at a time
+    pub fn move_to(&mut self, offset: Point2, zoom: GfxCoord) {
+        self.move_target = Some((offset, zoom.max(MIN_ZOOM).min(MAX_ZOOM)));
+    }
+
+    pub fn stop_move(&mut self) {
+        self.move_target = None;
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.move_target.is_some()
+    }
+
+    pub fn step_move(&mut self, delta_time: Delta) {
+        let (target, target_zoom) = match self.move_target {
+            Some((ref target, target_zoom)) => (target.clone(), target_zoom),
+            None => return,
+        };
+
+        //the same fraction of the remaining distance every second whatever the frame rate
+        let blend = 1.0 - (-delta_time * MOVE_RATE).exp();
+        let offset = self.get_offset();
+        let next = offset.clone() + (target.clone() - offset) * blend;
+        let next_zoom = self.zoom * (target_zoom / self.zoom).powf(blend as GfxCoord);
+
+        //close enough once the rest of the move is under a hundredth of a tile and a thousandth of the zoom
+        if (target.clone() - next.clone()).length() < 0.01 && (next_zoom / target_zoom - 1.0).abs() < 0.001 {
+            self.set_zoom(target_zoom);
+            self.set_offset(target);
+            self.move_target = None;
+        } else {
+            self.set_zoom(next_zoom);
+            self.set_offset(next);
+        }
+    }
+
+    pub fn get_aspect_ratio(&self) -> GfxCoord {
+        self.aspect_ratio
     }
 
     pub fn get_offset(&self) -> Point2 {
@@ -91,22 +258,65 @@ impl Component {
     }
 
     pub fn get_proj(&self) -> [[GfxCoord; 4]; 4] {
-        *self.proj.as_matrix().as_ref()
+        *self.make_proj_matrix(self.render_target.is_none()).as_ref()
+    }
+
+    //maps normalized device coordinates back into the world, used by full screen passes
+    pub fn get_inverse_view_proj(&self) -> [[GfxCoord; 4]; 4] {
+        *self.make_view_projection(self.render_target.is_none()).get_inverse().as_ref()
+    }
+
+    //the world space rectangle this camera can currently see
+    pub fn get_view_rect(&self) -> Rect {
+        let view_projection = self.make_view_projection(true);
+        let (min, max) = (self.viewport.get_bot_left(), self.viewport.get_top_right());
+        let a = view_projection.unproject(Point2::new(min.get_x(), 1.0 - min.get_y()));
+        let b = view_projection.unproject(Point2::new(max.get_x(), 1.0 - max.get_y()));
+        Rect::new_from_coords(
+            a.get_x().min(b.get_x()),
+            a.get_y().min(b.get_y()),
+            a.get_x().max(b.get_x()),
+            a.get_y().max(b.get_y())
+        )
     }
 
     pub fn is_main(&self) -> bool {
         self.is_main
     }
 
+    //screen points are normalized over the whole window with (0, 0) at the top left, like the mouse location
     pub fn screen_to_world_point(&self, screen_point: Point2) -> Point2 {
-        let view_depth = self.proj.zfar() - self.proj.znear();
+        self.make_view_projection(true).unproject(screen_point)
+    }
+
+    pub fn world_to_screen_point(&self, world_point: Point2) -> Point2 {
+        self.make_view_projection(true).project(world_point)
+    }
 
-        let world_point = Point2::new(
-            (((screen_point.get_x() * 2.0) - 1.0) * view_depth as Coord) * 4.0 / 5.0 + self.get_offset().get_x(),
-            (((1.0 - screen_point.get_y()) * 2.0 - 1.0) * view_depth as Coord / self.aspect_ratio as Coord) * 4.0 / 5.0 + self.get_offset().get_y()
-        );
+    //in the window the projection is squeezed into the viewport, in a render target it fills the target
+    fn make_proj_matrix(&self, in_window: bool) -> Matrix4<GfxCoord> {
+        let proj = *self.proj.as_matrix();
+        if !in_window {
+            return proj;
+        }
 
-        world_point
+        let (min, max) = (self.viewport.get_bot_left(), self.viewport.get_top_right());
+        let (scale_x, scale_y) = ((max.get_x() - min.get_x()) as GfxCoord, (max.get_y() - min.get_y()) as GfxCoord);
+        let (move_x, move_y) = ((min.get_x() + max.get_x() - 1.0) as GfxCoord, (min.get_y() + max.get_y() - 1.0) as GfxCoord);
+        Matrix4::new(
+            scale_x, 0.0, 0.0, move_x,
+            0.0, scale_y, 0.0, move_y,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ) * proj
+    }
+
+    fn make_view_projection(&self, in_window: bool) -> ViewProjection {
+        let view = Isometry3::look_at_rh(&self.eye, &self.target, &self.up).to_homogeneous();
+        match ViewProjection::new(self.make_proj_matrix(in_window) * view) {
+            Some(view_projection) => view_projection,
+            None => panic!("camera view projection matrix is not invertible"),
+        }
     }
 
     fn set_dirty(&mut self) {
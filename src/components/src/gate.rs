@@ -0,0 +1,165 @@
+use specs::{self, VecStorage};
+
+//*************************************************************************************************
+
+use math::{Point3I};
+
+use ::non_components::link::Link;
+
+//*************************************************************************************************
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Buf,
+    Not,
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+}
+
+impl Kind {
+    pub fn all() -> &'static [Kind] {
+        static ALL: [Kind; 8] = [Kind::Buf, Kind::Not, Kind::And, Kind::Or, Kind::Xor, Kind::Nand, Kind::Nor, Kind::Xnor];
+        &ALL
+    }
+
+    pub fn from_name(name: &str) -> Option<Kind> {
+        match name {
+            "buf" => Some(Kind::Buf),
+            "not" => Some(Kind::Not),
+            "and" => Some(Kind::And),
+            "or" => Some(Kind::Or),
+            "xor" => Some(Kind::Xor),
+            "nand" => Some(Kind::Nand),
+            "nor" => Some(Kind::Nor),
+            "xnor" => Some(Kind::Xnor),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            Kind::Buf => "buf",
+            Kind::Not => "not",
+            Kind::And => "and",
+            Kind::Or => "or",
+            Kind::Xor => "xor",
+            Kind::Nand => "nand",
+            Kind::Nor => "nor",
+            Kind::Xnor => "xnor",
+        }
+    }
+
+    pub fn is_unary(&self) -> bool {
+        match *self {
+            Kind::Buf | Kind::Not => true,
+            _ => false,
+        }
+    }
+
+    pub fn evaluate(&self, inputs: &[u8]) -> u8 {
+        let ones = inputs.iter().filter(|&&value| value != 0).count();
+        let all = ones == inputs.len();
+        let any = ones > 0;
+        let odd = ones % 2 == 1;
+        let result = match *self {
+            Kind::Buf => any,
+            Kind::Not => !any,
+            Kind::And => all,
+            Kind::Or => any,
+            Kind::Xor => odd,
+            Kind::Nand => !all,
+            Kind::Nor => !any,
+            Kind::Xnor => !odd,
+        };
+        if result {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+//a gate drives every cell in outputs, more than one when its signal fans out
+#[derive(Debug)]
+pub struct Component {
+    kind: Kind,
+    inputs: Vec<Link>,
+    outputs: Vec<Link>,
+    me: Link,
+    value: u8,
+    dirty: bool,
+}
+
+impl Component {
+    pub fn new(kind: Kind, inputs: Vec<Link>, outputs: Vec<Link>, me: Link) -> Component {
+        Component {
+            kind: kind,
+            inputs: inputs,
+            outputs: outputs,
+            me: me,
+            value: 0,
+            dirty: true,
+        }
+    }
+
+    pub fn new_from_points(kind: Kind, inputs: Vec<Point3I>, outputs: Vec<Point3I>, me: Point3I) -> Component {
+        Component::new(
+            kind,
+            inputs.into_iter().map(|input| Link::new(input)).collect(),
+            outputs.into_iter().map(|output| Link::new(output)).collect(),
+            Link::new(me)
+        )
+    }
+
+    pub fn get_mut_inputs(&mut self) -> &mut Vec<Link> {
+        &mut self.inputs
+    }
+
+    pub fn get_mut_outputs(&mut self) -> &mut Vec<Link> {
+        &mut self.outputs
+    }
+
+    pub fn get_mut_me(&mut self) -> &mut Link {
+        &mut self.me
+    }
+
+    pub fn get_mut_value(&mut self) -> &mut u8 {
+        &mut self.value
+    }
+
+    pub fn get_mut_dirty(&mut self) -> &mut bool {
+        &mut self.dirty
+    }
+
+    pub fn get_kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn get_inputs(&self) -> &[Link] {
+        self.inputs.as_slice()
+    }
+
+    pub fn get_outputs(&self) -> &[Link] {
+        self.outputs.as_slice()
+    }
+
+    pub fn get_me(&self) -> &Link {
+        &self.me
+    }
+
+    pub fn get_value(&self) -> u8 {
+        self.value
+    }
+
+    pub fn get_dirty(&self) -> bool {
+        self.dirty
+    }
+}
+
+impl specs::Component for Component {
+    type Storage = VecStorage<Component>;
+}
@@ -11,28 +11,41 @@ extern crate utils;
 extern crate math;
 extern crate art;
 
+pub mod animation;
 pub mod camera;
-pub mod clickable;
+pub mod gate;
+pub mod overlay;
 pub mod render_data;
 pub mod render_id;
+pub mod text;
 pub mod transform;
 pub mod wire_in;
 pub mod wire_out;
 pub mod wire;
 
+pub use self::animation::Component as Animation;
+pub use self::animation::Mode as AnimationMode;
 pub use self::camera::Component as Camera;
-pub use self::clickable::Component as Clickable;
+pub use self::gate::Component as Gate;
+pub use self::gate::Kind as GateKind;
+pub use self::overlay::Component as Overlay;
+pub use self::overlay::Anchor as OverlayAnchor;
 pub use self::render_data::Component as RenderData;
 pub use self::render_id::Component as RenderId;
+pub use self::text::Component as Text;
 pub use self::transform::Component as Transform;
 pub use self::wire_in::Component as WireIn;
 pub use self::wire_out::Component as WireOut;
 pub use self::wire::Component as Wire;
 
 pub mod non_components {
+    pub mod console;
     pub mod link;
     pub mod map;
+    pub mod selection;
 
+    pub use self::console::Console;
     pub use self::link::Link;
     pub use self::map::Map;
+    pub use self::selection::Selection;
 }
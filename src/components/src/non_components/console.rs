@@ -0,0 +1,50 @@
+use std::collections::{VecDeque};
+
+//*************************************************************************************************
+
+const MAX_LINES: usize = 64;
+
+//what the console shows, written by the console system and read by whatever draws it
+#[derive(Debug)]
+pub struct Console {
+    open: bool,
+    input: String,
+    lines: VecDeque<String>,
+}
+
+impl Console {
+    pub fn new() -> Console {
+        Console {
+            open: false,
+            input: String::new(),
+            lines: VecDeque::new(),
+        }
+    }
+
+    pub fn push_line(&mut self, line: String) {
+        self.lines.push_back(line);
+        while self.lines.len() > MAX_LINES {
+            self.lines.pop_front();
+        }
+    }
+
+    pub fn get_mut_open(&mut self) -> &mut bool {
+        &mut self.open
+    }
+
+    pub fn get_mut_input(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    pub fn get_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_input(&self) -> &str {
+        self.input.as_str()
+    }
+
+    pub fn get_lines(&self) -> &VecDeque<String> {
+        &self.lines
+    }
+}
@@ -16,6 +16,15 @@ impl Link {
         }
     }
 
+    //a pin left unconnected on purpose, it never resolves so nothing placed later is wired to it
+    pub fn new_floating() -> Link {
+        Link::new(get_floating_cell())
+    }
+
+    pub fn is_floating(&self) -> bool {
+        self.slow == get_floating_cell()
+    }
+
     pub fn get_mut_slow(&mut self) -> &mut Point3I {
         &mut self.slow
     }
@@ -32,3 +41,8 @@ impl Link {
         self.fast.as_ref()
     }
 }
+
+//the slow location of a floating link, far outside anything a board or level can reach
+pub fn get_floating_cell() -> Point3I {
+    Point3I::new(i32::min_value(), i32::min_value(), i32::min_value())
+}
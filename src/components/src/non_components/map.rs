@@ -4,7 +4,7 @@ use std::collections::{HashMap};
 
 //*************************************************************************************************
 
-use math::{Point3I};
+use math::{Point2I, Point3I};
 
 //*************************************************************************************************
 
@@ -27,4 +27,20 @@ impl Map {
     pub fn get_map(&self) -> &HashMap<Point3I, Entity> {
         &self.map
     }
+
+    //the lowest and highest occupied cells over every layer, none when the board is empty
+    pub fn get_bounds(&self) -> Option<(Point2I, Point2I)> {
+        let mut bounds: Option<(Point2I, Point2I)> = None;
+        for location in self.map.keys() {
+            let (x, y) = (location.get_x(), location.get_y());
+            bounds = Some(match bounds {
+                Some((min, max)) => (
+                    Point2I::new(min.get_x().min(x), min.get_y().min(y)),
+                    Point2I::new(max.get_x().max(x), max.get_y().max(y))
+                ),
+                None => (Point2I::new(x, y), Point2I::new(x, y)),
+            });
+        }
+        bounds
+    }
 }
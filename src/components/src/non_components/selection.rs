@@ -0,0 +1,45 @@
+use math::{Point2I};
+
+use utils::{CoordI};
+
+//the grid cell last picked with a left click, with the layer edits go on and a route waiting for its end
+#[derive(Debug)]
+pub struct Selection {
+    cell: Option<Point2I>,
+    layer: CoordI,
+    route_start: Option<Point2I>,
+}
+
+impl Selection {
+    pub fn new() -> Selection {
+        Selection {
+            cell: None,
+            layer: 0,
+            route_start: None,
+        }
+    }
+
+    pub fn set_cell(&mut self, cell: Option<Point2I>) {
+        self.cell = cell;
+    }
+
+    pub fn set_layer(&mut self, layer: CoordI) {
+        self.layer = layer;
+    }
+
+    pub fn set_route_start(&mut self, route_start: Option<Point2I>) {
+        self.route_start = route_start;
+    }
+
+    pub fn get_cell(&self) -> Option<&Point2I> {
+        self.cell.as_ref()
+    }
+
+    pub fn get_layer(&self) -> CoordI {
+        self.layer
+    }
+
+    pub fn get_route_start(&self) -> Option<&Point2I> {
+        self.route_start.as_ref()
+    }
+}
@@ -0,0 +1,115 @@
+use specs::{self, VecStorage};
+
+use math::{Point2};
+
+use utils::{Coord};
+
+//overlay units scale with the window so the layout looks the same at any resolution, the height is always this many units
+pub const REFERENCE_HEIGHT: Coord = 480.0;
+
+//the point of the window the element is placed relative to, the element's matching corner or edge sits on it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    BottomLeft,
+    Bottom,
+    BottomRight,
+    Left,
+    Center,
+    Right,
+    TopLeft,
+    Top,
+    TopRight,
+}
+
+impl Anchor {
+    //0 for left or bottom, 0.5 for centre and 1 for right or top
+    fn get_fraction(&self) -> (Coord, Coord) {
+        match *self {
+            Anchor::BottomLeft => (0.0, 0.0),
+            Anchor::Bottom => (0.5, 0.0),
+            Anchor::BottomRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::TopLeft => (0.0, 1.0),
+            Anchor::Top => (0.5, 1.0),
+            Anchor::TopRight => (1.0, 1.0),
+        }
+    }
+}
+
+//draws the entity in the screen space overlay pass instead of through the camera,
+//control keeps its transform in window pixels and gives its clicks priority over the world
+#[derive(Debug)]
+pub struct Component {
+    anchor: Anchor,
+    offset: Point2,
+    size: Point2,
+}
+
+impl specs::Component for Component {
+    type Storage = VecStorage<Component>;
+}
+
+impl Component {
+    //offset moves away from the anchor towards the centre, both offset and size are in overlay units
+    pub fn new(anchor: Anchor, offset: Point2, size: Point2) -> Component {
+        Component {
+            anchor: anchor,
+            offset: offset,
+            size: size,
+        }
+    }
+
+    pub fn set_offset(&mut self, offset: Point2) {
+        self.offset = offset;
+    }
+
+    pub fn set_size(&mut self, size: Point2) {
+        self.size = size;
+    }
+
+    pub fn get_anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    pub fn get_offset(&self) -> Point2 {
+        self.offset.clone()
+    }
+
+    pub fn get_size(&self) -> Point2 {
+        self.size.clone()
+    }
+
+    pub fn get_units_to_pixels(screen_resolution: &Point2) -> Coord {
+        screen_resolution.get_y() / REFERENCE_HEIGHT
+    }
+
+    //bottom left corner and size in pixels from the bottom left of the window
+    pub fn get_screen_rect(&self, screen_resolution: &Point2) -> (Point2, Point2) {
+        let scale = Component::get_units_to_pixels(screen_resolution);
+        let (fraction_x, fraction_y) = self.anchor.get_fraction();
+
+        let (width, height) = (self.size.get_x() * scale, self.size.get_y() * scale);
+        //offsets point inwards so they flip on the right and top
+        let inwards = |fraction: Coord| if fraction > 0.5 { -1.0 } else { 1.0 };
+        let (offset_x, offset_y) = (
+            self.offset.get_x() * scale * inwards(fraction_x),
+            self.offset.get_y() * scale * inwards(fraction_y)
+        );
+
+        (
+            Point2::new(
+                screen_resolution.get_x() * fraction_x - width * fraction_x + offset_x,
+                screen_resolution.get_y() * fraction_y - height * fraction_y + offset_y
+            ),
+            Point2::new(width, height)
+        )
+    }
+
+    pub fn contains(&self, screen_resolution: &Point2, pixel: &Point2) -> bool {
+        let (min, size) = self.get_screen_rect(screen_resolution);
+        pixel.get_x() >= min.get_x() && pixel.get_x() < min.get_x() + size.get_x() &&
+        pixel.get_y() >= min.get_y() && pixel.get_y() < min.get_y() + size.get_y()
+    }
+}
@@ -0,0 +1,47 @@
+use specs::{self, VecStorage};
+
+//drawn with the entity's transform, whose scale is the height of one line,
+//in tiles for world text or overlay units for text with an overlay
+#[derive(Debug)]
+pub struct Component {
+    text: String,
+    color: [f32; 4],
+    layer: u8,
+}
+
+impl specs::Component for Component {
+    type Storage = VecStorage<Component>;
+}
+
+impl Component {
+    pub fn new(text: &str, color: [f32; 4], layer: u8) -> Component {
+        Component {
+            text: text.to_string(),
+            color: color,
+            layer: layer,
+        }
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text.clear();
+            self.text.push_str(text);
+        }
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]) {
+        self.color = color;
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn get_color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    pub fn get_layer(&self) -> u8 {
+        self.layer
+    }
+}
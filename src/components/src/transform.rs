@@ -39,6 +39,11 @@ impl Component {
         self.isometry.translation.y += pos_delta.get_y() as GfxCoord;
     }
 
+    pub fn set_scale(&mut self, scale: Point2) {
+        self.scale.x = scale.get_x() as GfxCoord;
+        self.scale.y = scale.get_y() as GfxCoord;
+    }
+
     pub fn get_model(&self) -> [[GfxCoord; 4]; 4] {
         let mut refer = *self.isometry.to_homogeneous().as_ref();
         refer[0][0] *= self.scale.x;
@@ -51,6 +56,10 @@ impl Component {
         Point2::new(self.isometry.translation.x as f64, self.isometry.translation.y as f64)
     }
 
+    pub fn get_scale(&self) -> Point2 {
+        Point2::new(self.scale.x as Coord, self.scale.y as Coord)
+    }
+
     pub fn get_gui_offset(&self) -> Point2 {
         let translation = self.isometry.translation();
         Point2::new(-translation.x as f64, -translation.y as f64)
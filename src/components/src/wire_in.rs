@@ -4,34 +4,47 @@ use ::non_components::link::Link;
 
 use math::Point3I;
 
+//an input drives every cell in outputs, more than one when its signal fans out
 #[derive(Debug)]
 pub struct Component {
-    output: Link,
+    outputs: Vec<Link>,
     me: Link,
+    name: Option<String>,
     value: u8,
     dirty: bool,
 }
 
 impl Component {
-    pub fn new(output: Link, me: Link) -> Component {
+    pub fn new(outputs: Vec<Link>, me: Link) -> Component {
         Component {
-            output: output,
+            outputs: outputs,
             me: me,
+            name: None,
             value: 0,
             dirty: true,
         }
     }
 
-    pub fn new_from_points(output: Point3I, me: Point3I) -> Component {
-        Component::new(Link::new(output), Link::new(me))
+    pub fn new_from_points(outputs: Vec<Point3I>, me: Point3I) -> Component {
+        Component::new(outputs.into_iter().map(|output| Link::new(output)).collect(), Link::new(me))
+    }
+
+    pub fn new_named(outputs: Vec<Point3I>, me: Point3I, name: &str) -> Component {
+        let mut component = Component::new_from_points(outputs, me);
+        component.set_name(name);
+        component
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(name.to_string());
     }
 
     pub fn get_mut_me(&mut self) -> &mut Link {
         &mut self.me
     }
 
-    pub fn get_mut_output(&mut self) -> &mut Link {
-        &mut self.output
+    pub fn get_mut_outputs(&mut self) -> &mut Vec<Link> {
+        &mut self.outputs
     }
 
     pub fn get_mut_value(&mut self) -> &mut u8 {
@@ -46,8 +59,12 @@ impl Component {
         &self.me
     }
 
-    pub fn get_output(&self) -> &Link {
-        &self.output
+    pub fn get_outputs(&self) -> &[Link] {
+        self.outputs.as_slice()
+    }
+
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| name.as_str())
     }
 
     pub fn get_value(&self) -> u8 {
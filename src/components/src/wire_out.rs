@@ -12,6 +12,7 @@ use ::non_components::link::Link;
 pub struct Component {
     input: Link,
     me: Link,
+    name: Option<String>,
     value: u8,
     dirty: bool,
 }
@@ -21,6 +22,7 @@ impl Component {
         Component {
             input: input,
             me: me,
+            name: None,
             value: 0,
             dirty: true,
         }
@@ -30,6 +32,16 @@ impl Component {
         Component::new(Link::new(input), Link::new(me))
     }
 
+    pub fn new_named(input: Point3I, me: Point3I, name: &str) -> Component {
+        let mut component = Component::new_from_points(input, me);
+        component.set_name(name);
+        component
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(name.to_string());
+    }
+
     pub fn get_mut_input(&mut self) -> &mut Link {
         &mut self.input
     }
@@ -54,6 +66,10 @@ impl Component {
         &self.me
     }
 
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| name.as_str())
+    }
+
     pub fn get_value(&self) -> u8 {
         self.value
     }
@@ -0,0 +1,122 @@
+extern crate components;
+extern crate graphics;
+
+use components::{Animation, AnimationMode};
+
+use graphics::atlas::{Atlas, Frame};
+
+//*************************************************************************************************
+
+fn make_frames(count: usize) -> Vec<[f32; 4]> {
+    (0..count).map(|index| [index as f32 * 10.0, 0.0, 10.0, 10.0]).collect()
+}
+
+//the frame shown after each of a run of equal steps
+fn make_run(animation: &mut Animation, delta: f64, steps: usize) -> Vec<usize> {
+    (0..steps).map(|_| {
+        animation.advance(delta);
+        animation.get_current()
+    }).collect()
+}
+
+#[test]
+fn first_advance_shows_the_first_frame() {
+    let mut animation = Animation::new("walk", make_frames(3), 1.0, AnimationMode::Loop);
+
+    assert!(animation.advance(0.0));
+    assert_eq!(animation.get_rect(), Some([0.0, 0.0, 10.0, 10.0]));
+    assert!(!animation.advance(0.0));
+
+    animation.advance(1.5);
+    animation.restart();
+    assert!(animation.advance(0.0));
+    assert_eq!(animation.get_current(), 0);
+}
+
+#[test]
+fn single_and_stopped_animations_still_show_their_first_frame() {
+    let mut single = Animation::new("still", make_frames(1), 1.0, AnimationMode::Loop);
+    assert!(single.advance(5.0));
+    assert!(!single.advance(5.0));
+
+    let mut stopped = Animation::new("walk", make_frames(3), 1.0, AnimationMode::Loop);
+    stopped.set_playing(false);
+    assert!(stopped.advance(5.0));
+    assert_eq!(stopped.get_current(), 0);
+
+    let mut empty = Animation::new("none", vec!(), 1.0, AnimationMode::Loop);
+    assert!(!empty.advance(1.0));
+    assert_eq!(empty.get_rect(), None);
+}
+
+#[test]
+fn loop_wraps_back_to_the_start() {
+    let mut animation = Animation::new("walk", make_frames(3), 1.0, AnimationMode::Loop);
+
+    assert_eq!(make_run(&mut animation, 1.0, 7), vec!(1, 2, 0, 1, 2, 0, 1));
+}
+
+#[test]
+fn ping_pong_turns_at_both_ends() {
+    let mut animation = Animation::new("walk", make_frames(3), 1.0, AnimationMode::PingPong);
+
+    assert_eq!(make_run(&mut animation, 1.0, 6), vec!(1, 2, 1, 0, 1, 2));
+}
+
+#[test]
+fn once_clamps_on_the_last_frame() {
+    let mut animation = Animation::new("door", make_frames(3), 1.0, AnimationMode::Once);
+
+    assert!(!animation.is_finished());
+    assert_eq!(make_run(&mut animation, 1.0, 2), vec!(1, 2));
+    assert!(animation.is_finished());
+    assert!(!animation.get_playing());
+
+    assert!(!animation.advance(10.0));
+    assert_eq!(animation.get_current(), 2);
+}
+
+#[test]
+fn one_long_step_lands_where_many_short_ones_do() {
+    let mut animation = Animation::new("walk", make_frames(4), 0.25, AnimationMode::Loop);
+    animation.advance(0.0);
+
+    assert!(animation.advance(1.6));
+    assert_eq!(animation.get_current(), 2);
+}
+
+#[test]
+fn speed_scales_time_and_never_runs_before_the_start() {
+    let mut animation = Animation::new("walk", make_frames(4), 1.0, AnimationMode::Loop);
+    animation.set_speed(2.0);
+    assert_eq!(make_run(&mut animation, 1.0, 2), vec!(2, 0));
+
+    animation.set_speed(-1.0);
+    animation.advance(100.0);
+    assert_eq!(animation.get_current(), 0);
+}
+
+#[test]
+fn zero_length_frames_stay_on_the_first_frame() {
+    let mut animation = Animation::new("flash", make_frames(3), 0.0, AnimationMode::Loop);
+
+    assert!(animation.advance(1.0));
+    assert!(!animation.advance(1.0));
+    assert_eq!(animation.get_current(), 0);
+}
+
+#[test]
+fn from_atlas_keeps_the_sequence_name_and_order() {
+    let mut atlas = Atlas::new();
+    atlas.add_frame("p1_walk02", Frame::new([72.0, 0.0, 72.0, 97.0]));
+    atlas.add_frame("p1_walk01", Frame::new([0.0, 0.0, 72.0, 97.0]));
+    atlas.add_frame("p1_front", Frame::new([0.0, 196.0, 66.0, 92.0]));
+
+    let mut animation = Animation::from_atlas(&atlas, "p1_walk", 0.1, AnimationMode::Loop);
+    assert_eq!(animation.get_name(), "p1_walk");
+    assert_eq!(animation.get_rect(), Some([0.0, 0.0, 72.0, 97.0]));
+
+    animation.advance(0.0);
+    animation.advance(0.1);
+    assert_eq!(animation.get_rect(), Some([72.0, 0.0, 72.0, 97.0]));
+}
@@ -0,0 +1,270 @@
+use std::fs::{File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path};
+
+use glutin::{VirtualKeyCode, MouseButton};
+
+//*************************************************************************************************
+
+use sys::control;
+
+//*************************************************************************************************
+
+//everything an input can be bound to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    PanRight,
+    PanLeft,
+    PanUp,
+    PanDown,
+    Control(control::Action),
+    ZoomFit,
+    ToggleMinimap,
+    ToggleSplit,
+    ToggleConsole,
+    Screenshot,
+    Verify,
+    ExportVerilog,
+    Quit,
+}
+
+const ACTIONS: &'static [(&'static str, Action)] = &[
+    ("pan_right", Action::PanRight),
+    ("pan_left", Action::PanLeft),
+    ("pan_up", Action::PanUp),
+    ("pan_down", Action::PanDown),
+    ("zoom_fit", Action::ZoomFit),
+    ("toggle_minimap", Action::ToggleMinimap),
+    ("toggle_split", Action::ToggleSplit),
+    ("toggle_console", Action::ToggleConsole),
+    ("screenshot", Action::Screenshot),
+    ("verify", Action::Verify),
+    ("export_verilog", Action::ExportVerilog),
+    ("quit", Action::Quit),
+];
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        match ACTIONS.iter().find(|&&(action_name, _)| action_name == name) {
+            Some(&(_, action)) => Some(action),
+            None => control::Action::from_name(name).map(Action::Control),
+        }
+    }
+}
+
+//scan codes stay on the same physical key whatever the keyboard layout
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Input {
+    Key(VirtualKeyCode),
+    Scan(u8),
+    Mouse(MouseButton),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+const KEYS: &'static [(&'static str, VirtualKeyCode)] = &[
+    ("A", VirtualKeyCode::A), ("B", VirtualKeyCode::B), ("C", VirtualKeyCode::C), ("D", VirtualKeyCode::D),
+    ("E", VirtualKeyCode::E), ("F", VirtualKeyCode::F), ("G", VirtualKeyCode::G), ("H", VirtualKeyCode::H),
+    ("I", VirtualKeyCode::I), ("J", VirtualKeyCode::J), ("K", VirtualKeyCode::K), ("L", VirtualKeyCode::L),
+    ("M", VirtualKeyCode::M), ("N", VirtualKeyCode::N), ("O", VirtualKeyCode::O), ("P", VirtualKeyCode::P),
+    ("Q", VirtualKeyCode::Q), ("R", VirtualKeyCode::R), ("S", VirtualKeyCode::S), ("T", VirtualKeyCode::T),
+    ("U", VirtualKeyCode::U), ("V", VirtualKeyCode::V), ("W", VirtualKeyCode::W), ("X", VirtualKeyCode::X),
+    ("Y", VirtualKeyCode::Y), ("Z", VirtualKeyCode::Z),
+    ("0", VirtualKeyCode::Key0), ("1", VirtualKeyCode::Key1), ("2", VirtualKeyCode::Key2), ("3", VirtualKeyCode::Key3),
+    ("4", VirtualKeyCode::Key4), ("5", VirtualKeyCode::Key5), ("6", VirtualKeyCode::Key6), ("7", VirtualKeyCode::Key7),
+    ("8", VirtualKeyCode::Key8), ("9", VirtualKeyCode::Key9),
+    ("F1", VirtualKeyCode::F1), ("F2", VirtualKeyCode::F2), ("F3", VirtualKeyCode::F3), ("F4", VirtualKeyCode::F4),
+    ("F5", VirtualKeyCode::F5), ("F6", VirtualKeyCode::F6), ("F7", VirtualKeyCode::F7), ("F8", VirtualKeyCode::F8),
+    ("F9", VirtualKeyCode::F9), ("F10", VirtualKeyCode::F10), ("F11", VirtualKeyCode::F11), ("F12", VirtualKeyCode::F12),
+    ("Left", VirtualKeyCode::Left), ("Right", VirtualKeyCode::Right), ("Up", VirtualKeyCode::Up), ("Down", VirtualKeyCode::Down),
+    ("Escape", VirtualKeyCode::Escape), ("Space", VirtualKeyCode::Space), ("Return", VirtualKeyCode::Return),
+    ("Tab", VirtualKeyCode::Tab), ("Back", VirtualKeyCode::Back), ("Delete", VirtualKeyCode::Delete),
+    ("Insert", VirtualKeyCode::Insert), ("Home", VirtualKeyCode::Home), ("End", VirtualKeyCode::End),
+    ("PageUp", VirtualKeyCode::PageUp), ("PageDown", VirtualKeyCode::PageDown),
+    ("Grave", VirtualKeyCode::Grave), ("Minus", VirtualKeyCode::Minus), ("Equals", VirtualKeyCode::Equals),
+    ("LBracket", VirtualKeyCode::LBracket), ("RBracket", VirtualKeyCode::RBracket),
+    ("Semicolon", VirtualKeyCode::Semicolon), ("Apostrophe", VirtualKeyCode::Apostrophe),
+    ("Comma", VirtualKeyCode::Comma), ("Period", VirtualKeyCode::Period),
+    ("Slash", VirtualKeyCode::Slash), ("Backslash", VirtualKeyCode::Backslash),
+    ("Add", VirtualKeyCode::Add), ("Subtract", VirtualKeyCode::Subtract),
+];
+
+//where a us layout prints these keys, so Scan W is the key left of E whatever is printed on it,
+//x11 sends evdev codes plus 8, windows sends set 1 codes and macos its virtual key codes
+#[cfg(target_os = "windows")]
+const SCAN_KEYS: &'static [(&'static str, u8)] = &[
+    ("W", 17), ("A", 30), ("S", 31), ("D", 32), ("Grave", 41), ("Minus", 12), ("Equals", 13),
+];
+#[cfg(target_os = "macos")]
+const SCAN_KEYS: &'static [(&'static str, u8)] = &[
+    ("W", 13), ("A", 0), ("S", 1), ("D", 2), ("Grave", 50), ("Minus", 27), ("Equals", 24),
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const SCAN_KEYS: &'static [(&'static str, u8)] = &[
+    ("W", 25), ("A", 38), ("S", 39), ("D", 40), ("Grave", 49), ("Minus", 20), ("Equals", 21),
+];
+
+//the bindings used when there is no bindings file, keys chosen for where they sit are scan codes
+//so panning stays under the left hand on azerty or dvorak, keys chosen for their letter stay key names
+const DEFAULT_BINDINGS: &'static str = "
+pan_right = Scan D
+pan_right = Right
+pan_left = Scan A
+pan_left = Left
+pan_up = Scan W
+pan_up = Up
+pan_down = Scan S
+pan_down = Down
+select = Mouse Left
+route = Mouse Right
+pan = Mouse Middle
+place = P
+erase = Delete
+rotate = R
+zoom_in = Scan Equals
+zoom_in = Add
+zoom_out = Scan Minus
+zoom_out = Subtract
+zoom_fit = Home
+toggle_minimap = M
+toggle_split = V
+undo = Ctrl+Z
+redo = Ctrl+Y
+redo = Ctrl+Shift+Z
+toggle_console = Scan Grave
+screenshot = F12
+verify = F6
+export_verilog = F5
+quit = Escape
+";
+
+//a bindings file is one binding per line as action = [ctrl+][shift+][alt+]input, where input is
+//a key name like D or Left, Scan <code or W A S D Grave Minus Equals> for a physical key,
+//or Mouse <Left|Right|Middle|number>, an action may be bound any number of times
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    binds: Vec<(Input, Modifiers, Action)>,
+}
+
+impl Bindings {
+    pub fn new() -> Bindings {
+        match Bindings::read(DEFAULT_BINDINGS.as_bytes()) {
+            Ok(bindings) => bindings,
+            Err(err) => panic!("default bindings error: {}", err),
+        }
+    }
+
+    //falls back to the defaults when the file is missing or broken
+    pub fn load(path: &Path) -> Bindings {
+        match File::open(path) {
+            Ok(file) => match Bindings::read(BufReader::new(file)) {
+                Ok(bindings) => bindings,
+                Err(err) => {
+                    error!("bindings {} error: {}, using defaults", path.display(), err);
+                    Bindings::new()
+                },
+            },
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Bindings::new(),
+            Err(err) => {
+                error!("bindings {} open error: {}, using defaults", path.display(), err);
+                Bindings::new()
+            },
+        }
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> Result<Bindings, String> {
+        let mut binds = vec!();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = try!(line.map_err(|err| format!("line {} read error: {}", index + 1, err)));
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, input) = match line.find('=') {
+                Some(split) => (line[..split].trim(), line[split + 1..].trim()),
+                None => return Err(format!("line {} has no =", index + 1)),
+            };
+
+            let action = try!(Action::from_name(name).ok_or(format!("line {} has unknown action {}", index + 1, name)));
+            let (input, modifiers) = try!(parse_input(input).map_err(|err| format!("line {}: {}", index + 1, err)));
+
+            binds.push((input, modifiers, action));
+        }
+
+        Ok(Bindings {
+            binds: binds,
+        })
+    }
+
+    //a press only matches bindings whose modifiers are exactly the ones held
+    pub fn find(&self, key: Option<VirtualKeyCode>, scan: u8, modifiers: Modifiers) -> Vec<Action> {
+        self.binds.iter()
+            .filter(|&&(input, bound, _)| bound == modifiers && match input {
+                Input::Key(bound_key) => Some(bound_key) == key,
+                Input::Scan(bound_scan) => bound_scan == scan,
+                Input::Mouse(_) => false,
+            })
+            .map(|&(_, _, action)| action)
+            .collect()
+    }
+
+    pub fn find_mouse(&self, button: MouseButton, modifiers: Modifiers) -> Vec<Action> {
+        self.binds.iter()
+            .filter(|&&(input, bound, _)| bound == modifiers && input == Input::Mouse(button))
+            .map(|&(_, _, action)| action)
+            .collect()
+    }
+}
+
+fn parse_input(text: &str) -> Result<(Input, Modifiers), String> {
+    let mut modifiers = Modifiers::default();
+    let mut parts: Vec<&str> = text.split('+').map(|part| part.trim()).collect();
+
+    let input = match parts.pop() {
+        Some(input) if !input.is_empty() => input,
+        _ => return Err(format!("{} has no input", text)),
+    };
+
+    for part in parts {
+        match part.to_lowercase().as_str() {
+            "ctrl" => modifiers.ctrl = true,
+            "shift" => modifiers.shift = true,
+            "alt" => modifiers.alt = true,
+            other => return Err(format!("unknown modifier {}", other)),
+        }
+    }
+
+    let words: Vec<&str> = input.split_whitespace().collect();
+    if words.len() > 2 {
+        return Err(format!("cannot read input {}", input));
+    }
+    let input = match (words.get(0), words.get(1)) {
+        (Some(&"Mouse"), Some(&button)) => Input::Mouse(match button {
+            "Left" => MouseButton::Left,
+            "Right" => MouseButton::Right,
+            "Middle" => MouseButton::Middle,
+            other => MouseButton::Other(try!(other.parse().map_err(|_| format!("unknown mouse button {}", other)))),
+        }),
+        (Some(&"Scan"), Some(&code)) => Input::Scan(try!(code.parse().ok().or_else(|| find_scan_code(code)).ok_or(format!("bad scan code {}", code)))),
+        (Some(&key), None) => match KEYS.iter().find(|&&(name, _)| name.to_lowercase() == key.to_lowercase()) {
+            Some(&(_, key)) => Input::Key(key),
+            None => return Err(format!("unknown key {}", key)),
+        },
+        _ => return Err(format!("cannot read input {}", input)),
+    };
+
+    Ok((input, modifiers))
+}
+
+//the scan code of the physical key a us layout prints name on
+pub fn find_scan_code(name: &str) -> Option<u8> {
+    SCAN_KEYS.iter().find(|&&(key, _)| key.to_lowercase() == name.to_lowercase()).map(|&(_, code)| code)
+}
@@ -0,0 +1,95 @@
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::path::{PathBuf};
+
+use time;
+
+//*************************************************************************************************
+
+use sys::{render, console};
+
+use graphics::software::{Canvas};
+
+use math::{Rect};
+
+use event::{DevEventHub};
+
+//*************************************************************************************************
+
+//images are drawn by the render system on the cpu and saved here once they arrive,
+//the console hears back when each one is written. nothing is read back from the gl framebuffer,
+//so a screenshot is the software reference renderer's take on the frame and can differ from the window
+//in filtering and blending by a channel or so
+pub struct Capturer {
+    pending: Vec<(Receiver<Canvas>, PathBuf)>,
+}
+
+impl Capturer {
+    pub fn new() -> Capturer {
+        Capturer {
+            pending: vec!(),
+        }
+    }
+
+    pub fn screenshot(&mut self, event_dev: &mut DevEventHub, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(make_screenshot_path);
+        let (sender, receiver) = channel();
+        event_dev.send_to_render(render::RecvEvent::RenderSoftware(sender));
+        self.pending.push((receiver, path));
+    }
+
+    pub fn export_board(&mut self, event_dev: &mut DevEventHub, path: PathBuf, rect: Rect, pixels_per_tile: u32) {
+        let (width, height) = match console::make_export_size(&rect, pixels_per_tile) {
+            Some(size) => size,
+            None => {
+                let err = format!("{} is too big to export at {} pixels per tile", path.display(), pixels_per_tile);
+                error!("capture export error: {}", err);
+                event_dev.send_to_console(console::RecvEvent::Reply(Err(err)));
+                return;
+            },
+        };
+        let (sender, receiver) = channel();
+        event_dev.send_to_render(render::RecvEvent::RenderRegion(rect, (width, height), sender));
+        self.pending.push((receiver, path));
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    //called from the main loop, saves whatever the render system has finished
+    pub fn poll(&mut self, event_dev: &mut DevEventHub) {
+        let mut finished = vec!();
+        self.pending.retain(|&(ref receiver, ref path)| match receiver.try_recv() {
+            Ok(image) => {
+                finished.push((image, path.clone()));
+                false
+            },
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                error!("capture {} was dropped by the render system", path.display());
+                false
+            },
+        });
+
+        for (image, path) in finished {
+            let result = image.save(&path)
+                .map(|()| format!("saved {}x{} image to {}", image.get_width(), image.get_height(), path.display()))
+                .map_err(|err| format!("{} {}", path.display(), err));
+            match result {
+                Ok(ref output) => info!("{}", output),
+                Err(ref err) => error!("capture save error: {}", err),
+            }
+            event_dev.send_to_console(console::RecvEvent::Reply(result));
+        }
+    }
+}
+
+fn make_screenshot_path() -> PathBuf {
+    match time::strftime("%Y%m%d-%H%M%S", &time::now()) {
+        Ok(stamp) => PathBuf::from(format!("screenshot-{}.png", stamp)),
+        Err(err) => {
+            warn!("screenshot time format error: {}", err);
+            PathBuf::from("screenshot.png")
+        },
+    }
+}
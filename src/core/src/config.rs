@@ -0,0 +1,294 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+//*************************************************************************************************
+
+use math::{Point2};
+
+use utils::{Coord, GfxCoord};
+
+//*************************************************************************************************
+
+pub const KEYS: &'static [&'static str] = &["width", "height", "title", "vsync", "fov", "znear", "zfar", "move_speed"];
+
+pub const DEFAULT_MOVE_SPEED: Coord = 10.0;
+
+//one setting per line as key = value, lines starting with # are comments
+#[derive(Debug, Clone)]
+pub struct Config {
+    width: u32,
+    height: u32,
+    title: String,
+    vsync: bool,
+    fov: GfxCoord,
+    znear: GfxCoord,
+    zfar: GfxCoord,
+    move_speed: Point2,
+    //the file as it was read, written back with only the changed settings replaced so comments,
+    //unknown settings and command line overrides are left as they were
+    lines: Vec<String>,
+    //settings changed in game since the file was read, saving is skipped while there are none
+    changed: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config {
+            width: 640,
+            height: 480,
+            title: "Explore".to_string(),
+            vsync: true,
+            fov: 90.0,
+            znear: 0.0,
+            zfar: 10.0,
+            move_speed: Point2::new(DEFAULT_MOVE_SPEED, DEFAULT_MOVE_SPEED),
+            lines: vec!(),
+            changed: vec!(),
+            path: None,
+        }
+    }
+
+    //explore/config.txt in the platform's config folder
+    pub fn get_default_path() -> Option<PathBuf> {
+        let folder = if cfg!(windows) {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| env::home_dir().map(|home| home.join(".config")))
+        };
+        folder.map(|folder| folder.join("explore").join("config.txt"))
+    }
+
+    //defaults for anything the file leaves out, and only defaults when there is no file yet,
+    //a file that could not be read is never saved over so a typo does not wipe the rest of it
+    pub fn load(path: &Path) -> Config {
+        match File::open(path) {
+            Ok(file) => match Config::read(BufReader::new(file)) {
+                Ok(mut config) => {
+                    config.path = Some(path.to_path_buf());
+                    config
+                },
+                Err(err) => {
+                    error!("config {} error: {}, using defaults and not saving", path.display(), err);
+                    Config::new()
+                },
+            },
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                info!("no config at {}, using defaults", path.display());
+                let mut config = Config::new();
+                config.path = Some(path.to_path_buf());
+                config
+            },
+            Err(err) => {
+                error!("config {} open error: {}, using defaults and not saving", path.display(), err);
+                Config::new()
+            },
+        }
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> Result<Config, String> {
+        let mut config = Config::new();
+
+        let mut lines = vec!();
+        for (index, line) in reader.lines().enumerate() {
+            let line = try!(line.map_err(|err| format!("line {} read error: {}", index + 1, err)));
+            lines.push(line.clone());
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.find('=') {
+                Some(split) => (line[..split].trim(), line[split + 1..].trim()),
+                None => return Err(format!("line {} has no =", index + 1)),
+            };
+
+            //settings from a newer or older version are left out rather than failing the whole file
+            if !KEYS.iter().any(|&known| known == key) {
+                warn!("config line {} unknown setting {}, skipped", index + 1, key);
+                continue;
+            }
+
+            try!(config.apply(key, value).map_err(|err| format!("line {}: {}", index + 1, err)));
+        }
+
+        config.lines = lines;
+        Ok(config)
+    }
+
+    //a new file lists every setting, otherwise the read lines are kept and only changed settings are
+    //rewritten, with any the file did not have added at the end
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.lines.is_empty() {
+            try!(writeln!(writer, "# explore settings, written back when they change in game"));
+            for key in KEYS {
+                try!(writeln!(writer, "{} = {}", key, self.get(key).unwrap_or(String::new())));
+            }
+            return Ok(());
+        }
+
+        let mut written = vec!();
+        for line in &self.lines {
+            let trimmed = line.trim();
+            let key = match trimmed.find('=') {
+                Some(split) if !trimmed.starts_with('#') => trimmed[..split].trim(),
+                _ => "",
+            };
+
+            if self.changed.iter().any(|changed| changed == key) {
+                try!(writeln!(writer, "{} = {}", key, self.get(key).unwrap_or(String::new())));
+                written.push(key);
+            } else {
+                try!(writeln!(writer, "{}", line));
+            }
+        }
+
+        for key in &self.changed {
+            if !written.iter().any(|&done| done == key) {
+                try!(writeln!(writer, "{} = {}", key, self.get(key).unwrap_or(String::new())));
+            }
+        }
+        Ok(())
+    }
+
+    //writes to the path it was loaded from, if any, when a setting changed
+    pub fn save(&mut self) {
+        if self.changed.is_empty() {
+            return;
+        }
+
+        let path = match self.path {
+            Some(ref path) => path.clone(),
+            None => return,
+        };
+
+        if let Some(folder) = path.parent() {
+            if let Err(err) = fs::create_dir_all(folder) {
+                error!("config create folder {} error: {}", folder.display(), err);
+                return;
+            }
+        }
+
+        let mut bytes = vec!();
+        let result = self.write(&mut bytes).and_then(|()| File::create(&path)).and_then(|mut file| file.write_all(&bytes));
+        match result {
+            Ok(()) => {
+                info!("saved config to {}", path.display());
+                //the saved file is now what later saves keep
+                self.lines = String::from_utf8_lossy(&bytes).lines().map(|line| line.to_string()).collect();
+                self.changed = vec!();
+            },
+            Err(err) => error!("config save {} error: {}", path.display(), err),
+        }
+    }
+
+    //a command line setting, used this run without being saved, the file's line is written back as it was
+    pub fn set_override(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.apply(key, value)
+    }
+
+    //a setting changed in game, saved on the next save
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        try!(self.apply(key, value));
+        self.mark_changed(key);
+        Ok(())
+    }
+
+    fn mark_changed(&mut self, key: &str) {
+        if !self.changed.iter().any(|changed| changed == key) {
+            self.changed.push(key.to_string());
+        }
+    }
+
+    fn apply(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let parse_error = |kind: &str| format!("{} expects {}, got {}", key, kind, value);
+
+        match key {
+            "width" | "height" => {
+                let size: u32 = try!(value.parse().map_err(|_| parse_error("a whole number")));
+                if size == 0 {
+                    return Err(parse_error("a size above zero"));
+                }
+                if key == "width" {
+                    self.width = size;
+                } else {
+                    self.height = size;
+                }
+            },
+            "title" => self.title = value.to_string(),
+            "vsync" => self.vsync = match value {
+                "on" | "true" | "1" => true,
+                "off" | "false" | "0" => false,
+                _ => return Err(parse_error("on or off")),
+            },
+            "fov" => self.fov = try!(value.parse().map_err(|_| parse_error("a number"))),
+            "znear" => self.znear = try!(value.parse().map_err(|_| parse_error("a number"))),
+            "zfar" => self.zfar = try!(value.parse().map_err(|_| parse_error("a number"))),
+            "move_speed" => {
+                let numbers: Vec<Coord> = try!(value.split_whitespace().map(|number| number.parse()).collect::<Result<Vec<_>, _>>().map_err(|_| parse_error("one or two numbers")));
+                self.move_speed = match numbers.len() {
+                    1 => Point2::new(numbers[0], numbers[0]),
+                    2 => Point2::new(numbers[0], numbers[1]),
+                    _ => return Err(parse_error("one or two numbers")),
+                };
+            },
+            _ => return Err(format!("unknown setting {}", key)),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        Some(match key {
+            "width" => self.width.to_string(),
+            "height" => self.height.to_string(),
+            "title" => self.title.clone(),
+            "vsync" => if self.vsync { "on".to_string() } else { "off".to_string() },
+            "fov" => self.fov.to_string(),
+            "znear" => self.znear.to_string(),
+            "zfar" => self.zfar.to_string(),
+            "move_speed" => format!("{} {}", self.move_speed.get_x(), self.move_speed.get_y()),
+            _ => return None,
+        })
+    }
+
+    //the window size is remembered whenever it is resized
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 && (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.mark_changed("width");
+            self.mark_changed("height");
+        }
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn get_title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn get_vsync(&self) -> bool {
+        self.vsync
+    }
+
+    pub fn get_fov(&self) -> GfxCoord {
+        self.fov
+    }
+
+    pub fn get_znear(&self) -> GfxCoord {
+        self.znear
+    }
+
+    pub fn get_zfar(&self) -> GfxCoord {
+        self.zfar
+    }
+
+    pub fn get_move_speed(&self) -> Point2 {
+        self.move_speed.clone()
+    }
+}
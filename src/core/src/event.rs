@@ -1,12 +1,20 @@
 use std::sync::mpsc::{Sender, Receiver, channel, TryRecvError};
-use glutin::Event;
+use std::path::{PathBuf};
+use glutin::{Event, VirtualKeyCode, MouseButton};
 
-use sys::{control, render, mapper};
+use sys::{console, control, render, mapper};
 use ::game;
+use ::bindings::{Bindings, Action, Modifiers};
+
+//touchpads scroll in pixels, this many make up one wheel line
+const SCROLL_LINE_PIXELS: f32 = 20.0;
 
 #[derive(Debug)]
 pub struct GameEventHub {
     pub control_channel: Option<control::Channel>,
+    pub control_sender: Option<Sender<control::RecvEvent>>,
+    pub console_channel: Option<console::Channel>,
+    pub console_sender: Option<Sender<console::RecvEvent>>,
     pub render_channel: Option<render::Channel>,
     pub game_channel: Option<game::Channel>,
     pub mapper_channel_mapper: Option<mapper::channel::Mapper>,
@@ -16,6 +24,9 @@ pub struct GameEventHub {
 impl GameEventHub {
     pub fn new(
         control_channel: control::Channel,
+        control_sender: Sender<control::RecvEvent>,
+        console_channel: console::Channel,
+        console_sender: Sender<console::RecvEvent>,
         render_channel: render::Channel,
         game_channel: game::Channel,
         mapper_channel_mapper: mapper::channel::Mapper,
@@ -23,6 +34,9 @@ impl GameEventHub {
     ) -> GameEventHub {
         GameEventHub {
             control_channel: Some(control_channel),
+            control_sender: Some(control_sender),
+            console_channel: Some(console_channel),
+            console_sender: Some(console_sender),
             render_channel: Some(render_channel),
             game_channel: Some(game_channel),
             mapper_channel_mapper: Some(mapper_channel_mapper),
@@ -35,16 +49,28 @@ impl GameEventHub {
 pub struct DevEventHub {
     send_to_control: Sender<control::RecvEvent>,
     recv_from_control: Receiver<control::SendEvent>,
+    send_to_console: Sender<console::RecvEvent>,
+    recv_from_console: Receiver<console::SendEvent>,
     send_to_render: Sender<render::RecvEvent>,
     recv_from_render: Receiver<render::SendEvent>,
     send_to_game: Sender<game::RecvEvent>,
     recv_from_game: Receiver<game::SendEvent>,
+    console_open: bool,
+    //the character of the key that just toggled the console, which should not be typed into it
+    skip_char: bool,
+    bindings: Bindings,
+    modifiers: Modifiers,
+    held_keys: Vec<(Option<VirtualKeyCode>, u8, Action)>,
+    held_buttons: Vec<(MouseButton, Action)>,
+    quit: bool,
 }
 
 impl DevEventHub{
     pub fn new() -> (DevEventHub, GameEventHub) {
         let (send_to_control, recv_to_control) = channel();
         let (send_from_control, recv_from_control) = channel();
+        let (send_to_console, recv_to_console) = channel();
+        let (send_from_console, recv_from_console) = channel();
         let (send_to_render, recv_to_render) = channel();
         let (send_from_render, recv_from_render) = channel();
         let (send_to_game, recv_to_game) = channel();
@@ -52,14 +78,21 @@ impl DevEventHub{
         let (send_to_mapper, recv_to_mapper) = channel();
         let (send_from_mapper, recv_from_mapper) = channel();
 
+        let control_sender = send_to_control.clone();
+        let console_sender = send_to_console.clone();
+
         (
             DevEventHub::new_internal(
                 send_to_control, recv_from_control,
+                send_to_console, recv_from_console,
                 send_to_render, recv_from_render,
                 send_to_game, recv_from_game,
             ),
             GameEventHub::new(
                 (send_from_control, recv_to_control),
+                control_sender,
+                (send_from_console, recv_to_console),
+                console_sender,
                 (send_from_render, recv_to_render),
                 (send_from_game, recv_to_game),
                 (send_from_mapper, recv_to_mapper),
@@ -71,6 +104,8 @@ impl DevEventHub{
     fn new_internal(
         send_to_control: Sender<control::RecvEvent>,
         recv_from_control: Receiver<control::SendEvent>,
+        send_to_console: Sender<console::RecvEvent>,
+        recv_from_console: Receiver<console::SendEvent>,
         send_to_render: Sender<render::RecvEvent>,
         recv_from_render: Receiver<render::SendEvent>,
         send_to_game: Sender<game::RecvEvent>,
@@ -79,18 +114,41 @@ impl DevEventHub{
         DevEventHub {
             send_to_control: send_to_control,
             recv_from_control: recv_from_control,
+            send_to_console: send_to_console,
+            recv_from_console: recv_from_console,
             send_to_render: send_to_render,
             recv_from_render: recv_from_render,
             send_to_game: send_to_game,
             recv_from_game: recv_from_game,
-
+            console_open: false,
+            skip_char: false,
+            bindings: Bindings::new(),
+            modifiers: Modifiers::default(),
+            held_keys: vec!(),
+            held_buttons: vec!(),
+            quit: false,
         }
     }
 
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.bindings = bindings;
+    }
+
+    //true once the quit action was pressed
+    pub fn take_quit(&mut self) -> bool {
+        let quit = self.quit;
+        self.quit = false;
+        quit
+    }
+
+    //input goes through the game so it is applied, and recorded, at the start of a tick
     pub fn send_to_control(&mut self, event: control::RecvEvent) {
-        match self.send_to_control.send(event) {
-            Ok(()) => (),
-            Err(err) => error!("send to control error: {}", err),
+        match event {
+            control::RecvEvent::Exit => match self.send_to_control.send(event) {
+                Ok(()) => (),
+                Err(err) => error!("send to control error: {}", err),
+            },
+            event => self.send_to_game(game::RecvEvent::Control(event)),
         }
     }
 
@@ -111,6 +169,27 @@ impl DevEventHub{
         }
     }
 
+    pub fn send_to_console(&mut self, event: console::RecvEvent) {
+        match event {
+            console::RecvEvent::Reply(_) |
+            console::RecvEvent::Exit => match self.send_to_console.send(event) {
+                Ok(()) => (),
+                Err(err) => error!("send to console error: {}", err),
+            },
+            event => self.send_to_game(game::RecvEvent::Console(event)),
+        }
+    }
+
+    pub fn try_recv_from_console(&mut self) -> Option<console::SendEvent> {
+        match self.recv_from_console.try_recv() {
+            Ok(event) => Some(event),
+            Err(err) => match err {
+                TryRecvError::Empty => None,
+                TryRecvError::Disconnected => panic!("try recv from console was disconnected"),
+            },
+        }
+    }
+
     pub fn send_to_render(&mut self, event: render::RecvEvent) {
         match self.send_to_render.send(event) {
             Ok(()) => (),
@@ -150,38 +229,149 @@ impl DevEventHub{
     }
 
     pub fn process_glutin(&mut self, event: Event) {
-        use glutin::Event::{MouseMoved, MouseInput, KeyboardInput, Resized};
-        use glutin::VirtualKeyCode;
+        use glutin::Event::{MouseMoved, MouseInput, MouseWheel, KeyboardInput, ReceivedCharacter, Resized, DroppedFile, Focused};
+        use glutin::MouseScrollDelta::{LineDelta, PixelDelta};
         use glutin::ElementState::{Pressed, Released};
+
         match event {
-            MouseMoved(x, y) => self.send_to_control(control::RecvEvent::MouseMoved(x as u32, y as u32)),
-            MouseInput(state, button) => self.send_to_control(control::RecvEvent::MouseInput(match state {
-                Pressed => true,
-                Released => false,
-            },
-            button)),
-            KeyboardInput(state, _, Some(VirtualKeyCode::D)) |
-            KeyboardInput(state, _, Some(VirtualKeyCode::Right)) => match state {
-                Pressed => self.send_to_control(control::RecvEvent::Right(true)),
-                Released => self.send_to_control(control::RecvEvent::Right(false)),
-            },
-            KeyboardInput(state, _, Some(VirtualKeyCode::A)) |
-            KeyboardInput(state, _, Some(VirtualKeyCode::Left)) => match state {
-                Pressed => self.send_to_control(control::RecvEvent::Left(true)),
-                Released => self.send_to_control(control::RecvEvent::Left(false)),
-            },
-            KeyboardInput(state, _, Some(VirtualKeyCode::W)) |
-            KeyboardInput(state, _, Some(VirtualKeyCode::Up)) => match state {
-                Pressed => self.send_to_control(control::RecvEvent::Up(true)),
-                Released => self.send_to_control(control::RecvEvent::Up(false)),
-            },
-            KeyboardInput(state, _, Some(VirtualKeyCode::S)) |
-            KeyboardInput(state, _, Some(VirtualKeyCode::Down)) => match state {
-                Pressed => self.send_to_control(control::RecvEvent::Down(true)),
-                Released => self.send_to_control(control::RecvEvent::Down(false)),
+            KeyboardInput(state, scan, key) => {
+                let pressed = state == Pressed;
+                self.update_modifiers(key, pressed);
+
+                if pressed {
+                    //a character only follows its own key, so a toggle key that types nothing skips nothing
+                    self.skip_char = false;
+
+                    let actions = self.bindings.find(key, scan, self.modifiers);
+                    if actions.contains(&Action::ToggleConsole) {
+                        self.skip_char = true;
+                        self.toggle_console();
+                        return;
+                    }
+
+                    //while the console is open the keyboard types into it instead of moving the camera
+                    if self.console_open {
+                        self.send_console_key(key);
+                        return;
+                    }
+
+                    for action in actions {
+                        self.held_keys.push((key, scan, action));
+                        self.send_action(action, true);
+                    }
+                } else {
+                    //released with whatever modifiers are held now, so match on the key alone
+                    let mut released = vec!();
+                    self.held_keys.retain(|&(held_key, held_scan, action)| {
+                        let matches = (key.is_some() && held_key == key) || held_scan == scan;
+                        if matches {
+                            released.push(action);
+                        }
+                        !matches
+                    });
+                    for action in released {
+                        self.send_action(action, false);
+                    }
+                }
+            },
+            ReceivedCharacter(c) => if self.skip_char {
+                self.skip_char = false;
+            } else if self.console_open && !c.is_control() {
+                self.send_to_console(console::RecvEvent::Char(c));
+            },
+            MouseMoved(x, y) => self.send_to_control(control::RecvEvent::MouseMoved(x, y)),
+            MouseInput(Pressed, button) => {
+                for action in self.bindings.find_mouse(button, self.modifiers) {
+                    self.held_buttons.push((button, action));
+                    self.send_action(action, true);
+                }
+            },
+            MouseInput(Released, button) => {
+                let mut released = vec!();
+                self.held_buttons.retain(|&(held_button, action)| {
+                    if held_button == button {
+                        released.push(action);
+                    }
+                    held_button != button
+                });
+                for action in released {
+                    self.send_action(action, false);
+                }
             },
+            MouseWheel(LineDelta(_, lines), _) => self.send_to_control(control::RecvEvent::Zoom(lines)),
+            MouseWheel(PixelDelta(_, pixels), _) => self.send_to_control(control::RecvEvent::Zoom(pixels / SCROLL_LINE_PIXELS)),
+            Focused(focused) => self.send_to_control(control::RecvEvent::Focused(focused)),
+            DroppedFile(path) => self.send_to_game(game::RecvEvent::Import(path)),
             Resized(width, height) => self.send_to_control(control::RecvEvent::Resize(width, height)),
             _ => (),
         }
     }
+
+    fn update_modifiers(&mut self, key: Option<VirtualKeyCode>, pressed: bool) {
+        match key {
+            Some(VirtualKeyCode::LControl) | Some(VirtualKeyCode::RControl) => self.modifiers.ctrl = pressed,
+            Some(VirtualKeyCode::LShift) | Some(VirtualKeyCode::RShift) => self.modifiers.shift = pressed,
+            Some(VirtualKeyCode::LAlt) | Some(VirtualKeyCode::RAlt) => self.modifiers.alt = pressed,
+            _ => (),
+        }
+    }
+
+    fn send_console_key(&mut self, key: Option<VirtualKeyCode>) {
+        match key {
+            Some(VirtualKeyCode::Back) => self.send_to_console(console::RecvEvent::Backspace),
+            Some(VirtualKeyCode::Return) => self.send_to_console(console::RecvEvent::Submit),
+            Some(VirtualKeyCode::Tab) => self.send_to_console(console::RecvEvent::Complete),
+            Some(VirtualKeyCode::Up) => self.send_to_console(console::RecvEvent::HistoryPrev),
+            Some(VirtualKeyCode::Down) => self.send_to_console(console::RecvEvent::HistoryNext),
+            _ => (),
+        }
+    }
+
+    //one shot actions only fire on press, the rest are forwarded with their state
+    fn send_action(&mut self, action: Action, pressed: bool) {
+        match action {
+            Action::PanRight => self.send_to_control(control::RecvEvent::Right(pressed)),
+            Action::PanLeft => self.send_to_control(control::RecvEvent::Left(pressed)),
+            Action::PanUp => self.send_to_control(control::RecvEvent::Up(pressed)),
+            Action::PanDown => self.send_to_control(control::RecvEvent::Down(pressed)),
+            Action::Control(action) => self.send_to_control(control::RecvEvent::Action(action, pressed)),
+            Action::ToggleConsole => if pressed {
+                self.toggle_console();
+            },
+            Action::ZoomFit => if pressed {
+                self.send_to_console(console::RecvEvent::Command("fit".to_string()));
+            },
+            Action::ToggleMinimap => if pressed {
+                self.send_to_console(console::RecvEvent::Command("minimap".to_string()));
+            },
+            Action::ToggleSplit => if pressed {
+                self.send_to_console(console::RecvEvent::Command("split".to_string()));
+            },
+            Action::Screenshot => if pressed {
+                self.send_to_console(console::RecvEvent::Command("screenshot".to_string()));
+            },
+            Action::Verify => if pressed {
+                self.send_to_console(console::RecvEvent::Command("verify".to_string()));
+            },
+            Action::ExportVerilog => if pressed {
+                self.send_to_game(game::RecvEvent::ExportVerilog(PathBuf::from("circuit.v")));
+            },
+            Action::Quit => if pressed {
+                self.quit = true;
+            },
+        }
+    }
+
+    fn toggle_console(&mut self) {
+        self.console_open = !self.console_open;
+        if self.console_open {
+            //keys released while typing never reach control so stop any movement now
+            self.send_to_control(control::RecvEvent::Right(false));
+            self.send_to_control(control::RecvEvent::Left(false));
+            self.send_to_control(control::RecvEvent::Up(false));
+            self.send_to_control(control::RecvEvent::Down(false));
+            self.held_keys.clear();
+        }
+        self.send_to_console(console::RecvEvent::Toggle);
+    }
 }
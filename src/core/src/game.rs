@@ -1,8 +1,12 @@
 use std::sync::mpsc::{Sender, Receiver, TryRecvError};
+use std::path::{Path, PathBuf};
+use std::fs::{File};
+use std::io::{BufReader};
+use std::collections::{HashMap};
 
 use nalgebra;
 
-use specs::{Planner, World};
+use specs::{Planner, World, Entity};
 
 use gfx_device_gl::Factory as GLFactory;
 
@@ -12,21 +16,34 @@ use time::{precise_time_ns};
 
 //*************************************************************************************************
 
-use comps::{RenderId, Transform, Camera, RenderData, Clickable, Wire, WireIn, WireOut};
-use comps::non_components::{Map};
+use comps::{Animation, AnimationMode, Text, Overlay, OverlayAnchor, RenderId, Transform, Camera, RenderData, Wire, WireIn, WireOut, Gate};
+use comps::non_components::{Map, Console, Selection};
+use comps::non_components::link::{get_floating_cell};
 
-use sys::{Render, Control, Mapper, WireFlow, LinkConnector, mapper};
+use sys::{Animator, Hud, Render, Control, Console as ConsoleSystem, Mapper, WireFlow, LinkConnector, console, control, mapper, router};
+use sys::spawn::{self, make_tile_transform};
+use sys::console::{count_placed, make_board_rect};
 
 use graphics::{load_texture};
+use graphics::software::{Canvas};
+use graphics::text::{Font};
 
 use event::{GameEventHub};
+use record::{Recorder};
+use reload::{Reloader};
+use config::{DEFAULT_MOVE_SPEED};
+use snapshot;
 
-use utils::{Delta, GfxCoord};
-use utils::fps_counter::{FpsCounter};
+use utils::{Delta, Coord, GfxCoord};
 
-use math::{OrthographicHelper, Point2, Rect};
+use math::{OrthographicHelper, Point2, Point2I, Point3I, Rect, Router};
 
-use art::{layers, wires, empty, make_square_render};
+use netlist::{Netlist, NodeKind, blif, svg, verilog};
+use netlist::layout::{self, Layout};
+use netlist::level::{Level};
+use netlist::save::{Circuit, Item};
+
+use art::{layers, wires, gates, text, camera, make_square_render};
 
 //*************************************************************************************************
 
@@ -35,8 +52,13 @@ pub type Channel = (
     Receiver<RecvEvent>,
 );
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RecvEvent {
+    //input for control and the console goes through here so it is applied, and recorded, at the start of a tick
+    Control(control::RecvEvent),
+    Console(console::RecvEvent),
+    ExportVerilog(PathBuf),
+    Import(PathBuf),
     Exit,
 }
 
@@ -48,53 +70,34 @@ pub enum SendEvent {
 pub struct Game {
     planner: Planner<Delta>,
     last_time: u64,
+    fixed_delta: Option<Delta>,
     channel: Channel,
+    control_sender: Sender<control::RecvEvent>,
+    console_sender: Sender<console::RecvEvent>,
+    recorder: Option<Recorder>,
+    tick: u64,
     mapper_channel: mapper::channel::Game,
-    fps_counter: FpsCounter,
     wires_render: RenderId,
+    split_target: Option<RenderId>,
+    level: Option<Level>,
 }
 
 impl Game {
     pub fn new(
         factory: &mut GLFactory,
+        reloader: &mut Reloader,
         mut game_event_hub: GameEventHub,
         mouse_location: Point2,
         screen_resolution: Point2,
-        ortho_helper: OrthographicHelper
+        ortho_helper: OrthographicHelper,
+        move_speed: Point2,
+        level: Option<Level>
     ) -> Game {
-        let mut planner = {
-            let mut w = World::new();
-
-            w.register::<RenderId>();
-            w.register::<Transform>();
-            w.register::<Camera>();
-            w.register::<RenderData>();
-            w.register::<Clickable>();
-            w.register::<Wire>();
-            w.register::<WireIn>();
-            w.register::<WireOut>();
-
-            w.add_resource(Map::new());
-
-            Planner::<Delta>::new(w, 8)
-        };
-
         let mut renderer = Render::new(match game_event_hub.render_channel.take() {
             Some(channel) => channel,
             None => panic!("game event hub render channel was none"),
         });
 
-        //make the camera
-        planner.mut_world().create_now()
-            .with(Camera::new_from_ortho_helper(
-                nalgebra::Point3::new(0.0, 0.0, 2.0),
-                nalgebra::Point3::new(0.0, 0.0, 0.0),
-                nalgebra::Vector3::new(0.0, 1.0, 0.0),
-                &ortho_helper,
-                true
-            ))
-            .build();
-
         //make the basic square render packet
         let packet = make_square_render();
 
@@ -104,54 +107,206 @@ impl Game {
             Err(err) => panic!("error finding assets folder: {}", err),
         };
 
-        //spritesheet for empty tiles
-        let empty_render = {
-            let texture = load_texture(
-                factory,
-                assets_folder.join(
-                    empty::NAME
-                )
-            );
-            renderer.add_render_spritesheet(
-                factory,
-                &packet,
-                texture
-            )
-        };
-
-        //create 21x21 grid of empty tiles
-        for y in -10..11isize {
-            for x in -10..11isize {
-                planner.mut_world().create_now()
-                    .with(empty_render)//empty tile spritesheet
-                    .with(Transform::new(
-                        nalgebra::Isometry3::new(
-                            nalgebra::Vector3::new(x as GfxCoord, y as GfxCoord, 0.0),
-                            nalgebra::Vector3::new(0.0, 0.0, 0.0)
-                        ),
-                        nalgebra::Vector3::new(1.0, 1.0, 1.0)
-                    ))//with transform (x,y)
-                    .with(Clickable::new(Rect::new_from_coords(0.0, 0.0, 1.0, 1.0)))//that can be clicked on
-                    .with(RenderData::new(layers::EMPTY, empty::DEFAULT_TINT, empty::RECT, empty::SIZE))//with render data that is of empty
-                    .build();
-            }
-        }
+        //grid pass drawn behind everything in place of empty tiles
+        renderer.add_render_grid(factory, level.as_ref().map(make_board_bounds));
+        reloader.add_grid();
 
         //wires render with spritesheet id
         let wires_render = {
-            let texture = load_texture(
+            let path = assets_folder.join(
+                wires::NAME
+            );
+            let (texture, canvas) = load_texture(
                 factory,
-                assets_folder.join(
-                    wires::NAME
-                )
+                &path
             );
-            renderer.add_render_spritesheet(
+            let render_id = renderer.add_render_spritesheet(
                 factory,
                 &packet,
-                texture
-            )
+                texture,
+                canvas
+            );
+            reloader.add_texture(render_id, &path);
+            reloader.add_spritesheet(render_id, packet.get_rasterizer());
+            render_id
+        };
+
+        let (font_texture, font_canvas, font) = Font::new_builtin(factory);
+        renderer.add_render_text(factory, &packet, font_texture, font_canvas, font);
+
+        //the split view starts at the size it takes up in the window
+        let minimap_target = renderer.add_camera_target(factory, &packet, camera::MINIMAP_TARGET_SIZE);
+        let split_target = renderer.add_camera_target(factory, &packet, make_split_target_size(
+            screen_resolution.get_x() as u32,
+            screen_resolution.get_y() as u32
+        ));
+
+        Game::new_internal(
+            game_event_hub,
+            Some(renderer),
+            Some((minimap_target, split_target)),
+            wires_render,
+            mouse_location,
+            screen_resolution,
+            ortho_helper,
+            move_speed,
+            level
+        )
+    }
+
+    pub fn new_headless(
+        game_event_hub: GameEventHub,
+        mouse_location: Point2,
+        screen_resolution: Point2,
+        ortho_helper: OrthographicHelper,
+        level: Option<Level>
+    ) -> Game {
+        //nothing is drawn without a renderer so any id will do
+        Game::new_internal(
+            game_event_hub,
+            None,
+            None,
+            RenderId {
+                id: 0,
+            },
+            mouse_location,
+            screen_resolution,
+            ortho_helper,
+            //replays must not depend on the local config
+            Point2::new(DEFAULT_MOVE_SPEED, DEFAULT_MOVE_SPEED),
+            level
+        )
+    }
+
+    //draws with the software renderer alone, so images of the world can be asked for without a window
+    pub fn new_software(
+        mut game_event_hub: GameEventHub,
+        screen_resolution: Point2,
+        ortho_helper: OrthographicHelper,
+        level: Option<Level>
+    ) -> Game {
+        let (width, height) = (screen_resolution.get_x() as u32, screen_resolution.get_y() as u32);
+        let mut renderer = Render::new_software(match game_event_hub.render_channel.take() {
+            Some(channel) => channel,
+            None => panic!("game event hub render channel was none"),
+        }, width, height);
+
+        let packet = make_square_render();
+
+        let assets_folder = match Search::ParentsThenKids(3, 3).for_folder("assets") {
+            Ok(path) => path,
+            Err(err) => panic!("error finding assets folder: {}", err),
+        };
+
+        renderer.add_software_grid(level.as_ref().map(make_board_bounds));
+
+        let wires_render = match Canvas::load(assets_folder.join(wires::NAME)) {
+            Ok(canvas) => renderer.add_software_spritesheet(&packet, canvas),
+            Err(err) => panic!("software wires texture load error: {}", err),
+        };
+
+        let (font_canvas, font) = Font::new_builtin_canvas();
+        renderer.add_software_text(&packet, font_canvas, font);
+
+        let minimap_target = renderer.add_software_camera_target(&packet, camera::MINIMAP_TARGET_SIZE);
+        let split_target = renderer.add_software_camera_target(&packet, make_split_target_size(width, height));
+
+        Game::new_internal(
+            game_event_hub,
+            Some(renderer),
+            Some((minimap_target, split_target)),
+            wires_render,
+            Point2::new(0.0, 0.0),
+            screen_resolution,
+            ortho_helper,
+            //like replays, nothing here depends on the local config
+            Point2::new(DEFAULT_MOVE_SPEED, DEFAULT_MOVE_SPEED),
+            level
+        )
+    }
+
+    fn new_internal(
+        mut game_event_hub: GameEventHub,
+        renderer: Option<Render>,
+        camera_targets: Option<(RenderId, RenderId)>,
+        wires_render: RenderId,
+        mouse_location: Point2,
+        screen_resolution: Point2,
+        ortho_helper: OrthographicHelper,
+        move_speed: Point2,
+        level: Option<Level>
+    ) -> Game {
+        let mut planner = {
+            let mut w = World::new();
+
+            w.register::<RenderId>();
+            w.register::<Transform>();
+            w.register::<Camera>();
+            w.register::<RenderData>();
+            w.register::<Wire>();
+            w.register::<WireIn>();
+            w.register::<WireOut>();
+            w.register::<Gate>();
+            w.register::<Animation>();
+            w.register::<Text>();
+            w.register::<Overlay>();
+
+            w.add_resource(Map::new());
+            w.add_resource(Console::new());
+            w.add_resource(Selection::new());
+
+            Planner::<Delta>::new(w, 8)
         };
 
+        //make the camera
+        let mut camera = Camera::new_from_ortho_helper(
+            nalgebra::Point3::new(0.0, 0.0, 2.0),
+            nalgebra::Point3::new(0.0, 0.0, 0.0),
+            nalgebra::Vector3::new(0.0, 1.0, 0.0),
+            &ortho_helper,
+            true
+        );
+        if let Some(ref level) = level {
+            let (width, height) = level.get_size();
+            camera.set_offset(Point2::new(width as Coord / 2.0, height as Coord / 2.0));
+        }
+        let offset = camera.get_offset();
+        planner.mut_world().create_now()
+            .with(camera)
+            .build();
+
+        //the minimap is framed on the board by control, the split view stays hidden until asked for,
+        //both always exist so replays behave the same with or without a window
+        let mut minimap = Camera::new_from_ortho_helper(
+            nalgebra::Point3::new(0.0, 0.0, 2.0),
+            nalgebra::Point3::new(0.0, 0.0, 0.0),
+            nalgebra::Vector3::new(0.0, 1.0, 0.0),
+            &ortho_helper,
+            false
+        );
+        minimap.set_minimap(true);
+        minimap.set_viewport(make_viewport(camera::MINIMAP_VIEWPORT));
+        minimap.set_render_target(camera_targets.map(|(minimap_target, _)| minimap_target));
+        minimap.set_offset(offset.clone());
+        planner.mut_world().create_now()
+            .with(minimap)
+            .build();
+
+        let mut split = Camera::new_from_ortho_helper(
+            nalgebra::Point3::new(0.0, 0.0, 2.0),
+            nalgebra::Point3::new(0.0, 0.0, 0.0),
+            nalgebra::Vector3::new(0.0, 1.0, 0.0),
+            &ortho_helper,
+            false
+        );
+        split.set_visible(false);
+        split.set_viewport(make_viewport(camera::SPLIT_VIEWPORT));
+        split.set_render_target(camera_targets.map(|(_, split_target)| split_target));
+        split.set_offset(offset);
+        planner.mut_world().create_now()
+            .with(split)
+            .build();
+
         // planner.mut_world().create_now()
         //     .with(wires_render)
         //     .with(Transform::new(
@@ -191,21 +346,96 @@ impl Game {
         //     .with(RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE))
         //     .build();
 
+        //hud text in the bottom left corner, the selection line sits above the fps line and the mode line above that
+        let fps_text = planner.mut_world().create_now()
+            .with(Transform::new_identity())
+            .with(Overlay::new(
+                OverlayAnchor::BottomLeft,
+                Point2::new(text::HUD_MARGIN as Coord, text::HUD_MARGIN as Coord),
+                Point2::new(text::HUD_WIDTH as Coord, text::HUD_SIZE as Coord)
+            ))
+            .with(Text::new("", text::HUD_COLOR, layers::TEXT))
+            .build();
+        let selection_text = planner.mut_world().create_now()
+            .with(Transform::new_identity())
+            .with(Overlay::new(
+                OverlayAnchor::BottomLeft,
+                Point2::new(text::HUD_MARGIN as Coord, (text::HUD_MARGIN + text::HUD_SIZE) as Coord),
+                Point2::new(text::HUD_WIDTH as Coord, text::HUD_SIZE as Coord)
+            ))
+            .with(Text::new("", text::HUD_COLOR, layers::TEXT))
+            .build();
+        let mode_text = planner.mut_world().create_now()
+            .with(Transform::new_identity())
+            .with(Overlay::new(
+                OverlayAnchor::BottomLeft,
+                Point2::new(text::HUD_MARGIN as Coord, (text::HUD_MARGIN + text::HUD_SIZE * 2.0) as Coord),
+                Point2::new(text::MODE_WIDTH as Coord, text::HUD_SIZE as Coord)
+            ))
+            .with(Text::new("", text::HUD_COLOR, layers::TEXT))
+            .build();
+        //the console's first line sits at the top and the rest run down from it
+        let console_text = planner.mut_world().create_now()
+            .with(Transform::new_identity())
+            .with(Overlay::new(
+                OverlayAnchor::TopLeft,
+                Point2::new(text::HUD_MARGIN as Coord, text::HUD_MARGIN as Coord),
+                Point2::new(text::CONSOLE_WIDTH as Coord, text::HUD_SIZE as Coord)
+            ))
+            .with(Text::new("", text::HUD_COLOR, layers::TEXT))
+            .build();
+
+        //level ports are fixed in place and named so verify can find them, their labels show the port's value
+        let mut hud = Hud::new(fps_text, selection_text, mode_text, console_text);
+        if let Some(ref level) = level {
+            for port in level.get_inputs() {
+                let location = port.get_location().clone();
+                let output = Point3I::new(location.get_x() + 1, location.get_y(), location.get_z());
+                let entity = spawn::spawn_item(planner.mut_world(), &Item::WireIn(location.clone(), vec!(output), Some(port.get_name().to_string())), wires_render);
+                planner.mut_world().write::<Animation>().insert(entity, make_port_pulse());
+                let label = spawn_label(planner.mut_world(), &location, port.get_name());
+                hud.add_value_label(label, location, port.get_name());
+            }
+            for port in level.get_outputs() {
+                let location = port.get_location().clone();
+                let input = Point3I::new(location.get_x() - 1, location.get_y(), location.get_z());
+                let entity = spawn::spawn_item(planner.mut_world(), &Item::WireOut(location.clone(), input, Some(port.get_name().to_string())), wires_render);
+                planner.mut_world().write::<Animation>().insert(entity, make_port_pulse());
+                let label = spawn_label(planner.mut_world(), &location, port.get_name());
+                hud.add_value_label(label, location, port.get_name());
+            }
+            info!("loaded level {}", level.get_name());
+        }
+
+        let game_level = level.clone();
+        let console_sender = game_event_hub.console_sender.take().expect("Game event hub console sender was none");
+
         planner.add_system(
             Control::new(
                 match game_event_hub.control_channel.take() {
                     Some(channel) => channel,
                     None => panic!("game event hub control channel was none"),
                 },
-                Point2::new(10.0, 10.0),
+                move_speed,
                 mouse_location,
                 screen_resolution,
                 ortho_helper,
+                console_sender.clone(),
             ),
             "control",
             30
         );
 
+        planner.add_system(
+            ConsoleSystem::new(
+                game_event_hub.console_channel.take().expect("Game event hub console channel was none"),
+                wires_render,
+                level
+            ),
+            "console",
+            31
+        );
+
         planner.add_system(
             Mapper::new(
                 game_event_hub.mapper_channel_mapper.take().expect("Game Event Hub Mapper Channel Mapper was none"),
@@ -226,38 +456,393 @@ impl Game {
             27
         );
 
-        planner.add_system(renderer, "renderer", 10);
+        planner.add_system(
+            Animator::new(),
+            "animator",
+            20
+        );
+
+        planner.add_system(
+            hud,
+            "hud",
+            20
+        );
+
+        if let Some(renderer) = renderer {
+            planner.add_system(renderer, "renderer", 10);
+        }
 
         Game {
             planner: planner,
             last_time: precise_time_ns(),
+            fixed_delta: None,
             channel: match game_event_hub.game_channel.take() {
                 Some(channel) => channel,
                 None => panic!("game event hub game channel was none"),
             },
+            control_sender: game_event_hub.control_sender.take().expect("Game event hub control sender was none"),
+            console_sender: console_sender,
+            recorder: None,
+            tick: 0,
             mapper_channel: game_event_hub.mapper_channel_game.take().expect("Game event hub mapper channel game was none"),
-            fps_counter: FpsCounter::new(),
             wires_render: wires_render,
+            split_target: camera_targets.map(|(_, split_target)| split_target),
+            level: game_level,
         }
     }
 
 
+    //the main thread remakes this target whenever the window is resized
+    pub fn get_split_target(&self) -> Option<RenderId> {
+        self.split_target
+    }
+
+    //runs every frame with the same delta and waits for it to finish so replays stay deterministic
+    pub fn set_fixed_delta(&mut self, delta: Delta) {
+        self.fixed_delta = Some(delta);
+    }
+
+    pub fn start_recording(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    fn stop_recording(&mut self) {
+        if let Some(mut recorder) = self.recorder.take() {
+            recorder.finish(self.tick);
+        }
+    }
+
     pub fn frame(&mut self) -> bool {
         let new_time = precise_time_ns();
-        let delta = (new_time - self.last_time) as Delta / 1e9;
+        let delta = match self.fixed_delta {
+            Some(delta) => delta,
+            None => (new_time - self.last_time) as Delta / 1e9,
+        };
         self.last_time = new_time;
 
-        match self.channel.1.try_recv() {
-            Err(TryRecvError::Empty) => {
-                self.planner.dispatch(delta);
-                self.fps_counter.frame(delta);
-                true
+        //everything sent since the last tick is applied before this one
+        loop {
+            match self.channel.1.try_recv() {
+                Err(TryRecvError::Empty) => break,
+                Ok(RecvEvent::Exit) |
+                Err(TryRecvError::Disconnected) => {
+                    //finished before waiting since systems may be blocked on the main thread that is waiting for it
+                    self.stop_recording();
+                    match self.channel.0.send(SendEvent::Exited) {
+                        Ok(()) => (),
+                        Err(err) => error!("game send exited error: {}", err),
+                    }
+                    self.planner.wait();
+                    return false;
+                },
+                Ok(event) => self.apply(event),
+            }
+        }
+
+        self.planner.dispatch(delta);
+        if self.fixed_delta.is_some() {
+            self.planner.wait();
+        }
+        self.tick += 1;
+        true
+    }
+
+    //control and the console read their channels at the start of their run, so they see this in the coming tick
+    fn apply(&mut self, event: RecvEvent) {
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record(self.tick, &event);
+        }
+
+        match event {
+            RecvEvent::Control(event) => match self.control_sender.send(event) {
+                Ok(()) => (),
+                Err(err) => error!("game send to control error: {}", err),
             },
-            Ok(RecvEvent::Exit) |
-            Err(TryRecvError::Disconnected) => {
-                self.planner.wait();
-                false
+            RecvEvent::Console(event) => match self.console_sender.send(event) {
+                Ok(()) => (),
+                Err(err) => error!("game send to console error: {}", err),
             },
+            RecvEvent::ExportVerilog(path) => self.export_verilog(path),
+            RecvEvent::Import(path) => self.import(path),
+            RecvEvent::Exit => (),
         }
     }
+
+    fn export_verilog(&mut self, path: PathBuf) {
+        self.planner.wait();
+
+        let netlist = Netlist::from_world(self.planner.mut_world());
+
+        let module_name = match path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => "circuit".to_string(),
+        };
+
+        let mut file = match File::create(&path) {
+            Ok(file) => file,
+            Err(err) => {
+                error!("export verilog create file error: {}", err);
+                return;
+            },
+        };
+
+        match verilog::write(&netlist, &module_name, &mut file) {
+            Ok(()) => info!("exported verilog to {}", path.display()),
+            Err(err) => error!("export verilog write error: {}", err),
+        }
+    }
+
+    pub fn write_snapshot(&mut self, path: &Path) {
+        self.planner.wait();
+
+        let mut file = match File::create(path) {
+            Ok(file) => file,
+            Err(err) => panic!("write snapshot create file error: {}", err),
+        };
+
+        match snapshot::write_world(self.planner.mut_world(), &mut file) {
+            Ok(()) => info!("wrote snapshot to {}", path.display()),
+            Err(err) => panic!("write snapshot error: {}", err),
+        }
+    }
+
+    //places every item of a save file that lands on an empty cell, as the console's load does without a level
+    pub fn load_save(&mut self, path: &Path) -> Result<(), String> {
+        self.planner.wait();
+
+        let file = try!(File::open(path).map_err(|err| format!("open {} error: {}", path.display(), err)));
+        let circuit = try!(Circuit::read(BufReader::new(file)).map_err(|err| format!("{} {}", path.display(), err)));
+        if circuit.get_items().is_empty() {
+            return Err(format!("{} has nothing in it", path.display()));
+        }
+
+        let wires_render = self.wires_render;
+        let world = self.planner.mut_world();
+        for item in circuit.get_items() {
+            let occupied = world.read_resource::<Map>().get_map().contains_key(item.get_location());
+            if occupied {
+                warn!("load save {} skipped occupied {:?}", path.display(), item.get_location());
+            } else {
+                spawn::spawn_item(world, item, wires_render);
+            }
+        }
+        info!("loaded {} items from {}", circuit.get_items().len(), path.display());
+        Ok(())
+    }
+
+    //the board with a margin, none while it is empty
+    pub fn get_board_rect(&mut self) -> Option<Rect> {
+        self.planner.wait();
+
+        make_board_rect(&self.planner.mut_world().read_resource::<Map>())
+    }
+
+    pub fn write_svg(&mut self, path: &Path, values: bool) {
+        self.planner.wait();
+
+        let netlist = Netlist::from_world(self.planner.mut_world());
+
+        let mut file = match File::create(path) {
+            Ok(file) => file,
+            Err(err) => panic!("write svg create file error: {}", err),
+        };
+
+        match svg::write(&netlist, values, &mut file) {
+            Ok(()) => info!("wrote svg to {}", path.display()),
+            Err(err) => panic!("write svg error: {}", err),
+        }
+    }
+
+    fn import(&mut self, path: PathBuf) {
+        let netlist = match read_netlist(&path) {
+            Ok(netlist) => netlist,
+            Err(err) => {
+                error!("import {} error: {}", path.display(), err);
+                return;
+            },
+        };
+
+        self.planner.wait();
+
+        let layout = {
+            let map = self.planner.mut_world().read_resource::<Map>();
+
+            let origin = match map.get_map().keys().map(|location| location.get_x()).max() {
+                Some(x) => Point3I::new(x + 2, 0, 0),
+                None => Point3I::zero(),
+            };
+
+            let level = self.level.as_ref();
+            let is_blocked = |location: &Point3I| {
+                map.get_map().contains_key(location) || level.map_or(false, |level| !level.contains(location))
+            };
+            layout::place_and_route(&netlist, origin, &Router::new_layered(router::LAYERS.0, router::LAYERS.1), is_blocked)
+        };
+
+        //nothing is built unless the whole import fits the level
+        let layout = match layout {
+            Ok(layout) => match self.level {
+                Some(ref level) => {
+                    let world = &*self.planner.mut_world();
+                    let checked = level.check_additions(make_additions(&netlist, &layout), |kind| count_placed(world, kind));
+                    checked.map(|()| layout)
+                },
+                None => Ok(layout),
+            },
+            Err(err) => Err(err),
+        };
+
+        match layout {
+            Ok(layout) => {
+                self.build_layout(&netlist, &layout);
+                info!("imported {} with {} nodes", path.display(), netlist.get_nodes().len());
+            },
+            Err(err) => error!("import {} layout error: {}", path.display(), err),
+        }
+    }
+
+    fn build_layout(&mut self, netlist: &Netlist, layout: &Layout) {
+        let mut mappings = vec!();
+
+        {
+            let world = self.planner.mut_world();
+
+            for route in layout.get_routes() {
+                let path = route.get_path();
+                for i in 1..path.len() - 1 {
+                    let entity = world.create_now()
+                        .with(self.wires_render)
+                        .with(make_tile_transform(&path[i]))
+                        .with(RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE))
+                        .with(Wire::new_from_points(path[i - 1].clone(), path[i + 1].clone(), path[i].clone()))
+                        .build();
+                    mappings.push((path[i].clone(), entity));
+                }
+            }
+
+            //a driver that fans out points at the first cell of every route leaving it
+            let mut first_cells = HashMap::new();
+            for route in layout.get_routes() {
+                let path = route.get_path();
+                first_cells.entry(path[0].clone()).or_insert(vec!()).push(path[1].clone());
+            }
+
+            for (index, node) in netlist.get_nodes().iter().enumerate() {
+                let location = layout.get_location(index).clone();
+
+                //unrouted pins float so a wire placed next to them later is not silently wired in
+                let outputs = match first_cells.get(&location) {
+                    Some(cells) => cells.clone(),
+                    None => vec!(get_floating_cell()),
+                };
+
+                let input = |input: usize| match layout.get_route(index, input) {
+                    Some(route) => route.get_path()[route.get_path().len() - 2].clone(),
+                    None => get_floating_cell(),
+                };
+
+                let builder = world.create_now()
+                    .with(self.wires_render)
+                    .with(make_tile_transform(&location));
+
+                let entity = match *node.get_kind() {
+                    NodeKind::Input(ref name) => builder
+                        .with(RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE))
+                        .with(WireIn::new_named(outputs, location.clone(), name))
+                        .build(),
+                    NodeKind::Output(ref name) => builder
+                        .with(RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE))
+                        .with(WireOut::new_named(input(0), location.clone(), name))
+                        .build(),
+                    NodeKind::Gate(kind) => builder
+                        .with(RenderData::new(layers::GATES, gates::DEFAULT_TINT, gates::RECT, gates::SIZE))
+                        .with(Gate::new_from_points(kind, (0..node.get_inputs().len()).map(|i| input(i)).collect(), outputs, location.clone()))
+                        .build(),
+                };
+
+                mappings.push((location.clone(), entity));
+            }
+        }
+
+        for (location, entity) in mappings {
+            match self.mapper_channel.0.send(mapper::RecvEvent::NewMapping(location, entity)) {
+                Ok(()) => (),
+                Err(err) => error!("build layout mapper send error: {}", err),
+            }
+        }
+    }
+}
+
+//every part a layout adds with the name the level limits it by
+fn make_additions<'a>(netlist: &Netlist, layout: &'a Layout) -> Vec<(&'static str, &'a Point3I)> {
+    let mut additions = vec!();
+    for (index, node) in netlist.get_nodes().iter().enumerate() {
+        let kind = match *node.get_kind() {
+            NodeKind::Input(_) => "in",
+            NodeKind::Output(_) => "out",
+            NodeKind::Gate(kind) => kind.get_name(),
+        };
+        additions.push((kind, layout.get_location(index)));
+    }
+    for route in layout.get_routes() {
+        let path = route.get_path();
+        for location in &path[1..path.len() - 1] {
+            additions.push(("wire", location));
+        }
+    }
+    additions
+}
+
+fn read_netlist(path: &Path) -> Result<Netlist, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => return Err(format!("open error: {}", err)),
+    };
+
+    let extension = path.extension().map(|extension| extension.to_string_lossy().into_owned());
+
+    let result = match extension.as_ref().map(|extension| extension.as_str()) {
+        Some("blif") => blif::read(BufReader::new(file)),
+        Some("v") => verilog::read(file),
+        _ => return Err("only .blif and .v netlists can be imported".to_string()),
+    };
+
+    result.map_err(|err| err.to_string())
+}
+
+fn make_viewport(viewport: [Coord; 4]) -> Rect {
+    Rect::new_from_coords(viewport[0], viewport[1], viewport[2], viewport[3])
+}
+
+//the split view's share of a window, never smaller than a pixel
+pub fn make_split_target_size(width: u32, height: u32) -> (u16, u16) {
+    let viewport = camera::SPLIT_VIEWPORT;
+    (
+        ((width as Coord * (viewport[2] - viewport[0])) as u32).max(1).min(u16::max_value() as u32) as u16,
+        ((height as Coord * (viewport[3] - viewport[1])) as u32).max(1).min(u16::max_value() as u32) as u16
+    )
+}
+
+fn make_board_bounds(level: &Level) -> (Point2I, Point2I) {
+    let (width, height) = level.get_size();
+    (Point2I::new(0, 0), Point2I::new(width, height))
+}
+
+//a port's name drawn just above its tile, the hud adds its value once it has one
+fn spawn_label(world: &mut World, location: &Point3I, name: &str) -> Entity {
+    world.create_now()
+        .with(Transform::new(
+            nalgebra::Isometry3::new(
+                nalgebra::Vector3::new(location.get_x() as GfxCoord, location.get_y() as GfxCoord + 1.0, 1.0),
+                nalgebra::Vector3::new(0.0, 0.0, 0.0)
+            ),
+            nalgebra::Vector3::new(text::LABEL_SIZE, text::LABEL_SIZE, 1.0)
+        ))
+        .with(Text::new(name, text::LABEL_COLOR, layers::TEXT))
+        .build()
+}
+
+//level ports pulse so they stand out from the wires placed around them
+fn make_port_pulse() -> Animation {
+    Animation::new(wires::PULSE_NAME, vec!(wires::RECT, wires::PULSE_RECT), wires::PULSE_FRAME_DURATION, AnimationMode::Loop)
 }
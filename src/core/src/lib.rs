@@ -16,52 +16,82 @@ extern crate utils;
 extern crate components as comps;
 extern crate graphics;
 extern crate math;
+extern crate netlist;
 
+pub mod bindings;
+pub mod capture;
+pub mod config;
 pub mod event;
 pub mod game;
+pub mod record;
+pub mod reload;
+pub mod snapshot;
 
 use std::thread;
+use std::path::{Path, PathBuf};
+use std::fs::{File};
+use std::io::{BufReader};
 
 use math::{Point2, OrthographicHelper};
 use utils::{GfxCoord, Coord};
 use graphics::{build_graphics};
 use event::{DevEventHub};
-use sys::{render, control};
+use sys::{render, control, console};
 use game::{Game};
+use record::{Recorder, Replay};
+use reload::{Reloader};
+use capture::{Capturer};
+use config::{Config};
+use bindings::{Bindings};
+use netlist::level::{Level};
 
-pub fn start() {
-    let (width, height): (u32, u32) = (640, 480);
+pub const REPLAY_TICK: ::utils::Delta = 1.0 / 60.0;
+//ticks a loaded save runs before it is drawn
+pub const RENDER_SAVE_TICKS: u64 = 120;
 
-    let fov = 90.0;
+pub fn start(record: Option<&Path>, level: Option<&Path>, mut config: Config, bindings: Bindings) {
+    let level = level.map(load_level);
 
-    let znear = 0.0;
-
-    let zfar = 10.0;
+    let (width, height) = config.get_size();
 
     let aspect_ratio = width as GfxCoord / height as GfxCoord;
 
-    let ortho_helper = OrthographicHelper::new(aspect_ratio, fov, znear, zfar);
+    let ortho_helper = OrthographicHelper::new(aspect_ratio, config.get_fov(), config.get_znear(), config.get_zfar());
 
-    let ((mut out_color, mut out_depth), mut factory, encoder, window, mut device) = build_graphics(640, 480);
+    let ((mut out_color, mut out_depth), mut factory, encoder, window, mut device) = build_graphics(width, height, config.get_title(), config.get_vsync());
 
     let (mut event_dev, game_event) = DevEventHub::new();
+    event_dev.set_bindings(bindings);
 
     event_dev.send_to_render(render::RecvEvent::GraphicsData(out_color.clone(), out_depth.clone()));
 
     event_dev.send_to_render(render::RecvEvent::Encoder(encoder.clone_empty()));
     event_dev.send_to_render(render::RecvEvent::Encoder(encoder));
 
-    let game = Game::new(
+    let mut reloader = Reloader::new();
+    let mut capturer = Capturer::new();
+
+    let mut game = Game::new(
         &mut factory,
+        &mut reloader,
         game_event,
         Point2::new(0.0, 0.0),
         Point2::new(
             out_color.get_dimensions().0 as Coord,
             out_color.get_dimensions().1 as Coord
         ),
-        ortho_helper
+        ortho_helper,
+        config.get_move_speed(),
+        level
     );
 
+    if let Some(path) = record {
+        game.set_fixed_delta(REPLAY_TICK);
+        game.start_recording(Recorder::new(path, REPLAY_TICK, width, height));
+    }
+
+    let split_target = game.get_split_target();
+
     thread::spawn(|| {
         let mut game = game;
         while game.frame() {}
@@ -74,11 +104,13 @@ pub fn start() {
 
                 for event in window.poll_events() {
                     match event {
-                        glutin::Event::KeyboardInput(_, _, Some(glutin::VirtualKeyCode::Escape)) |
                         glutin::Event::Closed => break 'main,
                         _ => event_dev.process_glutin(event),
                     }
                 }
+                if event_dev.take_quit() {
+                    break 'main;
+                }
 
                 encoder.flush(&mut device);
                 event_dev.send_to_render(render::RecvEvent::Encoder(encoder));
@@ -87,6 +119,9 @@ pub fn start() {
                     Err(err) => panic!("window swap buffers error: {}", err),
                 };
                 device.cleanup();
+
+                reloader.poll(&mut factory, &mut event_dev);
+                capturer.poll(&mut event_dev);
             },
             render::SendEvent::Exited => panic!("render system has exited while in main loop"),
         }
@@ -96,12 +131,40 @@ pub fn start() {
                 control::SendEvent::Resize => {
                     gfx_window_glutin::update_views(&window, &mut out_color, &mut out_depth);
                     event_dev.send_to_render(render::RecvEvent::GraphicsData(out_color.clone(), out_depth.clone()));
+                    let (width, height, _, _) = out_color.get_dimensions();
+                    config.set_size(width as u32, height as u32);
+
+                    //the split view keeps drawing at the size it shows at
+                    if let Some(split_target) = split_target {
+                        let size = game::make_split_target_size(width as u32, height as u32);
+                        let (texture, target_color, target_depth) = render::make_camera_target(&mut factory, size);
+                        event_dev.send_to_render(render::RecvEvent::ResizeCameraTarget(split_target, size, texture, target_color, target_depth));
+                    }
                 },
                 control::SendEvent::Exited => panic!("control system has exited while in main loop"),
             },
             None => (),
         }
 
+        match event_dev.try_recv_from_console() {
+            Some(console::SendEvent::Quit) => break 'main,
+            Some(console::SendEvent::Config(key, value)) => {
+                let result = match value {
+                    Some(value) => config.set(&key, &value).map(|()| {
+                        config.save();
+                        format!("{} = {}, takes effect on the next start", key, value)
+                    }),
+                    None => config.get(&key).map(|value| format!("{} = {}", key, value)).ok_or(format!("unknown setting {}", key)),
+                };
+                event_dev.send_to_console(console::RecvEvent::Reply(result));
+            },
+            Some(console::SendEvent::Screenshot(path)) => capturer.screenshot(&mut event_dev, path.map(PathBuf::from)),
+            Some(console::SendEvent::ExportBoard(path, rect, pixels_per_tile)) => {
+                capturer.export_board(&mut event_dev, PathBuf::from(path), rect, pixels_per_tile);
+            },
+            None => (),
+        }
+
         while match event_dev.try_recv_from_game() {
             Some(event) => match event {
                 game::SendEvent::Exited => panic!("game exited while in main loop"),
@@ -112,10 +175,22 @@ pub fn start() {
         }
     }
 
+    config.save();
+
     event_dev.send_to_render(render::RecvEvent::Exit);
     event_dev.send_to_control(control::RecvEvent::Exit);
+    event_dev.send_to_console(console::RecvEvent::Exit);
     event_dev.send_to_game(game::RecvEvent::Exit);
 
+    //the game finishes the recording on its own thread
+    if record.is_some() {
+        while match event_dev.recv_from_game() {
+            game::SendEvent::Exited => false,
+        } {
+
+        }
+    }
+
     // while match try!(event_dev.recv_from_render()) {
     //     ::sys::render::SendEvent::Exited => false,
     //     _ => true,
@@ -137,3 +212,105 @@ pub fn start() {
     //
     // }
 }
+
+//feeds a recorded session back through the game without a window and writes the final world,
+//optionally drawing it as an svg circuit diagram too
+pub fn replay(path: &Path, snapshot_path: &Path, svg: Option<(&Path, bool)>, config: &Config) {
+    let mut replay = Replay::load(path);
+
+    //the recorded size, so the mouse lands where it did while recording
+    let (width, height) = replay.get_size();
+
+    let ortho_helper = OrthographicHelper::new(width as GfxCoord / height as GfxCoord, config.get_fov(), config.get_znear(), config.get_zfar());
+
+    let (mut event_dev, game_event) = DevEventHub::new();
+
+    let mut game = Game::new_headless(
+        game_event,
+        Point2::new(0.0, 0.0),
+        Point2::new(width as Coord, height as Coord),
+        ortho_helper,
+        None
+    );
+
+    game.set_fixed_delta(replay.get_tick());
+
+    //each tick's events are queued before the tick so the game applies them just as it did while recording
+    for tick in 0..replay.get_frames() {
+        for event in replay.take_events(tick) {
+            event_dev.send_to_game(event);
+        }
+        if !game.frame() {
+            break;
+        }
+    }
+
+    game.write_snapshot(snapshot_path);
+
+    if let Some((svg_path, values)) = svg {
+        game.write_svg(svg_path, values);
+    }
+}
+
+//draws a save file without a window, as an svg circuit diagram and as a board image from the software renderer,
+//once the links have connected and the values have had time to settle
+pub fn render_save(path: &Path, svg: Option<(&Path, bool)>, png: Option<&Path>, config: &Config) -> Result<(), String> {
+    let (width, height) = config.get_size();
+
+    let ortho_helper = OrthographicHelper::new(width as GfxCoord / height as GfxCoord, config.get_fov(), config.get_znear(), config.get_zfar());
+
+    let (mut event_dev, game_event) = DevEventHub::new();
+
+    let mut game = Game::new_software(
+        game_event,
+        Point2::new(width as Coord, height as Coord),
+        ortho_helper,
+        None
+    );
+
+    game.set_fixed_delta(REPLAY_TICK);
+    try!(game.load_save(path));
+
+    for _ in 0..RENDER_SAVE_TICKS {
+        game.frame();
+    }
+
+    if let Some((svg_path, values)) = svg {
+        game.write_svg(svg_path, values);
+    }
+
+    if let Some(png_path) = png {
+        let rect = try!(game.get_board_rect().ok_or(format!("{} has nothing on the board", path.display())));
+
+        //fewer pixels per tile for boards that would be past the export limit
+        let tiles = (rect.get_top_right().get_x() - rect.get_bot_left().get_x()).max(rect.get_top_right().get_y() - rect.get_bot_left().get_y()) as u32;
+        let pixels_per_tile = art::camera::EXPORT_PIXELS_PER_TILE.min(art::camera::EXPORT_MAX_SIZE / tiles.max(1));
+        if pixels_per_tile == 0 {
+            return Err(format!("{} is too big for a board image", path.display()));
+        }
+
+        //the region is drawn a tile a tick, so ticks run until it is saved
+        let mut capturer = Capturer::new();
+        capturer.export_board(&mut event_dev, png_path.to_path_buf(), rect, pixels_per_tile);
+        while capturer.is_busy() {
+            if !game.frame() {
+                break;
+            }
+            capturer.poll(&mut event_dev);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn load_level(path: &Path) -> Level {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => panic!("level open file error: {}", err),
+    };
+
+    match Level::read(BufReader::new(file)) {
+        Ok(level) => level,
+        Err(err) => panic!("level {} error: {}", path.display(), err),
+    }
+}
@@ -0,0 +1,291 @@
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::fs::{File};
+use std::path::{Path, PathBuf};
+
+use glutin::MouseButton;
+
+//*************************************************************************************************
+
+use sys::{console, control};
+use sys::control::{Action};
+
+use game::{RecvEvent};
+
+use utils::{Delta};
+
+//*************************************************************************************************
+
+const HEADER: &'static str = "inception-replay 2";
+
+#[derive(Debug)]
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn new<P>(path: P, tick: Delta, width: u32, height: u32) -> Recorder
+    where P: AsRef<Path>
+    {
+        let file = match File::create(path) {
+            Ok(file) => file,
+            Err(err) => panic!("recorder create file error: {}", err),
+        };
+
+        let mut recorder = Recorder {
+            writer: BufWriter::new(file),
+        };
+
+        recorder.write_line(&format!("{}\ntick {}\nsize {} {}", HEADER, tick, width, height));
+
+        recorder
+    }
+
+    //stamped with the tick the event is applied before
+    pub fn record(&mut self, tick: u64, event: &RecvEvent) {
+        if let Some(line) = encode(event) {
+            let line = format!("{} {}", tick, line);
+            self.write_line(&line);
+        }
+    }
+
+    pub fn finish(&mut self, ticks: u64) {
+        let line = format!("end {}", ticks);
+        self.write_line(&line);
+        match self.writer.flush() {
+            Ok(()) => (),
+            Err(err) => error!("recorder flush error: {}", err),
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        match writeln!(self.writer, "{}", line) {
+            Ok(()) => (),
+            Err(err) => error!("recorder write error: {}", err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Replay {
+    tick: Delta,
+    size: (u32, u32),
+    frames: u64,
+    events: Vec<(u64, RecvEvent)>,
+}
+
+impl Replay {
+    pub fn load<P>(path: P) -> Replay
+    where P: AsRef<Path>
+    {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => panic!("replay open file error: {}", err),
+        };
+
+        let mut replay = Replay {
+            tick: 1.0 / 60.0,
+            size: (640, 480),
+            frames: 0,
+            events: vec!(),
+        };
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => panic!("replay read line error: {}", err),
+            };
+
+            if index == 0 {
+                //older versions stamped main loop frames instead of ticks, so their events land out of step
+                if line.trim().starts_with("inception-replay ") && line.trim() != HEADER {
+                    panic!("replay file starts with {}, it was recorded by an older version, re-record it", line.trim());
+                }
+                if line.trim() != HEADER {
+                    panic!("replay file does not start with {}", HEADER);
+                }
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice().first() {
+                None => (),
+                Some(&"tick") => replay.tick = parse_word(&words, 1, index),
+                Some(&"size") => replay.size = (parse_word(&words, 1, index), parse_word(&words, 2, index)),
+                Some(&"end") => replay.frames = parse_word(&words, 1, index),
+                Some(_) => {
+                    let frame: u64 = parse_word(&words, 0, index);
+                    let event = line.trim_left().splitn(2, char::is_whitespace).nth(1).unwrap_or("");
+                    match decode(event) {
+                        Some(event) => {
+                            replay.events.push((frame, event));
+                            if frame + 1 > replay.frames {
+                                replay.frames = frame + 1;
+                            }
+                        },
+                        None => panic!("replay line {} has an unknown event: {}", index + 1, line),
+                    }
+                },
+            }
+        }
+
+        replay
+    }
+
+    pub fn get_tick(&self) -> Delta {
+        self.tick
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn get_frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn take_events(&mut self, frame: u64) -> Vec<RecvEvent> {
+        let count = self.events.iter().take_while(|&&(event_frame, _)| event_frame <= frame).count();
+        self.events.drain(..count).map(|(_, event)| event).collect()
+    }
+}
+
+fn parse_word<T>(words: &[&str], index: usize, line: usize) -> T
+where T: ::std::str::FromStr
+{
+    match words.get(index).and_then(|word| word.parse().ok()) {
+        Some(value) => value,
+        None => panic!("replay line {} is malformed", line + 1),
+    }
+}
+
+//one line per event without its tick, none for events that are never recorded
+pub fn encode(event: &RecvEvent) -> Option<String> {
+    match *event {
+        RecvEvent::Control(ref event) => encode_control(event),
+        RecvEvent::Console(ref event) => encode_console(event),
+        RecvEvent::ExportVerilog(ref path) => Some(format!("export_verilog {}", path.display())),
+        RecvEvent::Import(ref path) => Some(format!("import {}", path.display())),
+        RecvEvent::Exit => None,
+    }
+}
+
+fn encode_control(event: &control::RecvEvent) -> Option<String> {
+    use sys::control::RecvEvent;
+
+    let bool_word = |value: bool| if value { 1 } else { 0 };
+
+    match *event {
+        RecvEvent::Right(pressed) => Some(format!("right {}", bool_word(pressed))),
+        RecvEvent::Left(pressed) => Some(format!("left {}", bool_word(pressed))),
+        RecvEvent::Up(pressed) => Some(format!("up {}", bool_word(pressed))),
+        RecvEvent::Down(pressed) => Some(format!("down {}", bool_word(pressed))),
+        RecvEvent::Resize(width, height) => Some(format!("resize {} {}", width, height)),
+        RecvEvent::MouseMoved(x, y) => Some(format!("mouse_moved {} {}", x, y)),
+        RecvEvent::MouseInput(pressed, button) => Some(format!("mouse_input {} {}", bool_word(pressed), match button {
+            MouseButton::Left => "left".to_string(),
+            MouseButton::Right => "right".to_string(),
+            MouseButton::Middle => "middle".to_string(),
+            MouseButton::Other(other) => format!("{}", other),
+        })),
+        RecvEvent::Action(action, pressed) => Some(format!("action {} {}", action.get_name(), bool_word(pressed))),
+        RecvEvent::Zoom(lines) => Some(format!("zoom {}", lines)),
+        RecvEvent::Focused(focused) => Some(format!("focused {}", bool_word(focused))),
+        RecvEvent::Exit => None,
+    }
+}
+
+//replies only print to the console so they are left out
+fn encode_console(event: &console::RecvEvent) -> Option<String> {
+    use sys::console::RecvEvent;
+
+    match *event {
+        RecvEvent::Toggle => Some("console toggle".to_string()),
+        RecvEvent::Char(c) => Some(format!("console char {}", c as u32)),
+        RecvEvent::Backspace => Some("console backspace".to_string()),
+        RecvEvent::Submit => Some("console submit".to_string()),
+        RecvEvent::Complete => Some("console complete".to_string()),
+        RecvEvent::HistoryPrev => Some("console history_prev".to_string()),
+        RecvEvent::HistoryNext => Some("console history_next".to_string()),
+        RecvEvent::Command(ref command) => Some(format!("command {}", command)),
+        RecvEvent::Reply(_) |
+        RecvEvent::Exit => None,
+    }
+}
+
+//the inverse of encode, commands and paths take the rest of the line
+pub fn decode(line: &str) -> Option<RecvEvent> {
+    let line = line.trim();
+    let mut parts = line.splitn(2, char::is_whitespace);
+    let name = parts.next().unwrap_or("");
+    let rest = parts.next().unwrap_or("").trim_left();
+
+    match name {
+        "export_verilog" if !rest.is_empty() => Some(RecvEvent::ExportVerilog(PathBuf::from(rest))),
+        "import" if !rest.is_empty() => Some(RecvEvent::Import(PathBuf::from(rest))),
+        "command" if !rest.is_empty() => Some(RecvEvent::Console(console::RecvEvent::Command(rest.to_string()))),
+        "console" => decode_console(rest).map(RecvEvent::Console),
+        _ => {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            decode_control(&words).map(RecvEvent::Control)
+        },
+    }
+}
+
+fn decode_console(rest: &str) -> Option<console::RecvEvent> {
+    use sys::console::RecvEvent;
+
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    match words.first() {
+        Some(&"toggle") => Some(RecvEvent::Toggle),
+        Some(&"char") => words.get(1).and_then(|word| word.parse().ok()).and_then(::std::char::from_u32).map(RecvEvent::Char),
+        Some(&"backspace") => Some(RecvEvent::Backspace),
+        Some(&"submit") => Some(RecvEvent::Submit),
+        Some(&"complete") => Some(RecvEvent::Complete),
+        Some(&"history_prev") => Some(RecvEvent::HistoryPrev),
+        Some(&"history_next") => Some(RecvEvent::HistoryNext),
+        _ => None,
+    }
+}
+
+fn decode_control(words: &[&str]) -> Option<control::RecvEvent> {
+    use sys::control::RecvEvent;
+
+    let number = |index: usize| words.get(index).and_then(|word| word.parse::<u32>().ok());
+    let flag = |index: usize| number(index).map(|value| value != 0);
+    let signed = |index: usize| words.get(index).and_then(|word| word.parse::<i32>().ok());
+
+    match words.first() {
+        Some(&"right") => flag(1).map(RecvEvent::Right),
+        Some(&"left") => flag(1).map(RecvEvent::Left),
+        Some(&"up") => flag(1).map(RecvEvent::Up),
+        Some(&"down") => flag(1).map(RecvEvent::Down),
+        Some(&"resize") => match (number(1), number(2)) {
+            (Some(width), Some(height)) => Some(RecvEvent::Resize(width, height)),
+            _ => None,
+        },
+        Some(&"mouse_moved") => match (signed(1), signed(2)) {
+            (Some(x), Some(y)) => Some(RecvEvent::MouseMoved(x, y)),
+            _ => None,
+        },
+        Some(&"mouse_input") => {
+            let button = match words.get(2) {
+                Some(&"left") => MouseButton::Left,
+                Some(&"right") => MouseButton::Right,
+                Some(&"middle") => MouseButton::Middle,
+                Some(other) => match other.parse() {
+                    Ok(other) => MouseButton::Other(other),
+                    Err(_) => return None,
+                },
+                None => return None,
+            };
+            flag(1).map(|pressed| RecvEvent::MouseInput(pressed, button))
+        },
+        Some(&"action") => match (words.get(1).and_then(|name| Action::from_name(name)), flag(2)) {
+            (Some(action), Some(pressed)) => Some(RecvEvent::Action(action, pressed)),
+            _ => None,
+        },
+        Some(&"zoom") => words.get(1).and_then(|word| word.parse().ok()).map(RecvEvent::Zoom),
+        Some(&"focused") => flag(1).map(RecvEvent::Focused),
+        _ => None,
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use gfx::state::{Rasterizer};
+
+use gfx_device_gl::Factory as GLFactory;
+
+use time::{precise_time_ns};
+
+use find_folder::Search;
+
+//*************************************************************************************************
+
+use comps::{RenderId};
+
+use sys::{render};
+
+use graphics::{Shaders, try_load_texture, find_shader_folder, spritesheet, grid};
+use graphics::watch::{Watcher};
+
+use event::{DevEventHub};
+
+//*************************************************************************************************
+
+const POLL_INTERVAL_NS: u64 = 500_000_000;
+
+//remembers where every render bundle came from so edited textures and shaders can be rebuilt in place
+#[derive(Debug)]
+pub struct Reloader {
+    watcher: Watcher,
+    textures: Vec<(RenderId, PathBuf)>,
+    spritesheets: Vec<(RenderId, Rasterizer)>,
+    grid: bool,
+    last_poll: u64,
+}
+
+impl Reloader {
+    pub fn new() -> Reloader {
+        let mut watcher = Watcher::new();
+        //with no folder the embedded shaders are in use and there is nothing to watch
+        if let Some(folder) = find_shader_folder() {
+            watcher.watch_folder(folder);
+        }
+        //everything under assets is watched so textures swapped in by an editor are still seen
+        match Search::ParentsThenKids(3, 3).for_folder("assets") {
+            Ok(folder) => watcher.watch_folder(folder),
+            Err(err) => warn!("reloader assets folder error: {}", err),
+        }
+
+        Reloader {
+            watcher: watcher,
+            textures: vec!(),
+            spritesheets: vec!(),
+            grid: false,
+            last_poll: precise_time_ns(),
+        }
+    }
+
+    pub fn add_texture<P>(&mut self, render_id: RenderId, path: P)
+    where P: AsRef<Path>
+    {
+        self.watcher.watch(path.as_ref());
+        self.textures.push((render_id, path.as_ref().to_path_buf()));
+    }
+
+    pub fn add_spritesheet(&mut self, render_id: RenderId, rasterizer: Rasterizer) {
+        self.spritesheets.push((render_id, rasterizer));
+    }
+
+    pub fn add_grid(&mut self) {
+        self.grid = true;
+    }
+
+    //called from the main loop, only touches the disk every poll interval
+    pub fn poll(&mut self, factory: &mut GLFactory, event_dev: &mut DevEventHub) {
+        let now = precise_time_ns();
+        if now - self.last_poll < POLL_INTERVAL_NS {
+            return;
+        }
+        self.last_poll = now;
+
+        //a save can touch both halves of a shader pair in one poll, each pipeline is only rebuilt once
+        let mut spritesheet_changed = false;
+        let mut grid_changed = false;
+
+        for path in self.watcher.poll() {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or(String::new());
+
+            if name == spritesheet::VERTEX_SHADER || name == spritesheet::FRAGMENT_SHADER {
+                spritesheet_changed = true;
+            } else if name == grid::VERTEX_SHADER || name == grid::FRAGMENT_SHADER {
+                grid_changed = true;
+            }
+
+            for &(render_id, ref texture_path) in self.textures.iter().filter(|&&(_, ref texture_path)| *texture_path == path) {
+                match try_load_texture(factory, texture_path) {
+                    Ok((texture, canvas)) => {
+                        info!("reloaded texture {}", texture_path.display());
+                        event_dev.send_to_render(render::RecvEvent::ReloadTexture(render_id, texture, canvas));
+                    },
+                    Err(err) => error!("reload texture {} error: {}", texture_path.display(), err),
+                }
+            }
+        }
+
+        if spritesheet_changed {
+            self.reload_spritesheet_pipelines(factory, event_dev);
+        }
+        if grid_changed {
+            self.reload_grid_pipeline(factory, event_dev);
+        }
+    }
+
+    fn reload_spritesheet_pipelines(&self, factory: &mut GLFactory, event_dev: &mut DevEventHub) {
+        let shaders = match Shaders::try_new(spritesheet::VERTEX_SHADER, spritesheet::FRAGMENT_SHADER) {
+            Ok(shaders) => shaders,
+            Err(err) => {
+                error!("reload spritesheet shaders error: {}", err);
+                return;
+            },
+        };
+
+        for &(render_id, rasterizer) in &self.spritesheets {
+            match spritesheet::make_pipeline(factory, &shaders, rasterizer) {
+                Ok(pso) => event_dev.send_to_render(render::RecvEvent::ReloadSpritesheetPipeline(render_id, pso)),
+                Err(err) => {
+                    //every bundle shares the shaders so the rest would fail the same way
+                    error!("reload spritesheet pipeline error: {}", err);
+                    return;
+                },
+            }
+        }
+        info!("reloaded spritesheet shaders");
+    }
+
+    fn reload_grid_pipeline(&self, factory: &mut GLFactory, event_dev: &mut DevEventHub) {
+        if !self.grid {
+            return;
+        }
+
+        let shaders = match Shaders::try_new(grid::VERTEX_SHADER, grid::FRAGMENT_SHADER) {
+            Ok(shaders) => shaders,
+            Err(err) => {
+                error!("reload grid shaders error: {}", err);
+                return;
+            },
+        };
+
+        match grid::make_pipeline(factory, &shaders) {
+            Ok(pso) => {
+                info!("reloaded grid shaders");
+                event_dev.send_to_render(render::RecvEvent::ReloadGridPipeline(pso));
+            },
+            Err(err) => error!("reload grid pipeline error: {}", err),
+        }
+    }
+}
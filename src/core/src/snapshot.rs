@@ -0,0 +1,73 @@
+use std::io::{self, Write};
+
+use specs::{World};
+
+//*************************************************************************************************
+
+use comps::{Camera, Wire, WireIn, WireOut, Gate};
+use comps::non_components::{Link, Selection};
+
+use math::{Point3I};
+
+//*************************************************************************************************
+
+//writes a sorted text dump of the world so two runs can be compared with a plain diff
+pub fn write_world<W: Write>(world: &World, writer: &mut W) -> io::Result<()> {
+    use specs::Join;
+
+    let cameras = world.read::<Camera>();
+    let wires = world.read::<Wire>();
+    let wires_in = world.read::<WireIn>();
+    let wires_out = world.read::<WireOut>();
+    let gates = world.read::<Gate>();
+    let selection = world.read_resource::<Selection>();
+
+    let mut lines = vec!();
+
+    for camera in (&cameras).iter() {
+        let offset = camera.get_offset();
+        lines.push(format!("camera {} {} {}", camera.is_main(), offset.get_x(), offset.get_y()));
+    }
+
+    if let Some(cell) = selection.get_cell() {
+        lines.push(format!("selected {} {}", cell.get_x(), cell.get_y()));
+    }
+
+    for wire in (&wires).iter() {
+        lines.push(format!("wire {} {} {} {}", point(wire.get_me()), point(wire.get_input()), point(wire.get_output()), wire.get_value()));
+    }
+
+    for wire_in in (&wires_in).iter() {
+        lines.push(format!("wire_in {} {} {} {}", point(wire_in.get_me()), points(wire_in.get_outputs()), wire_in.get_value(), wire_in.get_name().unwrap_or("-")));
+    }
+
+    for wire_out in (&wires_out).iter() {
+        lines.push(format!("wire_out {} {} {} {}", point(wire_out.get_me()), point(wire_out.get_input()), wire_out.get_value(), wire_out.get_name().unwrap_or("-")));
+    }
+
+    for gate in (&gates).iter() {
+        let inputs: Vec<String> = gate.get_inputs().iter().map(point).collect();
+        lines.push(format!("gate {} {} {} [{}] {}", gate.get_kind().get_name(), point(gate.get_me()), points(gate.get_outputs()), inputs.join(" "), gate.get_value()));
+    }
+
+    lines.sort();
+
+    for line in lines {
+        try!(writeln!(writer, "{}", line));
+    }
+
+    Ok(())
+}
+
+fn points(links: &[Link]) -> String {
+    let points: Vec<String> = links.iter().map(point).collect();
+    points.join(";")
+}
+
+fn point(link: &Link) -> String {
+    if link.is_floating() {
+        return "none".to_string();
+    }
+    let location: &Point3I = link.get_slow();
+    format!("{},{},{}", location.get_x(), location.get_y(), location.get_z())
+}
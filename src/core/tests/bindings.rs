@@ -0,0 +1,131 @@
+extern crate core;
+extern crate glutin;
+extern crate systems;
+
+use glutin::{VirtualKeyCode, MouseButton};
+
+use core::bindings::{Action, Bindings, Modifiers, find_scan_code};
+
+use systems::control;
+
+//*************************************************************************************************
+
+//no physical key sends this on any platform we map
+const UNUSED_SCAN: u8 = 200;
+
+fn read_ok(source: &str) -> Bindings {
+    match Bindings::read(source.as_bytes()) {
+        Ok(bindings) => bindings,
+        Err(err) => panic!("read failed: {}", err),
+    }
+}
+
+fn read_err(source: &str) -> String {
+    match Bindings::read(source.as_bytes()) {
+        Ok(_) => panic!("read of\n{}\nshould have failed", source),
+        Err(err) => err,
+    }
+}
+
+fn none() -> Modifiers {
+    Modifiers::default()
+}
+
+fn ctrl() -> Modifiers {
+    Modifiers {
+        ctrl: true,
+        .. Modifiers::default()
+    }
+}
+
+#[test]
+fn default_panning_follows_the_physical_keys() {
+    let bindings = Bindings::new();
+    let w = find_scan_code("W").expect("W has no scan code");
+
+    //azerty prints Z where a us layout prints W
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Z), w, none()), vec!(Action::PanUp));
+    assert_eq!(bindings.find(Some(VirtualKeyCode::W), UNUSED_SCAN, none()), vec!());
+    assert_eq!(bindings.find(None, find_scan_code("d").expect("D has no scan code"), none()), vec!(Action::PanRight));
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Up), UNUSED_SCAN, none()), vec!(Action::PanUp));
+}
+
+#[test]
+fn default_shortcuts_follow_the_printed_letter() {
+    let bindings = Bindings::new();
+
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Z), UNUSED_SCAN, ctrl()), vec!(Action::Control(control::Action::Undo)));
+    assert_eq!(bindings.find(Some(VirtualKeyCode::R), UNUSED_SCAN, none()), vec!(Action::Control(control::Action::Rotate)));
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Escape), UNUSED_SCAN, none()), vec!(Action::Quit));
+}
+
+#[test]
+fn modifiers_must_match_exactly() {
+    let bindings = Bindings::new();
+    let ctrl_shift = Modifiers {
+        ctrl: true,
+        shift: true,
+        alt: false,
+    };
+
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Z), UNUSED_SCAN, none()), vec!());
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Z), UNUSED_SCAN, ctrl_shift), vec!(Action::Control(control::Action::Redo)));
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Escape), UNUSED_SCAN, ctrl()), vec!());
+}
+
+#[test]
+fn default_mouse_buttons() {
+    let bindings = Bindings::new();
+
+    assert_eq!(bindings.find_mouse(MouseButton::Left, none()), vec!(Action::Control(control::Action::Select)));
+    assert_eq!(bindings.find_mouse(MouseButton::Right, none()), vec!(Action::Control(control::Action::Route)));
+    assert_eq!(bindings.find_mouse(MouseButton::Left, ctrl()), vec!());
+}
+
+#[test]
+fn reads_every_kind_of_input() {
+    let bindings = read_ok("
+# comments and blank lines are skipped
+
+place = ctrl+shift+alt+F1
+erase = Mouse 4
+rotate = Scan 57
+verify = Scan grave
+quit = left
+quit = Mouse Middle
+");
+    let all = Modifiers {
+        ctrl: true,
+        shift: true,
+        alt: true,
+    };
+
+    assert_eq!(bindings.find(Some(VirtualKeyCode::F1), UNUSED_SCAN, all), vec!(Action::Control(control::Action::Place)));
+    assert_eq!(bindings.find_mouse(MouseButton::Other(4), none()), vec!(Action::Control(control::Action::Erase)));
+    assert_eq!(bindings.find(None, 57, none()), vec!(Action::Control(control::Action::Rotate)));
+    assert_eq!(bindings.find(Some(VirtualKeyCode::Left), UNUSED_SCAN, none()), vec!(Action::Quit));
+    assert_eq!(bindings.find_mouse(MouseButton::Middle, none()), vec!(Action::Quit));
+    assert_eq!(bindings.find(None, find_scan_code("Grave").expect("Grave has no scan code"), none()), vec!(Action::Verify));
+}
+
+#[test]
+fn one_input_can_do_several_actions() {
+    let bindings = read_ok("screenshot = F12\nverify = F12\n");
+
+    assert_eq!(bindings.find(Some(VirtualKeyCode::F12), UNUSED_SCAN, none()), vec!(Action::Screenshot, Action::Verify));
+}
+
+#[test]
+fn errors_report_their_line() {
+    assert!(read_err("quit = Escape\nquit Escape\n").contains("line 2"));
+    assert!(read_err("\njump = Space\n").contains("line 2"));
+    assert!(read_err("quit = Escape\n\nquit = Hyper+Escape\n").contains("line 3"));
+}
+
+#[test]
+fn bad_inputs_are_refused() {
+    for input in &["Keypad9", "Scan", "Scan 300", "Scan Tab", "Mouse Fourth", "ctrl+", "Mouse Left Right"] {
+        let err = read_err(&format!("quit = {}\n", input));
+        assert!(err.contains("line 1"), "{} gave {}", input, err);
+    }
+}
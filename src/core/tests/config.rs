@@ -0,0 +1,181 @@
+extern crate core;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use core::config::{Config, KEYS};
+
+//*************************************************************************************************
+
+fn make_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("inception-config-{}", name))
+}
+
+fn read_ok(source: &str) -> Config {
+    match Config::read(source.as_bytes()) {
+        Ok(config) => config,
+        Err(err) => panic!("read failed: {}", err),
+    }
+}
+
+fn write_string(config: &Config) -> String {
+    let mut bytes = vec!();
+    config.write(&mut bytes).expect("write failed");
+    String::from_utf8(bytes).expect("write was not utf8")
+}
+
+fn read_file(path: &Path) -> String {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut file| file.read_to_string(&mut contents)).expect("read file failed");
+    contents
+}
+
+#[test]
+fn read_takes_settings_and_keeps_defaults() {
+    let config = read_ok("# window\n\nwidth = 800\n  title =  Half Adder  \nvsync = off\nmove_speed = 4 6\n");
+
+    assert_eq!(config.get_size(), (800, 480));
+    assert_eq!(config.get_title(), "Half Adder");
+    assert!(!config.get_vsync());
+    assert_eq!(config.get("move_speed"), Some("4 6".to_string()));
+    assert_eq!(config.get_fov(), Config::new().get_fov());
+}
+
+#[test]
+fn read_reports_the_bad_line() {
+    let err = Config::read("width = 800\nheight\n".as_bytes()).err().expect("read should have failed");
+    assert!(err.contains("line 2"), "error was {}", err);
+
+    let err = Config::read("width = 800\n\nvsync = maybe\n".as_bytes()).err().expect("read should have failed");
+    assert!(err.contains("line 3") && err.contains("vsync"), "error was {}", err);
+
+    assert!(Config::read("height = 0\n".as_bytes()).is_err());
+}
+
+#[test]
+fn read_skips_unknown_settings() {
+    let config = read_ok("width = 800\nfullscreen = on\nheight = 600\n");
+
+    assert_eq!(config.get_size(), (800, 600));
+    assert_eq!(config.get("fullscreen"), None);
+    //but kept for the version that knows them
+    assert!(write_string(&config).contains("fullscreen = on\n"));
+}
+
+#[test]
+fn write_keeps_the_file_and_replaces_only_changed_settings() {
+    let source = "# my window\nwidth=800\n\nfullscreen = on\n  vsync = on  \n";
+    let mut config = read_ok(source);
+    assert_eq!(write_string(&config), source);
+
+    config.set("vsync", "off").expect("set failed");
+    config.set("fov", "60").expect("set failed");
+    assert_eq!(write_string(&config), "# my window\nwidth=800\n\nfullscreen = on\nvsync = off\nfov = 60\n");
+}
+
+#[test]
+fn write_lists_every_setting_and_reads_back() {
+    let mut config = Config::new();
+    config.set("title", "Explore Two").expect("set failed");
+    config.set("move_speed", "3").expect("set failed");
+    config.set_size(1024, 768);
+
+    let written = write_string(&config);
+    for key in KEYS {
+        assert!(written.lines().any(|line| line.starts_with(&format!("{} = ", key))), "{} missing from\n{}", key, written);
+    }
+
+    let read = read_ok(&written);
+    for key in KEYS {
+        assert_eq!(read.get(key), config.get(key), "{}", key);
+    }
+}
+
+#[test]
+fn overrides_apply_without_being_written() {
+    let mut config = read_ok("width = 800\nvsync = on\n");
+
+    config.set_override("width", "1280").expect("override failed");
+    config.set_override("vsync", "off").expect("override failed");
+    config.set_override("width", "1920").expect("override failed");
+    assert_eq!(config.get_size(), (1920, 480));
+    assert!(!config.get_vsync());
+
+    let written = write_string(&config);
+    assert!(written.contains("width = 800\n"), "written was\n{}", written);
+    assert!(written.contains("vsync = on\n"), "written was\n{}", written);
+
+    //changing an overridden setting in game keeps the new value
+    config.set("vsync", "off").expect("set failed");
+    config.set_size(1000, 700);
+    let written = write_string(&config);
+    assert!(written.contains("vsync = off\n"), "written was\n{}", written);
+    assert!(written.contains("width = 1000\n"), "written was\n{}", written);
+}
+
+#[test]
+fn bad_overrides_are_refused() {
+    let mut config = Config::new();
+
+    assert!(config.set_override("height", "tall").is_err());
+    assert!(config.set_override("colour", "red").is_err());
+    assert_eq!(config.get_size(), (640, 480));
+}
+
+#[test]
+fn load_saves_back_to_its_path() {
+    let path = make_path("save");
+    let _ = fs::remove_file(&path);
+
+    let mut config = Config::load(&path);
+    config.save();
+    assert!(!path.exists(), "an unchanged config should not be saved");
+
+    config.set("fov", "60").expect("set failed");
+    config.save();
+    assert_eq!(read_ok(&read_file(&path)).get_fov(), 60.0);
+
+    fs::remove_file(&path).expect("remove failed");
+}
+
+#[test]
+fn overrides_and_unchanged_sizes_do_not_save() {
+    let path = make_path("override");
+    {
+        let mut file = File::create(&path).expect("create failed");
+        write!(file, "# mine\nwidth=800\nheight=600\n").expect("write failed");
+    }
+
+    let mut config = Config::load(&path);
+    config.set_override("width", "1280").expect("override failed");
+    config.set_override("vsync", "off").expect("override failed");
+    config.set_size(1280, 600);
+    config.save();
+    assert_eq!(read_file(&path), "# mine\nwidth=800\nheight=600\n");
+
+    //a real resize only rewrites the size
+    config.set_size(1024, 768);
+    config.save();
+    assert_eq!(read_file(&path), "# mine\nwidth = 1024\nheight = 768\n");
+
+    fs::remove_file(&path).expect("remove failed");
+}
+
+#[test]
+fn a_file_that_fails_to_read_is_never_saved_over() {
+    let path = make_path("broken");
+    {
+        let mut file = File::create(&path).expect("create failed");
+        write!(file, "width = wide\nfov = 75\n").expect("write failed");
+    }
+
+    let mut config = Config::load(&path);
+    assert_eq!(config.get_size(), Config::new().get_size());
+    config.set("fov", "60").expect("set failed");
+    config.save();
+
+    assert_eq!(read_file(&path), "width = wide\nfov = 75\n");
+    fs::remove_file(&path).expect("remove failed");
+}
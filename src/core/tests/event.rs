@@ -0,0 +1,62 @@
+extern crate core;
+extern crate glutin;
+extern crate systems;
+
+use std::sync::mpsc::{Receiver};
+
+use glutin::{Event, ElementState, VirtualKeyCode};
+
+use core::bindings::{Bindings};
+use core::event::{DevEventHub};
+use core::game::{RecvEvent};
+
+use systems::console;
+
+//*************************************************************************************************
+
+fn make_hub(bindings: &str) -> (DevEventHub, Receiver<RecvEvent>) {
+    let (mut event_dev, mut game_event) = DevEventHub::new();
+    let bindings = Bindings::read(bindings.as_bytes()).expect("bindings did not read");
+    event_dev.set_bindings(bindings);
+    let (_, from_dev) = game_event.game_channel.take().expect("game channel was none");
+    (event_dev, from_dev)
+}
+
+//a key press followed by the character it types, if any
+fn type_key(event_dev: &mut DevEventHub, key: VirtualKeyCode, c: Option<char>) {
+    event_dev.process_glutin(Event::KeyboardInput(ElementState::Pressed, 0, Some(key)));
+    if let Some(c) = c {
+        event_dev.process_glutin(Event::ReceivedCharacter(c));
+    }
+    event_dev.process_glutin(Event::KeyboardInput(ElementState::Released, 0, Some(key)));
+}
+
+fn typed(from_dev: &Receiver<RecvEvent>) -> String {
+    from_dev.try_iter().filter_map(|event| match event {
+        RecvEvent::Console(console::RecvEvent::Char(c)) => Some(c),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn the_toggle_key_character_is_not_typed() {
+    let (mut event_dev, from_dev) = make_hub("toggle_console = Grave\n");
+
+    type_key(&mut event_dev, VirtualKeyCode::Grave, Some('`'));
+    type_key(&mut event_dev, VirtualKeyCode::A, Some('a'));
+    //the same character from another key still reaches the console
+    event_dev.process_glutin(Event::ReceivedCharacter('`'));
+
+    assert_eq!(typed(&from_dev), "a`");
+}
+
+#[test]
+fn a_toggle_key_without_a_character_skips_nothing() {
+    let (mut event_dev, from_dev) = make_hub("toggle_console = F1\n");
+
+    type_key(&mut event_dev, VirtualKeyCode::F1, None);
+    type_key(&mut event_dev, VirtualKeyCode::A, Some('a'));
+    type_key(&mut event_dev, VirtualKeyCode::Grave, Some('`'));
+
+    assert_eq!(typed(&from_dev), "a`");
+}
@@ -0,0 +1,119 @@
+extern crate core;
+extern crate glutin;
+extern crate systems;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Write};
+use std::panic;
+use std::path::{PathBuf};
+
+use glutin::MouseButton;
+
+use core::game::{RecvEvent};
+use core::record::{Recorder, Replay, encode, decode};
+
+use systems::{console, control};
+use systems::control::{Action};
+
+//*************************************************************************************************
+
+fn make_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("inception-record-{}", name))
+}
+
+fn make_events() -> Vec<RecvEvent> {
+    vec!(
+        RecvEvent::Control(control::RecvEvent::Right(true)),
+        RecvEvent::Control(control::RecvEvent::Left(false)),
+        RecvEvent::Control(control::RecvEvent::Up(true)),
+        RecvEvent::Control(control::RecvEvent::Down(false)),
+        RecvEvent::Control(control::RecvEvent::Resize(800, 600)),
+        RecvEvent::Control(control::RecvEvent::MouseMoved(12, 34)),
+        RecvEvent::Control(control::RecvEvent::MouseMoved(-5, 700)),
+        RecvEvent::Control(control::RecvEvent::MouseInput(true, MouseButton::Left)),
+        RecvEvent::Control(control::RecvEvent::MouseInput(false, MouseButton::Other(4))),
+        RecvEvent::Control(control::RecvEvent::Action(Action::Rotate, true)),
+        RecvEvent::Control(control::RecvEvent::Zoom(-1.5)),
+        RecvEvent::Control(control::RecvEvent::Focused(false)),
+        RecvEvent::Console(console::RecvEvent::Toggle),
+        RecvEvent::Console(console::RecvEvent::Char('é')),
+        RecvEvent::Console(console::RecvEvent::Char(' ')),
+        RecvEvent::Console(console::RecvEvent::Backspace),
+        RecvEvent::Console(console::RecvEvent::Submit),
+        RecvEvent::Console(console::RecvEvent::Complete),
+        RecvEvent::Console(console::RecvEvent::HistoryPrev),
+        RecvEvent::Console(console::RecvEvent::HistoryNext),
+        RecvEvent::Console(console::RecvEvent::Command("place and 1 2".to_string())),
+        RecvEvent::ExportVerilog(PathBuf::from("circuits/half adder.v")),
+        RecvEvent::Import(PathBuf::from("adder.blif")),
+    )
+}
+
+#[test]
+fn every_recorded_event_decodes_to_itself() {
+    for event in make_events() {
+        let line = encode(&event).expect("event was not recorded");
+        assert_eq!(decode(&line), Some(event), "line was {}", line);
+    }
+}
+
+#[test]
+fn exits_and_replies_are_not_recorded() {
+    assert_eq!(encode(&RecvEvent::Exit), None);
+    assert_eq!(encode(&RecvEvent::Control(control::RecvEvent::Exit)), None);
+    assert_eq!(encode(&RecvEvent::Console(console::RecvEvent::Exit)), None);
+    assert_eq!(encode(&RecvEvent::Console(console::RecvEvent::Reply(Ok("done".to_string())))), None);
+}
+
+#[test]
+fn unknown_lines_do_not_decode() {
+    assert_eq!(decode("jump 1"), None);
+    assert_eq!(decode("right"), None);
+    assert_eq!(decode("console shout"), None);
+    assert_eq!(decode("command"), None);
+}
+
+#[test]
+fn replay_returns_events_at_the_tick_they_were_recorded() {
+    let path = make_path("ticks");
+    let events = make_events();
+
+    {
+        let mut recorder = Recorder::new(&path, 0.25, 320, 200);
+        for (index, event) in events.iter().enumerate() {
+            recorder.record(index as u64 / 2, event);
+        }
+        recorder.finish(20);
+    }
+
+    let mut replay = Replay::load(&path);
+    fs::remove_file(&path).expect("remove failed");
+
+    assert_eq!(replay.get_tick(), 0.25);
+    assert_eq!(replay.get_size(), (320, 200));
+    assert_eq!(replay.get_frames(), 20);
+    for tick in 0..replay.get_frames() {
+        let expected: Vec<RecvEvent> = events.iter().enumerate()
+            .filter(|&(index, _)| index as u64 / 2 == tick)
+            .map(|(_, event)| event.clone())
+            .collect();
+        assert_eq!(replay.take_events(tick), expected, "tick {}", tick);
+    }
+}
+
+#[test]
+fn older_recordings_are_refused() {
+    let path = make_path("v1");
+    {
+        let mut file = File::create(&path).expect("create failed");
+        write!(file, "inception-replay 1\ntick 0.5\nsize 640 480\n0 right 1\n3 mouse_input 1 right\nend 5\n").expect("write failed");
+    }
+
+    let result = panic::catch_unwind(|| Replay::load(&path));
+    fs::remove_file(&path).expect("remove failed");
+
+    let err = result.err().expect("an older recording loaded");
+    let message = err.downcast_ref::<String>().expect("panic message was not a string");
+    assert!(message.contains("recorded by an older version, re-record"), "message was {}", message);
+}
@@ -0,0 +1,77 @@
+extern crate core;
+extern crate graphics;
+extern crate math;
+extern crate netlist;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read};
+use std::path::{PathBuf};
+
+use core::config::{Config};
+
+use graphics::software::{Canvas};
+
+use math::{Point3I};
+
+use netlist::save::{Circuit, Item};
+
+//*************************************************************************************************
+
+fn make_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("inception-render-save-{}", name))
+}
+
+//an input wired across one cell to an output
+fn make_circuit() -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit.add_item(Item::WireIn(Point3I::new(0, 0, 0), vec!(Point3I::new(1, 0, 0)), Some("a".to_string())));
+    circuit.add_item(Item::Wire(Point3I::new(1, 0, 0), Point3I::new(0, 0, 0), Point3I::new(2, 0, 0)));
+    circuit.add_item(Item::WireOut(Point3I::new(2, 0, 0), Point3I::new(1, 0, 0), Some("y".to_string())));
+    circuit
+}
+
+#[test]
+fn a_save_is_drawn_without_a_window() {
+    let (save_path, svg_path, png_path) = (make_path("circuit.txt"), make_path("circuit.svg"), make_path("circuit.png"));
+    {
+        let mut file = File::create(&save_path).expect("create failed");
+        make_circuit().write(&mut file).expect("write failed");
+    }
+    let _ = fs::remove_file(&svg_path);
+    let _ = fs::remove_file(&png_path);
+
+    core::render_save(&save_path, Some((svg_path.as_path(), true)), Some(png_path.as_path()), &Config::new()).expect("render failed");
+
+    let mut svg = String::new();
+    File::open(&svg_path).and_then(|mut file| file.read_to_string(&mut svg)).expect("svg was not written");
+    assert!(svg.contains("<title>input a</title>"), "svg was\n{}", svg);
+    assert!(svg.contains("<title>output y</title>"), "svg was\n{}", svg);
+    assert!(svg.contains(">y=0</text>"), "svg was\n{}", svg);
+
+    //three cells and a cell of margin all round at the export's pixels per tile
+    let image = Canvas::load(&png_path).expect("png was not written");
+    assert_eq!((image.get_width(), image.get_height()), (5 * 32, 3 * 32));
+    assert!(image.get_pixel(80, 48) != image.get_pixel(0, 0), "the wire was not drawn over the margin");
+
+    for path in &[save_path, svg_path, png_path] {
+        fs::remove_file(path).expect("remove failed");
+    }
+}
+
+#[test]
+fn missing_or_empty_saves_are_errors() {
+    let (missing_path, empty_path) = (make_path("missing.txt"), make_path("empty.txt"));
+    let _ = fs::remove_file(&missing_path);
+    File::create(&empty_path).expect("create failed");
+
+    let missing = core::render_save(&missing_path, None, None, &Config::new());
+    let empty = core::render_save(&empty_path, None, None, &Config::new());
+    fs::remove_file(&empty_path).expect("remove failed");
+
+    assert!(missing.is_err());
+    match empty {
+        Ok(()) => panic!("an empty save rendered"),
+        Err(err) => assert!(err.contains("nothing"), "error was {}", err),
+    }
+}
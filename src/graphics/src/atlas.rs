@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap};
+use std::io::{BufRead, BufReader, Read};
+use std::fs::{File};
+use std::path::{Path};
+
+use rustc_serialize::json::{Json};
+
+//*************************************************************************************************
+
+const IMAGE_EXTENSIONS: &'static [&'static str] = &["png", "jpg", "jpeg", "gif", "bmp"];
+
+//one named sprite, rects are x y w h in pixels like the ones handed to RenderData
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    rect: [f32; 4],
+    rotated: bool,
+    source_rect: [f32; 4],
+    source_size: [f32; 2],
+}
+
+impl Frame {
+    pub fn new(rect: [f32; 4]) -> Frame {
+        Frame {
+            rect: rect,
+            rotated: false,
+            source_rect: [0.0, 0.0, rect[2], rect[3]],
+            source_size: [rect[2], rect[3]],
+        }
+    }
+
+    pub fn new_trimmed(rect: [f32; 4], rotated: bool, source_rect: [f32; 4], source_size: [f32; 2]) -> Frame {
+        Frame {
+            rect: rect,
+            rotated: rotated,
+            source_rect: source_rect,
+            source_size: source_size,
+        }
+    }
+
+    pub fn get_rect(&self) -> [f32; 4] {
+        self.rect
+    }
+
+    pub fn get_rotated(&self) -> bool {
+        self.rotated
+    }
+
+    //where the trimmed pixels sit inside the untrimmed sprite
+    pub fn get_source_rect(&self) -> [f32; 4] {
+        self.source_rect
+    }
+
+    pub fn get_source_size(&self) -> [f32; 2] {
+        self.source_size
+    }
+
+    pub fn is_trimmed(&self) -> bool {
+        self.source_rect != [0.0, 0.0, self.rect[2], self.rect[3]] || self.source_size != [self.rect[2], self.rect[3]]
+    }
+}
+
+//named frames from one spritesheet, names have any image extension removed so hud_0.png is hud_0
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    image: Option<String>,
+    size: Option<[f32; 2]>,
+    frames: BTreeMap<String, Frame>,
+}
+
+impl Atlas {
+    pub fn new() -> Atlas {
+        Atlas {
+            image: None,
+            size: None,
+            frames: BTreeMap::new(),
+        }
+    }
+
+    //picks the format from the extension: .xml, .txt or .json
+    pub fn load<P>(path: P) -> Result<Atlas, String>
+    where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => return Err(format!("atlas open {} error: {}", path.display(), err)),
+        };
+
+        let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+
+        let result = match extension.as_ref().map(|extension| extension.as_str()) {
+            Some("xml") => Atlas::from_xml(file),
+            Some("txt") => Atlas::from_txt(BufReader::new(file)),
+            Some("json") => {
+                let mut bytes = vec!();
+                match BufReader::new(file).read_to_end(&mut bytes) {
+                    Ok(_) => Atlas::from_json(&bytes),
+                    Err(err) => Err(format!("read error: {}", err)),
+                }
+            },
+            _ => Err("only .xml, .txt and .json atlases are supported".to_string()),
+        };
+
+        result.map_err(|err| format!("atlas {} error: {}", path.display(), err))
+    }
+
+    //TexturePacker xml: <SubTexture name="hud_0.png" x="230" y="0" width="30" height="38"/>
+    pub fn from_xml<R: Read>(mut reader: R) -> Result<Atlas, String> {
+        let mut text = String::new();
+        if let Err(err) = reader.read_to_string(&mut text) {
+            return Err(format!("read error: {}", err));
+        }
+
+        let mut atlas = Atlas::new();
+
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find('<') {
+            let end = match rest[start..].find('>') {
+                Some(end) => start + end,
+                None => return Err("unterminated tag".to_string()),
+            };
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+
+            let attributes = parse_attributes(tag);
+            let attribute = |name: &str| attributes.iter().find(|&&(ref key, _)| key == name).map(|&(_, ref value)| value.as_str());
+            let number = |name: &str| match attribute(name).and_then(|value| value.parse::<f32>().ok()) {
+                Some(value) => Ok(value),
+                None => Err(format!("{} needs a numeric {}", tag, name)),
+            };
+
+            if tag.starts_with("TextureAtlas") {
+                atlas.image = attribute("imagePath").map(|image| image.to_string());
+                if let (Some(width), Some(height)) = (attribute("width"), attribute("height")) {
+                    if let (Ok(width), Ok(height)) = (width.parse(), height.parse()) {
+                        atlas.size = Some([width, height]);
+                    }
+                }
+            } else if tag.starts_with("SubTexture") {
+                let name = match attribute("name") {
+                    Some(name) => name,
+                    None => return Err(format!("{} has no name", tag)),
+                };
+                let rect = [try!(number("x")), try!(number("y")), try!(number("width")), try!(number("height"))];
+                let frame = match (attribute("frameX"), attribute("frameWidth")) {
+                    (Some(_), Some(_)) => Frame::new_trimmed(
+                        rect,
+                        attribute("rotated") == Some("true"),
+                        [-try!(number("frameX")), -try!(number("frameY")), rect[2], rect[3]],
+                        [try!(number("frameWidth")), try!(number("frameHeight"))]
+                    ),
+                    _ => Frame::new(rect),
+                };
+                atlas.add_frame(name, frame);
+            }
+        }
+
+        Ok(atlas)
+    }
+
+    //one frame per line: p1_front = 0 196 66 92
+    pub fn from_txt<R: BufRead>(reader: R) -> Result<Atlas, String> {
+        let mut atlas = Atlas::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Err(format!("line {} read error: {}", index + 1, err)),
+            };
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, numbers) = match line.find('=') {
+                Some(split) => (line[..split].trim(), &line[split + 1..]),
+                None => return Err(format!("line {} has no =", index + 1)),
+            };
+
+            let numbers: Vec<f32> = match numbers.split_whitespace().map(|number| number.parse()).collect() {
+                Ok(numbers) => numbers,
+                Err(_) => return Err(format!("line {} has a bad number", index + 1)),
+            };
+            if numbers.len() != 4 {
+                return Err(format!("line {} needs x y w h", index + 1));
+            }
+
+            atlas.add_frame(name, Frame::new([numbers[0], numbers[1], numbers[2], numbers[3]]));
+        }
+
+        Ok(atlas)
+    }
+
+    //TexturePacker json hash, in utf-8 or utf-16 with a byte order mark
+    pub fn from_json(bytes: &[u8]) -> Result<Atlas, String> {
+        let text = try!(decode_text(bytes));
+
+        let json = match Json::from_str(&text) {
+            Ok(json) => json,
+            Err(err) => return Err(format!("json error: {}", err)),
+        };
+
+        let mut atlas = Atlas::new();
+
+        if let Some(meta) = json.find("meta") {
+            atlas.image = meta.find("image").and_then(|image| image.as_string()).map(|image| image.to_string());
+            if let Some(size) = meta.find("size") {
+                atlas.size = Some([try!(json_number(size, "w")), try!(json_number(size, "h"))]);
+            }
+        }
+
+        let frames = match json.find("frames").and_then(|frames| frames.as_object()) {
+            Some(frames) => frames,
+            None => return Err("json has no frames object".to_string()),
+        };
+
+        for (name, entry) in frames {
+            let rect = match entry.find("frame") {
+                Some(frame) => try!(json_rect(frame)),
+                None => return Err(format!("{} has no frame", name)),
+            };
+            let rotated = entry.find("rotated").and_then(|rotated| rotated.as_boolean()).unwrap_or(false);
+            let trimmed = entry.find("trimmed").and_then(|trimmed| trimmed.as_boolean()).unwrap_or(false);
+
+            let frame = if trimmed {
+                let source_rect = match entry.find("spriteSourceSize") {
+                    Some(source_rect) => try!(json_rect(source_rect)),
+                    None => return Err(format!("{} is trimmed without spriteSourceSize", name)),
+                };
+                let source_size = match entry.find("sourceSize") {
+                    Some(source_size) => [try!(json_number(source_size, "w")), try!(json_number(source_size, "h"))],
+                    None => return Err(format!("{} is trimmed without sourceSize", name)),
+                };
+                Frame::new_trimmed(rect, rotated, source_rect, source_size)
+            } else {
+                Frame::new_trimmed(rect, rotated, [0.0, 0.0, rect[2], rect[3]], [rect[2], rect[3]])
+            };
+
+            atlas.add_frame(name, frame);
+        }
+
+        Ok(atlas)
+    }
+
+    pub fn add_frame(&mut self, name: &str, frame: Frame) {
+        self.frames.insert(strip_image_extension(name).to_string(), frame);
+    }
+
+    pub fn set_size(&mut self, size: [f32; 2]) {
+        self.size = Some(size);
+    }
+
+    //the sheet size when the file recorded it, otherwise set it from the loaded texture
+    pub fn get_size(&self) -> Option<[f32; 2]> {
+        self.size
+    }
+
+    pub fn get_image(&self) -> Option<&str> {
+        self.image.as_ref().map(|image| image.as_str())
+    }
+
+    pub fn get_frame(&self, name: &str) -> Option<&Frame> {
+        self.frames.get(strip_image_extension(name))
+    }
+
+    pub fn get_rect(&self, name: &str) -> Option<[f32; 4]> {
+        self.get_frame(name).map(|frame| frame.get_rect())
+    }
+
+    pub fn get_frames(&self) -> &BTreeMap<String, Frame> {
+        &self.frames
+    }
+
+    //every frame whose name starts with prefix in name order, so p1_walk gives p1_walk01 to p1_walk11
+    pub fn get_sequence(&self, prefix: &str) -> Vec<(&str, &Frame)> {
+        self.frames.iter()
+            .filter(|&(name, _)| name.starts_with(prefix))
+            .map(|(name, frame)| (name.as_str(), frame))
+            .collect()
+    }
+}
+
+fn strip_image_extension(name: &str) -> &str {
+    match name.rfind('.') {
+        Some(dot) if IMAGE_EXTENSIONS.contains(&name[dot + 1..].to_lowercase().as_str()) => &name[..dot],
+        _ => name,
+    }
+}
+
+//key="value" pairs from the inside of an xml tag
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = vec!();
+    let mut rest = tag;
+
+    while let Some(equals) = rest.find('=') {
+        let key = rest[..equals].split_whitespace().last().unwrap_or("").to_string();
+        let after = rest[equals + 1..].trim_left();
+
+        let quote = match after.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => quote,
+            _ => break,
+        };
+
+        match after[1..].find(quote) {
+            Some(end) => {
+                attributes.push((key, after[1..end + 1].to_string()));
+                rest = &after[end + 2..];
+            },
+            None => break,
+        }
+    }
+
+    attributes
+}
+
+fn decode_text(bytes: &[u8]) -> Result<String, String> {
+    let utf16 = |bytes: &[u8], little_endian: bool| {
+        let units: Vec<u16> = bytes.chunks(2).filter(|pair| pair.len() == 2).map(|pair| if little_endian {
+            pair[0] as u16 | (pair[1] as u16) << 8
+        } else {
+            (pair[0] as u16) << 8 | pair[1] as u16
+        }).collect();
+        String::from_utf16(&units).map_err(|err| format!("utf-16 error: {}", err))
+    };
+
+    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
+        utf16(&bytes[2..], true)
+    } else if bytes.len() >= 2 && bytes[0] == 0xFE && bytes[1] == 0xFF {
+        utf16(&bytes[2..], false)
+    } else if bytes.len() >= 3 && bytes[0] == 0xEF && bytes[1] == 0xBB && bytes[2] == 0xBF {
+        String::from_utf8(bytes[3..].to_vec()).map_err(|err| format!("utf-8 error: {}", err))
+    } else {
+        String::from_utf8(bytes.to_vec()).map_err(|err| format!("utf-8 error: {}", err))
+    }
+}
+
+fn json_number(json: &Json, key: &str) -> Result<f32, String> {
+    match json.find(key).and_then(|value| value.as_f64()) {
+        Some(value) => Ok(value as f32),
+        None => Err(format!("json is missing number {}", key)),
+    }
+}
+
+fn json_rect(json: &Json) -> Result<[f32; 4], String> {
+    Ok([try!(json_number(json, "x")), try!(json_number(json, "y")), try!(json_number(json, "w")), try!(json_number(json, "h"))])
+}
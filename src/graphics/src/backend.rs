@@ -0,0 +1,187 @@
+use gfx::{Encoder};
+use gfx::handle::{RenderTargetView, DepthStencilView};
+
+use gfx_device_gl::{Resources, CommandBuffer};
+
+//*************************************************************************************************
+
+use grid::{self, GridData};
+use spritesheet::{Bundle, Instance};
+
+use ::{ColorFormat, DepthFormat, ProjectionData};
+
+//*************************************************************************************************
+
+//what the render system draws a frame through, bundles are picked by the ids they were added with
+pub trait Backend {
+    //later draws go into the bundle's render target, or the window for none
+    fn set_output(&mut self, output: Option<usize>);
+    fn get_output_size(&self) -> (u32, u32);
+    fn clear(&mut self, color: [f32; 4]);
+    fn clear_depth(&mut self);
+    //fills the whole output
+    fn draw_grid(&mut self, grid_data: &GridData);
+    //every batch a frame draws, each being instances under their bundle with their layer,
+    //sent together before any is drawn so each bundle's buffer is only written once a frame
+    fn upload_instances(&mut self, batches: Vec<Vec<Vec<(u8, Instance)>>>);
+    //draws one of the last uploaded batches layer by layer across bundles
+    fn draw_batch(&mut self, batch: usize, projection_data: &ProjectionData);
+
+    fn draw_instances(&mut self, instances: Vec<Vec<(u8, Instance)>>, projection_data: &ProjectionData) {
+        self.upload_instances(vec!(instances));
+        self.draw_batch(0, projection_data);
+    }
+}
+
+//encodes a frame for the gpu, targets are the render targets of bundles showing a camera
+pub struct Gpu<'a> {
+    encoder: &'a mut Encoder<Resources, CommandBuffer>,
+    bundles: &'a mut [Bundle],
+    grid: Option<&'a mut grid::Bundle>,
+    window: (RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>),
+    targets: Vec<(usize, RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>)>,
+    out_color: RenderTargetView<Resources, ColorFormat>,
+    out_depth: DepthStencilView<Resources, DepthFormat>,
+    //each uploaded batch as (layer, bundle id, first instance, instance count) in draw order
+    batches: Vec<Vec<(u8, usize, u32, u32)>>,
+}
+
+impl<'a> Gpu<'a> {
+    pub fn new(
+        encoder: &'a mut Encoder<Resources, CommandBuffer>,
+        bundles: &'a mut [Bundle],
+        grid: Option<&'a mut grid::Bundle>,
+        window: (RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>),
+        targets: Vec<(usize, RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>)>
+    ) -> Gpu<'a> {
+        let mut gpu = Gpu {
+            encoder: encoder,
+            bundles: bundles,
+            grid: grid,
+            out_color: window.0.clone(),
+            out_depth: window.1.clone(),
+            window: window,
+            targets: targets,
+            batches: vec!(),
+        };
+        gpu.set_output(None);
+        gpu
+    }
+}
+
+impl<'a> Backend for Gpu<'a> {
+    fn set_output(&mut self, output: Option<usize>) {
+        let (out_color, out_depth) = match output {
+            Some(id) => match self.targets.iter().find(|&&(target, _, _)| target == id) {
+                Some(&(_, ref out_color, ref out_depth)) => (out_color.clone(), out_depth.clone()),
+                None => {
+                    warn!("gpu set output bundle {} has no render target", id);
+                    return;
+                },
+            },
+            None => self.window.clone(),
+        };
+
+        for bundle in self.bundles.iter_mut() {
+            bundle.data.out_color = out_color.clone();
+            bundle.data.out_depth = out_depth.clone();
+        }
+
+        if let Some(ref mut grid) = self.grid {
+            grid.data.out_color = out_color.clone();
+        }
+
+        self.out_color = out_color;
+        self.out_depth = out_depth;
+    }
+
+    fn get_output_size(&self) -> (u32, u32) {
+        let (width, height, _, _) = self.out_color.get_dimensions();
+        (width as u32, height as u32)
+    }
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.encoder.clear(&self.out_color, color);
+    }
+
+    fn clear_depth(&mut self) {
+        self.encoder.clear_depth(&self.out_depth, 1.0);
+    }
+
+    fn draw_grid(&mut self, grid_data: &GridData) {
+        if let Some(ref grid) = self.grid {
+            self.encoder.update_constant_buffer(&grid.data.grid_cb, grid_data);
+            grid.encode(&mut *self.encoder);
+        }
+    }
+
+    //packs every batch into each bundle's buffer one after another and uploads each buffer once
+    fn upload_instances(&mut self, batches: Vec<Vec<Vec<(u8, Instance)>>>) {
+        let mut data: Vec<Vec<Instance>> = self.bundles.iter().map(|_| vec!()).collect();
+        self.batches.clear();
+
+        for batch in batches {
+            let mut ranges = vec!();
+
+            for (id, mut bundle_instances) in batch.into_iter().enumerate() {
+                if bundle_instances.is_empty() {
+                    continue;
+                }
+
+                let b = match self.bundles.get(id) {
+                    Some(b) => b,
+                    None => {
+                        warn!("gpu has no bundle {} for {} instances", id, bundle_instances.len());
+                        continue;
+                    },
+                };
+
+                let room = b.get_capacity() - data[id].len();
+                bundle_instances.sort_by_key(|&(layer, _)| layer);
+                if bundle_instances.len() > room {
+                    warn!("render bundle {} has {} more instances this frame but only room for {}", id, bundle_instances.len(), room);
+                    bundle_instances.truncate(room);
+                }
+
+                let offset = data[id].len();
+                let mut start = 0;
+                while start < bundle_instances.len() {
+                    let layer = bundle_instances[start].0;
+                    let count = bundle_instances[start..].iter().take_while(|&&(other, _)| other == layer).count();
+                    ranges.push((layer, id, (offset + start) as u32, count as u32));
+                    start += count;
+                }
+
+                data[id].extend(bundle_instances.into_iter().map(|(_, instance)| instance));
+            }
+
+            ranges.sort_by_key(|&(layer, id, _, _)| (layer, id));
+            self.batches.push(ranges);
+        }
+
+        for (b, bundle_data) in self.bundles.iter().zip(data.iter()) {
+            if !bundle_data.is_empty() {
+                b.update_instances(&mut *self.encoder, bundle_data);
+            }
+        }
+    }
+
+    fn draw_batch(&mut self, batch: usize, projection_data: &ProjectionData) {
+        let ranges = match self.batches.get(batch) {
+            Some(ranges) => ranges,
+            None => {
+                warn!("gpu draw batch {} was never uploaded", batch);
+                return;
+            },
+        };
+
+        let mut projected = vec!(false; self.bundles.len());
+        for &(_, id, start, count) in ranges {
+            if !projected[id] {
+                self.encoder.update_constant_buffer(&self.bundles[id].data.projection_cb, projection_data);
+                projected[id] = true;
+            }
+            self.bundles[id].encode_range(&mut *self.encoder, start, count);
+        }
+    }
+}
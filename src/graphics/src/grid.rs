@@ -0,0 +1,109 @@
+use gfx::{Slice, PipelineState, Encoder, Primitive};
+use gfx::state::{Rasterizer};
+use gfx::traits::{Factory, FactoryExt};
+
+use gfx_device_gl::{Resources, CommandBuffer};
+use gfx_device_gl::Factory as GLFactory;
+
+use ::Shaders;
+
+pub const VERTEX_SHADER: &'static str = "grid_150_v.glsl";
+pub const FRAGMENT_SHADER: &'static str = "grid_150_f.glsl";
+
+pub fn make_shaders() -> Shaders {
+    Shaders::new(VERTEX_SHADER, FRAGMENT_SHADER)
+}
+
+pub fn make_pipeline(factory: &mut GLFactory, shaders: &Shaders) -> Result<PipelineState<Resources, pipe::Meta>, String> {
+    let shader_set = match factory.create_shader_set(shaders.get_vertex_shader(), shaders.get_fragment_shader()) {
+        Ok(shader_set) => shader_set,
+        Err(err) => return Err(format!("grid create shader set error: {}", shaders.describe_error(&err))),
+    };
+
+    let program = match factory.create_program(&shader_set) {
+        Ok(program) => program,
+        Err(err) => return Err(format!("grid create program error: {}", err)),
+    };
+
+    match factory.create_pipeline_from_program(&program, Primitive::TriangleList, Rasterizer::new_fill(), pipe::new()) {
+        Ok(pso) => Ok(pso),
+        Err(err) => Err(format!("grid create pipeline error: {}", err)),
+    }
+}
+
+gfx_defines! {
+    vertex Vertex {
+        pos: [f32; 2] = "a_Pos",
+    }
+
+    constant GridData {
+        inv_view_proj: [[f32; 4]; 4] = "u_InvViewProj",
+        line_color: [f32; 4] = "u_LineColor",
+        cell_color: [f32; 4] = "u_CellColor",
+        outside_color: [f32; 4] = "u_OutsideColor",
+        selected_color: [f32; 4] = "u_SelectedColor",
+        bounds: [f32; 4] = "u_Bounds",
+        selected: [f32; 4] = "u_Selected",
+        outline: [f32; 4] = "u_Outline",
+        outline_color: [f32; 4] = "u_OutlineColor",
+    }
+
+    pipeline pipe {
+        vbuf: gfx::VertexBuffer<Vertex> = (),
+
+        grid_cb: gfx::ConstantBuffer<GridData> = "b_GridData",
+
+        out_color: gfx::RenderTarget<::ColorFormat> = "Target0",
+    }
+}
+
+impl Vertex {
+    pub fn new(pos: [f32; 2]) -> Vertex {
+        Vertex {
+            pos: pos,
+        }
+    }
+}
+
+//a quad covering the whole screen in normalized device coordinates
+pub fn make_screen_quad() -> (Vec<Vertex>, Vec<u32>) {
+    (
+        vec!(
+            Vertex::new([-1.0, -1.0]),
+            Vertex::new([1.0, -1.0]),
+            Vertex::new([1.0, 1.0]),
+            Vertex::new([-1.0, 1.0]),
+        ),
+        vec!(
+            0, 1, 2, 2, 3, 0,
+        )
+    )
+}
+
+pub struct Bundle {
+    slice: Slice<Resources>,
+    pso: PipelineState<Resources, pipe::Meta>,
+    pub data: pipe::Data<Resources>,
+}
+
+impl Bundle {
+    pub fn new(
+        slice: Slice<Resources>,
+        pso: PipelineState<Resources, pipe::Meta>,
+        data: pipe::Data<Resources>,
+    ) -> Bundle {
+        Bundle {
+            slice: slice,
+            pso: pso,
+            data: data,
+        }
+    }
+
+    pub fn set_pso(&mut self, pso: PipelineState<Resources, pipe::Meta>) {
+        self.pso = pso;
+    }
+
+    pub fn encode(&self, encoder: &mut Encoder<Resources, CommandBuffer>) {
+        encoder.draw(&self.slice, &self.pso, &self.data);
+    }
+}
@@ -8,12 +8,15 @@ extern crate find_folder;
 #[macro_use]
 extern crate log;
 extern crate env_logger;
+extern crate rustc_serialize;
 
 extern crate utils;
 
 use std::io::{BufReader, Read};
 use std::fs::{File};
-use std::path::{Path};
+use std::path::{Path, PathBuf};
+use std::fmt::{Debug};
+use std::env;
 
 use gfx::{Factory, Encoder};
 use gfx::handle::{RenderTargetView, DepthStencilView, ShaderResourceView};
@@ -27,57 +30,82 @@ use glutin::{WindowBuilder, Window};
 
 use find_folder::Search;
 
+use software::{Canvas};
+
+pub mod atlas;
+pub mod backend;
+pub mod grid;
+pub mod software;
 pub mod spritesheet;
+pub mod text;
+pub mod watch;
 
 pub type ColorFormat = Rgba8;
 pub type DepthFormat = DepthStencil;
 
+//compiled in so a checkout runs without the shader folder, a folder on disk overrides them
+const EMBEDDED_SHADERS: &'static [(&'static str, &'static [u8])] = &[
+    ("spritesheet_150_v.glsl", include_bytes!("shader/spritesheet_150_v.glsl")),
+    ("spritesheet_150_f.glsl", include_bytes!("shader/spritesheet_150_f.glsl")),
+    ("grid_150_v.glsl", include_bytes!("shader/grid_150_v.glsl")),
+    ("grid_150_f.glsl", include_bytes!("shader/grid_150_f.glsl")),
+];
+
+pub const SHADER_FOLDER_VAR: &'static str = "EXPLORE_SHADER_FOLDER";
+
 #[derive(Debug)]
 pub struct Shaders {
     vertex: Vec<u8>,
     fragment: Vec<u8>,
+    vertex_name: String,
+    fragment_name: String,
+    embedded: bool,
 }
 
 impl Shaders {
+    //uses the shader folder when it has both files, otherwise the embedded copies
     pub fn new(vertex_name: &'static str, fragment_name: &'static str) -> Shaders {
-        let shaders_path = match Search::ParentsThenKids(3, 3).for_folder("shader") {
-            Ok(shaders_path) => shaders_path,
-            Err(err) => panic!("find folder shader error: {}", err),
-        };
-
-        let mut vertex_path = shaders_path.clone();
-        let mut fragment_path = shaders_path.clone();
+        if find_shader_folder().is_some() {
+            match Shaders::try_new(vertex_name, fragment_name) {
+                Ok(shaders) => return shaders,
+                Err(err) => warn!("shaders new using embedded shaders, folder error: {}", err),
+            }
+        }
 
-        vertex_path.push(vertex_name);
-        fragment_path.push(fragment_name);
+        match Shaders::embedded(vertex_name, fragment_name) {
+            Some(shaders) => shaders,
+            None => panic!("shaders new {} and {} are not embedded and no shader folder has them", vertex_name, fragment_name),
+        }
+    }
 
-        let vertex_file = match File::open(vertex_path) {
-            Ok(file) => file,
-            Err(err) => panic!("vertex file open error: {}", err),
-        };
-        let fragment_file = match File::open(fragment_path) {
-            Ok(file) => file,
-            Err(err) => panic!("fragment file open error: {}", err),
+    //reads only from the shader folder, used when reloading a shader mid edit
+    pub fn try_new(vertex_name: &str, fragment_name: &str) -> Result<Shaders, String> {
+        let shaders_path = match find_shader_folder() {
+            Some(shaders_path) => shaders_path,
+            None => return Err("no shader folder found".to_string()),
         };
 
-        let mut vertex_reader = BufReader::new(vertex_file);
-        let mut fragment_reader = BufReader::new(fragment_file);
-
-        let mut vertex_buffer = vec!();
-        let mut fragment_buffer = vec!();
+        Ok(Shaders {
+            vertex: try!(read_file(&shaders_path.join(vertex_name))),
+            fragment: try!(read_file(&shaders_path.join(fragment_name))),
+            vertex_name: vertex_name.to_string(),
+            fragment_name: fragment_name.to_string(),
+            embedded: false,
+        })
+    }
 
-        match vertex_reader.read_to_end(&mut vertex_buffer) {
-            Ok(_) => (),
-            Err(err) => panic!("vertex reader read to end error: {}", err),
-        };
-        match fragment_reader.read_to_end(&mut fragment_buffer) {
-            Ok(_) => (),
-            Err(err) => panic!("fragment reader read to end error: {}", err),
-        }
+    pub fn embedded(vertex_name: &str, fragment_name: &str) -> Option<Shaders> {
+        let find = |name: &str| EMBEDDED_SHADERS.iter().find(|&&(embedded, _)| embedded == name).map(|&(_, source)| source.to_vec());
 
-        Shaders {
-            vertex: vertex_buffer,
-            fragment: fragment_buffer,
+        match (find(vertex_name), find(fragment_name)) {
+            (Some(vertex), Some(fragment)) => Some(Shaders {
+                vertex: vertex,
+                fragment: fragment,
+                vertex_name: vertex_name.to_string(),
+                fragment_name: fragment_name.to_string(),
+                embedded: true,
+            }),
+            _ => None,
         }
     }
 
@@ -88,36 +116,140 @@ impl Shaders {
     pub fn get_fragment_shader(&self) -> &[u8] {
         self.fragment.as_slice()
     }
+
+    pub fn get_vertex_name(&self) -> &str {
+        self.vertex_name.as_str()
+    }
+
+    pub fn get_fragment_name(&self) -> &str {
+        self.fragment_name.as_str()
+    }
+
+    pub fn is_embedded(&self) -> bool {
+        self.embedded
+    }
+
+    //turns a driver compile log into file and line messages with the offending source line
+    pub fn describe_error<E: Debug>(&self, err: &E) -> String {
+        let log = format!("{:?}", err);
+
+        let (name, source) = if log.starts_with("Pixel") || log.starts_with("Fragment") {
+            (self.fragment_name.as_str(), self.fragment.as_slice())
+        } else if log.starts_with("Vertex") {
+            (self.vertex_name.as_str(), self.vertex.as_slice())
+        } else {
+            return format!("{} and {}: {}", self.vertex_name, self.fragment_name, log);
+        };
+
+        let source = String::from_utf8_lossy(source);
+        let source_lines: Vec<&str> = source.lines().collect();
+
+        let mut description = String::new();
+        for line in log.split("\\n").flat_map(|line| line.split('\n')) {
+            let line = line.trim();
+            match parse_glsl_line(line) {
+                Some(number) => {
+                    description.push_str(&format!("\n{} line {}: {}", name, number, line));
+                    if let Some(source_line) = source_lines.get(number.wrapping_sub(1)) {
+                        description.push_str(&format!("\n    {}", source_line.trim()));
+                    }
+                },
+                None => if !line.is_empty() && description.is_empty() {
+                    description.push_str(&format!("\n{}: {}", name, line));
+                },
+            }
+        }
+
+        if description.is_empty() {
+            format!("{}: {}", name, log)
+        } else {
+            description
+        }
+    }
+}
+
+//drivers write the location as 0:12(3) (mesa), 0(12) (nvidia) or ERROR: 0:12: (amd), the 0 being the source string
+pub fn parse_glsl_line(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    for index in 0..bytes.len() {
+        let starts_token = index == 0 || !(bytes[index - 1] as char).is_digit(10);
+        if starts_token && bytes[index] == b'0' && index + 1 < bytes.len() && (bytes[index + 1] == b':' || bytes[index + 1] == b'(') {
+            let digits: String = line[index + 2..].chars().take_while(|c| c.is_digit(10)).collect();
+            if let Ok(number) = digits.parse() {
+                return Some(number);
+            }
+        }
+    }
+    None
+}
+
+//the EXPLORE_SHADER_FOLDER environment variable wins over searching near the working directory
+pub fn find_shader_folder() -> Option<PathBuf> {
+    if let Some(folder) = env::var_os(SHADER_FOLDER_VAR) {
+        let folder = PathBuf::from(folder);
+        if folder.is_dir() {
+            return Some(folder);
+        }
+        warn!("{} is not a folder: {}", SHADER_FOLDER_VAR, folder.display());
+    }
+
+    Search::ParentsThenKids(3, 3).for_folder("shader").ok()
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => return Err(format!("{} open error: {}", path.display(), err)),
+    };
+
+    let mut buffer = vec!();
+    match BufReader::new(file).read_to_end(&mut buffer) {
+        Ok(_) => Ok(buffer),
+        Err(err) => Err(format!("{} read error: {}", path.display(), err)),
+    }
 }
 
-pub fn load_texture<P>(factory: &mut GLFactory, path: P) -> ShaderResourceView<Resources, [f32; 4]>
+//the texture and a copy of its pixels for the software renderer
+pub fn load_texture<P>(factory: &mut GLFactory, path: P) -> (ShaderResourceView<Resources, [f32; 4]>, Canvas)
 where P: AsRef<Path>
 {
-    let image = match image::open(path) {
-        Ok(image) => image,
-        Err(err) => panic!("image load error: {}", err),
-    }.to_rgba();
-    let (width, height) = image.dimensions();
-    let kind = Kind::D2(width as Size, height as Size, AaMode::Single);
-    let (_, view) = match factory.create_texture_const_u8::<ColorFormat>(kind, &[&image]) {
-        Ok(data) => data,
-        Err(err) => panic!("factory create texture const error: {}", err),
-    };
-    view
+    match try_load_texture(factory, path) {
+        Ok(texture) => texture,
+        Err(err) => panic!("load texture error: {}", err),
+    }
 }
 
-pub fn build_graphics(width: u32, height: u32) -> (
+//same as load_texture but reports a bad image instead of panicking, an editor may still be writing it
+pub fn try_load_texture<P>(factory: &mut GLFactory, path: P) -> Result<(ShaderResourceView<Resources, [f32; 4]>, Canvas), String>
+where P: AsRef<Path>
+{
+    let canvas = try!(Canvas::load(path));
+    let view = try!(create_texture(factory, &canvas));
+    Ok((view, canvas))
+}
+
+pub fn create_texture(factory: &mut GLFactory, canvas: &Canvas) -> Result<ShaderResourceView<Resources, [f32; 4]>, String> {
+    let kind = Kind::D2(canvas.get_width() as Size, canvas.get_height() as Size, AaMode::Single);
+    match factory.create_texture_const_u8::<ColorFormat>(kind, &[canvas.get_pixels()]) {
+        Ok((_, view)) => Ok(view),
+        Err(err) => Err(format!("factory create texture const error: {}", err)),
+    }
+}
+
+pub fn build_graphics(width: u32, height: u32, title: &str, vsync: bool) -> (
     (RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>),
     GLFactory,
     Encoder<Resources, CommandBuffer>,
     Window,
     Device
 ) {
-    let builder = WindowBuilder::new()
-        .with_title("Explore")
+    let mut builder = WindowBuilder::new()
+        .with_title(title.to_string())
         .with_dimensions(width, height)
-        .with_vsync()
     ;
+    if vsync {
+        builder = builder.with_vsync();
+    }
 
     let (window, device, mut factory, out_color, out_depth) = gfx_window_glutin::init::<ColorFormat, DepthFormat>(builder);
 
@@ -137,7 +269,6 @@ pub fn build_graphics(width: u32, height: u32) -> (
 
 gfx_constant_struct!(
     ProjectionData {
-        model: [[f32; 4]; 4] = "u_Model",
         view: [[f32; 4]; 4] = "u_View",
         proj: [[f32; 4]; 4] = "u_Proj",
     }
@@ -0,0 +1,51 @@
+#version 150 core
+
+in vec2 v_World;
+
+uniform b_GridData {
+    mat4 u_InvViewProj;
+    vec4 u_LineColor;
+    vec4 u_CellColor;
+    vec4 u_OutsideColor;
+    vec4 u_SelectedColor;
+    vec4 u_Bounds;
+    vec4 u_Selected;
+    vec4 u_Outline;
+    vec4 u_OutlineColor;
+};
+
+out vec4 Target0;
+
+void main() {
+    vec2 cell = floor(v_World);
+    vec2 pixel = fwidth(v_World);
+
+    // distance to the nearest cell edge in pixels so lines stay one pixel wide at any zoom
+    vec2 edge = abs(fract(v_World - 0.5) - 0.5) / pixel;
+    float line = 1.0 - clamp(min(edge.x, edge.y), 0.0, 1.0);
+
+    // fade the lines out once cells are only a few pixels across so far views do not turn solid
+    line *= clamp(1.0 / max(pixel.x, pixel.y) - 3.0, 0.0, 1.0);
+
+    // bounds with min past max means the grid goes on forever
+    bool unbounded = u_Bounds.x > u_Bounds.z;
+    bool inside = unbounded || (all(greaterThanEqual(cell, u_Bounds.xy)) && all(lessThan(cell, u_Bounds.zw)));
+
+    vec4 color = inside ? u_CellColor : u_OutsideColor;
+    if (u_Selected.z > 0.5 && cell == u_Selected.xy) {
+        color = u_SelectedColor;
+    }
+
+    color = mix(color, u_LineColor, line);
+
+    // a two pixel border just inside the outline rectangle, min past max means no outline
+    if (u_Outline.x <= u_Outline.z) {
+        vec2 outside = max((u_Outline.xy - v_World) / pixel, (v_World - u_Outline.zw) / pixel);
+        float inset = max(outside.x, outside.y);
+        if (inset <= 0.0 && inset > -2.0) {
+            color = u_OutlineColor;
+        }
+    }
+
+    Target0 = color;
+}
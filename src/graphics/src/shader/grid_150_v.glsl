@@ -0,0 +1,23 @@
+#version 150 core
+
+in vec2 a_Pos;
+
+uniform b_GridData {
+    mat4 u_InvViewProj;
+    vec4 u_LineColor;
+    vec4 u_CellColor;
+    vec4 u_OutsideColor;
+    vec4 u_SelectedColor;
+    vec4 u_Bounds;
+    vec4 u_Selected;
+    vec4 u_Outline;
+    vec4 u_OutlineColor;
+};
+
+out vec2 v_World;
+
+void main() {
+    vec4 world = u_InvViewProj * vec4(a_Pos, 0.0, 1.0);
+    v_World = world.xy / world.w;
+    gl_Position = vec4(a_Pos, 0.0, 1.0);
+}
@@ -3,15 +3,26 @@
 in vec3 a_Pos;
 in vec2 a_Uv;
 
+in vec4 a_Model0;
+in vec4 a_Model1;
+in vec4 a_Model2;
+in vec4 a_Model3;
+in vec4 a_Tint;
+in vec4 a_SpritesheetRect;
+in vec2 a_Mirror;
+
 uniform b_ProjData {
-    mat4 u_Model;
     mat4 u_View;
     mat4 u_Proj;
 };
 
 out vec2 v_Uv;
+out vec4 v_Tint;
 
 void main() {
-    v_Uv = a_Uv;
-    gl_Position = u_Proj * u_View * u_Model * vec4(a_Pos, 1.0);
+    vec2 uv = mix(a_Uv, vec2(1.0) - a_Uv, a_Mirror);
+    v_Uv = uv * a_SpritesheetRect.zw + a_SpritesheetRect.xy;
+    v_Tint = a_Tint;
+    mat4 model = mat4(a_Model0, a_Model1, a_Model2, a_Model3);
+    gl_Position = u_Proj * u_View * model * vec4(a_Pos, 1.0);
 }
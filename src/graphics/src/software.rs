@@ -0,0 +1,562 @@
+use std::path::{Path};
+use std::mem;
+
+use image;
+
+//*************************************************************************************************
+
+use backend::{Backend};
+use grid::{GridData};
+use spritesheet::{Vertex, Index, Instance, Packet};
+
+use ::ProjectionData;
+
+//*************************************************************************************************
+
+//rgba pixels with four bytes each, row 0 is the first row of the source image or,
+//for anything drawn by the renderer, the bottom row the same as a gl render target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    //starts fully transparent
+    pub fn new(width: u32, height: u32) -> Canvas {
+        Canvas {
+            width: width,
+            height: height,
+            pixels: vec!(0; width as usize * height as usize * 4),
+        }
+    }
+
+    //none when there are not four bytes for every pixel
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<u8>) -> Option<Canvas> {
+        if pixels.len() != width as usize * height as usize * 4 {
+            return None;
+        }
+
+        Some(Canvas {
+            width: width,
+            height: height,
+            pixels: pixels,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Canvas, String> {
+        let image = match image::open(path) {
+            Ok(image) => image,
+            Err(err) => return Err(format!("image load error: {}", err)),
+        }.to_rgba();
+        let (width, height) = image.dimensions();
+
+        match Canvas::from_pixels(width, height, image.into_raw()) {
+            Some(canvas) => Ok(canvas),
+            None => Err(format!("image load has the wrong number of bytes for {}x{}", width, height)),
+        }
+    }
+
+    //writes the rows in order, so flip anything the renderer drew first
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        match image::save_buffer(path, &self.pixels, self.width, self.height, image::ColorType::RGBA(8)) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(format!("image save error: {}", err)),
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[u8] {
+        self.pixels.as_slice()
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = self.index(x, y);
+        [self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let index = self.index(x, y);
+        for channel in 0..4 {
+            self.pixels[index + channel] = color[channel];
+        }
+    }
+
+    pub fn clear(&mut self, color: [f32; 4]) {
+        let color = to_bytes(color);
+        for pixel in self.pixels.chunks_mut(4) {
+            for channel in 0..4 {
+                pixel[channel] = color[channel];
+            }
+        }
+    }
+
+    pub fn flip_rows(&self) -> Canvas {
+        let row = self.width as usize * 4;
+        let mut pixels = Vec::with_capacity(self.pixels.len());
+        for y in (0..self.height as usize).rev() {
+            pixels.extend_from_slice(&self.pixels[y * row..(y + 1) * row]);
+        }
+
+        Canvas {
+            width: self.width,
+            height: self.height,
+            pixels: pixels,
+        }
+    }
+
+    //copies other in with its first row at row y, anything outside this canvas is dropped
+    pub fn blit(&mut self, other: &Canvas, x: u32, y: u32) {
+        for other_y in 0..other.height {
+            if y + other_y >= self.height {
+                break;
+            }
+            for other_x in 0..other.width {
+                if x + other_x >= self.width {
+                    break;
+                }
+                let color = other.get_pixel(other_x, other_y);
+                self.set_pixel(x + other_x, y + other_y, color);
+            }
+        }
+    }
+
+    //how many pixels have a channel further apart than tolerance, none when the sizes differ
+    pub fn count_differences(&self, other: &Canvas, tolerance: u8) -> Option<usize> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+
+        Some(self.pixels.chunks(4).zip(other.pixels.chunks(4))
+            .filter(|&(a, b)| a.iter().zip(b.iter()).any(|(&a, &b)| (a as i16 - b as i16).abs() > tolerance as i16))
+            .count()
+        )
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    //nearest texel with mirrored wrapping, the same as the sampler the spritesheet bundles use
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0, 0.0, 0.0, 0.0];
+        }
+
+        let x = mirror_index((u * self.width as f32).floor() as i64, self.width as i64);
+        let y = mirror_index((v * self.height as f32).floor() as i64, self.height as i64);
+        let pixel = self.get_pixel(x as u32, y as u32);
+
+        [
+            pixel[0] as f32 / 255.0,
+            pixel[1] as f32 / 255.0,
+            pixel[2] as f32 / 255.0,
+            pixel[3] as f32 / 255.0,
+        ]
+    }
+
+    //alpha blending the same as gfx's alpha preset, source alpha for color and added alpha
+    fn blend(&mut self, x: u32, y: u32, color: [f32; 4]) {
+        let index = self.index(x, y);
+        let alpha = clamp(color[3]);
+        for channel in 0..3 {
+            let dst = self.pixels[index + channel] as f32 / 255.0;
+            self.pixels[index + channel] = to_byte(color[channel] * alpha + dst * (1.0 - alpha));
+        }
+        let dst = self.pixels[index + 3] as f32 / 255.0;
+        self.pixels[index + 3] = to_byte(alpha + dst);
+    }
+}
+
+fn mirror_index(index: i64, size: i64) -> i64 {
+    let period = size * 2;
+    let index = ((index % period) + period) % period;
+    if index < size {
+        index
+    } else {
+        period - 1 - index
+    }
+}
+
+fn clamp(value: f32) -> f32 {
+    value.max(0.0).min(1.0)
+}
+
+fn to_byte(value: f32) -> u8 {
+    (clamp(value) * 255.0 + 0.5) as u8
+}
+
+fn to_bytes(color: [f32; 4]) -> [u8; 4] {
+    [to_byte(color[0]), to_byte(color[1]), to_byte(color[2]), to_byte(color[3])]
+}
+
+//*************************************************************************************************
+
+type Matrix = [[f32; 4]; 4];
+
+//column major like the matrices handed to the shaders
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for column in 0..4 {
+        result[column] = transform(a, b[column]);
+    }
+    result
+}
+
+fn transform(matrix: &Matrix, vector: [f32; 4]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for row in 0..4 {
+        for column in 0..4 {
+            result[row] += matrix[column][row] * vector[column];
+        }
+    }
+    result
+}
+
+//*************************************************************************************************
+
+struct Bundle {
+    vertices: Vec<Vertex>,
+    indices: Vec<Index>,
+    texture: Canvas,
+    //only used while the texture is the output
+    depth: Vec<f32>,
+}
+
+//a vertex after projection, in pixels from the bottom left of the output with depth from 0 at the near plane to 1 at the far
+#[derive(Debug, Copy, Clone)]
+struct Corner {
+    x: f32,
+    y: f32,
+    z: f32,
+    uv: [f32; 2],
+}
+
+//draws the same packets, instances and layer order as the spritesheet pipeline into canvases on the cpu,
+//so scenes can be rendered and compared without a gpu, a bundle's texture doubles as its render target,
+//every output has a depth buffer tested less or equal and written like the pipeline's
+pub struct Renderer {
+    window: Canvas,
+    window_depth: Vec<f32>,
+    bundles: Vec<Bundle>,
+    output: Option<usize>,
+    //each uploaded batch as (layer, bundle id, instance) in draw order
+    batches: Vec<Vec<(u8, usize, Instance)>>,
+}
+
+impl Renderer {
+    pub fn new(width: u32, height: u32) -> Renderer {
+        Renderer {
+            window: Canvas::new(width, height),
+            window_depth: vec!(),
+            bundles: vec!(),
+            output: None,
+            batches: vec!(),
+        }
+    }
+
+    //ids count up from 0 in the order bundles are added, the same as the render ids of the gfx bundles
+    pub fn add_spritesheet(&mut self, packet: &Packet, texture: Canvas) -> usize {
+        self.bundles.push(Bundle {
+            vertices: packet.get_vertices().to_vec(),
+            indices: packet.get_indices().to_vec(),
+            texture: texture,
+            depth: vec!(),
+        });
+        self.bundles.len() - 1
+    }
+
+    pub fn set_texture(&mut self, id: usize, texture: Canvas) {
+        match self.bundles.get_mut(id) {
+            Some(bundle) => bundle.texture = texture,
+            None => warn!("software renderer set texture id {} has no bundle", id),
+        }
+    }
+
+    //keeps the old pixels when the size is unchanged
+    pub fn set_window_size(&mut self, width: u32, height: u32) {
+        if self.window.get_width() != width || self.window.get_height() != height {
+            self.window = Canvas::new(width, height);
+        }
+    }
+
+    pub fn get_window(&self) -> &Canvas {
+        &self.window
+    }
+
+    pub fn get_bundle_count(&self) -> usize {
+        self.bundles.len()
+    }
+
+    pub fn get_texture(&self, id: usize) -> Option<&Canvas> {
+        self.bundles.get(id).map(|bundle| &bundle.texture)
+    }
+
+    //the window with the top row first, ready to save
+    pub fn to_image(&self) -> Canvas {
+        self.window.flip_rows()
+    }
+
+    fn get_mut_output(&mut self) -> &mut Canvas {
+        match self.output {
+            Some(id) => &mut self.bundles[id].texture,
+            None => &mut self.window,
+        }
+    }
+
+    fn get_mut_depth(&mut self) -> &mut Vec<f32> {
+        match self.output {
+            Some(id) => &mut self.bundles[id].depth,
+            None => &mut self.window_depth,
+        }
+    }
+
+    fn draw_instance(&self, target: &mut Canvas, depth: &mut [f32], bundle: &Bundle, view_proj: &Matrix, instance: &Instance) {
+        let model = [instance.model_0, instance.model_1, instance.model_2, instance.model_3];
+        let matrix = multiply(view_proj, &model);
+        let rect = instance.spritesheet_rect;
+
+        let corners: Vec<Corner> = bundle.vertices.iter().map(|vertex| {
+            let clip = transform(&matrix, [vertex.pos[0], vertex.pos[1], vertex.pos[2], 1.0]);
+            let mut uv = vertex.uv;
+            for axis in 0..2 {
+                if instance.mirror[axis] > 0.5 {
+                    uv[axis] = 1.0 - uv[axis];
+                }
+                uv[axis] = uv[axis] * rect[axis + 2] + rect[axis];
+            }
+            Corner {
+                x: (clip[0] / clip[3] + 1.0) / 2.0 * target.get_width() as f32,
+                y: (clip[1] / clip[3] + 1.0) / 2.0 * target.get_height() as f32,
+                z: (clip[2] / clip[3] + 1.0) / 2.0,
+                uv: uv,
+            }
+        }).collect();
+
+        for triangle in bundle.indices.chunks(3) {
+            if triangle.len() < 3 {
+                break;
+            }
+            match (corners.get(triangle[0] as usize), corners.get(triangle[1] as usize), corners.get(triangle[2] as usize)) {
+                (Some(a), Some(b), Some(c)) => draw_triangle(target, depth, &bundle.texture, instance.tint, *a, *b, *c),
+                _ => warn!("software renderer triangle index out of range"),
+            }
+        }
+    }
+}
+
+fn edge(a: &Corner, b: &Corner, x: f32, y: f32) -> f32 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+//pixels exactly on an edge shared by two triangles only belong to one of them
+fn owns_edge(a: &Corner, b: &Corner) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+//fills the pixels whose centers are inside with the texture times the tint, there is no culling,
+//pixels past the near or far plane are clipped and ones behind what was drawn before fail the depth test
+fn draw_triangle(target: &mut Canvas, depth: &mut [f32], texture: &Canvas, tint: [f32; 4], a: Corner, b: Corner, c: Corner) {
+    let area = edge(&a, &b, c.x, c.y);
+    if area == 0.0 {
+        return;
+    }
+    let (a, b, c, area) = if area < 0.0 {
+        (a, c, b, -area)
+    } else {
+        (a, b, c, area)
+    };
+
+    let (width, height) = (target.get_width() as f32, target.get_height() as f32);
+    let min_x = a.x.min(b.x).min(c.x).floor().max(0.0).min(width) as u32;
+    let max_x = a.x.max(b.x).max(c.x).ceil().max(0.0).min(width) as u32;
+    let min_y = a.y.min(b.y).min(c.y).floor().max(0.0).min(height) as u32;
+    let max_y = a.y.max(b.y).max(c.y).ceil().max(0.0).min(height) as u32;
+
+    let edges = [(b, c, owns_edge(&b, &c)), (c, a, owns_edge(&c, &a)), (a, b, owns_edge(&a, &b))];
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let (center_x, center_y) = (x as f32 + 0.5, y as f32 + 0.5);
+
+            let mut weights = [0.0; 3];
+            let mut inside = true;
+            for (index, &(from, to, owned)) in edges.iter().enumerate() {
+                let weight = edge(&from, &to, center_x, center_y);
+                if weight < 0.0 || (weight == 0.0 && !owned) {
+                    inside = false;
+                    break;
+                }
+                weights[index] = weight / area;
+            }
+            if !inside {
+                continue;
+            }
+
+            let z = weights[0] * a.z + weights[1] * b.z + weights[2] * c.z;
+            let index = y as usize * target.get_width() as usize + x as usize;
+            if z < 0.0 || z > 1.0 || z > depth[index] {
+                continue;
+            }
+            depth[index] = z;
+
+            let u = weights[0] * a.uv[0] + weights[1] * b.uv[0] + weights[2] * c.uv[0];
+            let v = weights[0] * a.uv[1] + weights[1] * b.uv[1] + weights[2] * c.uv[1];
+            let texel = texture.sample(u, v);
+
+            target.blend(x, y, [texel[0] * tint[0], texel[1] * tint[1], texel[2] * tint[2], texel[3] * tint[3]]);
+        }
+    }
+}
+
+//the grid fragment shader run for every pixel, with the derivatives taken from the neighbouring pixels
+fn grid_color(grid_data: &GridData, world: [f32; 2], pixel: [f32; 2]) -> [f32; 4] {
+    let cell = [world[0].floor(), world[1].floor()];
+
+    let mut edge = [0.0; 2];
+    for axis in 0..2 {
+        let shifted = world[axis] - 0.5;
+        let fract = shifted - shifted.floor();
+        edge[axis] = (fract - 0.5).abs() / pixel[axis];
+    }
+    let mut line = 1.0 - clamp(edge[0].min(edge[1]));
+    line *= clamp(1.0 / pixel[0].max(pixel[1]) - 3.0);
+
+    let bounds = grid_data.bounds;
+    let unbounded = bounds[0] > bounds[2];
+    let inside = unbounded || (cell[0] >= bounds[0] && cell[1] >= bounds[1] && cell[0] < bounds[2] && cell[1] < bounds[3]);
+
+    let mut color = if inside {
+        grid_data.cell_color
+    } else {
+        grid_data.outside_color
+    };
+    let selected = grid_data.selected;
+    if selected[2] > 0.5 && cell[0] == selected[0] && cell[1] == selected[1] {
+        color = grid_data.selected_color;
+    }
+
+    for channel in 0..4 {
+        color[channel] += (grid_data.line_color[channel] - color[channel]) * line;
+    }
+
+    let outline = grid_data.outline;
+    if outline[0] <= outline[2] {
+        let outside_x = ((outline[0] - world[0]) / pixel[0]).max((world[0] - outline[2]) / pixel[0]);
+        let outside_y = ((outline[1] - world[1]) / pixel[1]).max((world[1] - outline[3]) / pixel[1]);
+        let inset = outside_x.max(outside_y);
+        if inset <= 0.0 && inset > -2.0 {
+            color = grid_data.outline_color;
+        }
+    }
+
+    color
+}
+
+impl Backend for Renderer {
+    fn set_output(&mut self, output: Option<usize>) {
+        match output {
+            Some(id) if id >= self.bundles.len() => warn!("software renderer set output id {} has no bundle", id),
+            _ => self.output = output,
+        }
+    }
+
+    fn get_output_size(&self) -> (u32, u32) {
+        let output = match self.output {
+            Some(id) => &self.bundles[id].texture,
+            None => &self.window,
+        };
+        (output.get_width(), output.get_height())
+    }
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.get_mut_output().clear(color);
+    }
+
+    fn clear_depth(&mut self) {
+        let (width, height) = self.get_output_size();
+        *self.get_mut_depth() = vec!(1.0; width as usize * height as usize);
+    }
+
+    fn draw_grid(&mut self, grid_data: &GridData) {
+        let output = self.get_mut_output();
+        let (width, height) = (output.get_width(), output.get_height());
+        let inverse = &grid_data.inv_view_proj;
+
+        let to_world = |x: f32, y: f32| {
+            let ndc = [x / width as f32 * 2.0 - 1.0, y / height as f32 * 2.0 - 1.0, 0.0, 1.0];
+            let world = transform(inverse, ndc);
+            [world[0] / world[3], world[1] / world[3]]
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let (center_x, center_y) = (x as f32 + 0.5, y as f32 + 0.5);
+                let world = to_world(center_x, center_y);
+                let (right, up) = (to_world(center_x + 1.0, center_y), to_world(center_x, center_y + 1.0));
+                let pixel = [
+                    (right[0] - world[0]).abs() + (up[0] - world[0]).abs(),
+                    (right[1] - world[1]).abs() + (up[1] - world[1]).abs(),
+                ];
+
+                let color = grid_color(grid_data, world, pixel);
+                output.set_pixel(x, y, to_bytes(color));
+            }
+        }
+    }
+
+    fn upload_instances(&mut self, batches: Vec<Vec<Vec<(u8, Instance)>>>) {
+        self.batches.clear();
+
+        //the same order as the gfx backend, layer by layer and bundle by bundle within a layer
+        for batch in batches {
+            let mut ordered = vec!();
+            for (id, bundle_instances) in batch.into_iter().enumerate() {
+                if id >= self.bundles.len() {
+                    warn!("software renderer has no bundle {} for {} instances", id, bundle_instances.len());
+                    continue;
+                }
+                for (layer, instance) in bundle_instances {
+                    ordered.push((layer, id, instance));
+                }
+            }
+            ordered.sort_by_key(|&(layer, id, _)| (layer, id));
+            self.batches.push(ordered);
+        }
+    }
+
+    fn draw_batch(&mut self, batch: usize, projection_data: &ProjectionData) {
+        if batch >= self.batches.len() {
+            warn!("software renderer draw batch {} was never uploaded", batch);
+            return;
+        }
+        let view_proj = multiply(&projection_data.proj, &projection_data.view);
+
+        //taken out while drawing so the other bundles' textures can be read,
+        //an output that was never cleared or has changed size starts at the far plane
+        let mut target = mem::replace(self.get_mut_output(), Canvas::new(0, 0));
+        let mut depth = mem::replace(self.get_mut_depth(), vec!());
+        let size = target.get_width() as usize * target.get_height() as usize;
+        if depth.len() != size {
+            depth = vec!(1.0; size);
+        }
+
+        for &(_, id, ref instance) in &self.batches[batch] {
+            if Some(id) == self.output {
+                continue;
+            }
+            self.draw_instance(&mut target, &mut depth, &self.bundles[id], &view_proj, instance);
+        }
+        *self.get_mut_output() = target;
+        *self.get_mut_depth() = depth;
+    }
+}
@@ -1,14 +1,39 @@
-use gfx::{Slice, PipelineState, Encoder};
+use gfx::{Slice, PipelineState, Encoder, Primitive};
 use gfx::state::{Rasterizer};
+use gfx::traits::{Factory, FactoryExt};
 
 use gfx_device_gl::{Resources, CommandBuffer};
+use gfx_device_gl::Factory as GLFactory;
 
 use ::Shaders;
 
 pub type Index = u32;
 
+//every bundle gets an instance buffer this large since the render thread cannot grow it without the factory
+pub const MAX_INSTANCES: usize = 65536;
+
+pub const VERTEX_SHADER: &'static str = "spritesheet_150_v.glsl";
+pub const FRAGMENT_SHADER: &'static str = "spritesheet_150_f.glsl";
+
 pub fn make_shaders() -> Shaders {
-    Shaders::new("spritesheet_150_v.glsl", "spritesheet_150_f.glsl")
+    Shaders::new(VERTEX_SHADER, FRAGMENT_SHADER)
+}
+
+pub fn make_pipeline(factory: &mut GLFactory, shaders: &Shaders, rasterizer: Rasterizer) -> Result<PipelineState<Resources, pipe::Meta>, String> {
+    let shader_set = match factory.create_shader_set(shaders.get_vertex_shader(), shaders.get_fragment_shader()) {
+        Ok(shader_set) => shader_set,
+        Err(err) => return Err(format!("spritesheet create shader set error: {}", shaders.describe_error(&err))),
+    };
+
+    let program = match factory.create_program(&shader_set) {
+        Ok(program) => program,
+        Err(err) => return Err(format!("spritesheet create program error: {}", err)),
+    };
+
+    match factory.create_pipeline_from_program(&program, Primitive::TriangleList, rasterizer, pipe::new()) {
+        Ok(pso) => Ok(pso),
+        Err(err) => Err(format!("spritesheet create pipeline error: {}", err)),
+    }
 }
 
 gfx_defines! {
@@ -17,23 +42,25 @@ gfx_defines! {
         uv: [f32; 2] = "a_Uv",
     }
 
-    constant TextureData {
-        tint: [f32; 4] = "u_Tint",
-        spritesheet_rect: [f32; 4] = "u_SpritesheetRect",
-        spritesheet_size: [f32; 2] = "u_SpritesheetSize",
-        mirror_x: bool = "u_MirrorX",
-        mirror_y: bool = "u_MirrorY",
+    vertex Instance {
+        model_0: [f32; 4] = "a_Model0",
+        model_1: [f32; 4] = "a_Model1",
+        model_2: [f32; 4] = "a_Model2",
+        model_3: [f32; 4] = "a_Model3",
+        tint: [f32; 4] = "a_Tint",
+        spritesheet_rect: [f32; 4] = "a_SpritesheetRect",
+        mirror: [f32; 2] = "a_Mirror",
     }
 
     pipeline pipe {
         vbuf: gfx::VertexBuffer<Vertex> = (),
 
+        instances: gfx::InstanceBuffer<Instance> = (),
+
         projection_cb: gfx::ConstantBuffer<::ProjectionData> = "b_ProjData",
 
         spritesheet: gfx::TextureSampler<[f32; 4]> = "t_Texture",
 
-        texture_data: gfx::ConstantBuffer<TextureData> = "b_TextureData",
-
         out_color: ::gfx::BlendTarget<::ColorFormat> = ("Target0", gfx::state::MASK_ALL, gfx::preset::blend::ALPHA),
         out_depth: gfx::DepthTarget<::DepthFormat> = ::gfx::preset::depth::LESS_EQUAL_WRITE,
     }
@@ -48,10 +75,41 @@ impl Vertex {
     }
 }
 
+impl Instance {
+    //spritesheet rect and size are in pixels, the shader gets the rect as a fraction of the sheet
+    pub fn new(
+        model: [[f32; 4]; 4],
+        tint: [f32; 4],
+        spritesheet_rect: [f32; 4],
+        spritesheet_size: [f32; 2],
+        mirror_x: bool,
+        mirror_y: bool
+    ) -> Instance {
+        Instance {
+            model_0: model[0],
+            model_1: model[1],
+            model_2: model[2],
+            model_3: model[3],
+            tint: tint,
+            spritesheet_rect: [
+                spritesheet_rect[0] / spritesheet_size[0],
+                spritesheet_rect[1] / spritesheet_size[1],
+                spritesheet_rect[2] / spritesheet_size[0],
+                spritesheet_rect[3] / spritesheet_size[1],
+            ],
+            mirror: [
+                if mirror_x { 1.0 } else { 0.0 },
+                if mirror_y { 1.0 } else { 0.0 },
+            ],
+        }
+    }
+}
+
 pub struct Bundle {
     slice: Slice<Resources>,
     pso: PipelineState<Resources, pipe::Meta>,
     pub data: pipe::Data<Resources>,
+    capacity: usize,
 }
 
 impl Bundle {
@@ -59,16 +117,37 @@ impl Bundle {
         slice: Slice<Resources>,
         pso: PipelineState<Resources, pipe::Meta>,
         data: pipe::Data<Resources>,
+        capacity: usize,
     ) -> Bundle {
         Bundle {
             slice: slice,
             pso: pso,
             data: data,
+            capacity: capacity,
+        }
+    }
+
+    pub fn set_pso(&mut self, pso: PipelineState<Resources, pipe::Meta>) {
+        self.pso = pso;
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    //uploads every instance for this frame, ordered so each layer is one contiguous range
+    pub fn update_instances(&self, encoder: &mut Encoder<Resources, CommandBuffer>, instances: &[Instance]) {
+        match encoder.update_buffer(&self.data.instances, instances, 0) {
+            Ok(()) => (),
+            Err(err) => error!("bundle update instances error: {:?}", err),
         }
     }
 
-    pub fn encode(&self, encoder: &mut Encoder<Resources, CommandBuffer>) {
-        encoder.draw(&self.slice, &self.pso, &self.data);
+    //draws count instances starting at start in a single call
+    pub fn encode_range(&self, encoder: &mut Encoder<Resources, CommandBuffer>, start: u32, count: u32) {
+        let mut slice = self.slice.clone();
+        slice.instances = Some((count, start));
+        encoder.draw(&slice, &self.pso, &self.data);
     }
 }
 
@@ -0,0 +1,166 @@
+use std::collections::{HashMap};
+
+use gfx::handle::{ShaderResourceView};
+
+use gfx_device_gl::{Resources};
+use gfx_device_gl::Factory as GLFactory;
+
+use software::{Canvas};
+
+use ::create_texture;
+
+//*************************************************************************************************
+
+const GLYPH_WIDTH: usize = 6;
+const GLYPH_HEIGHT: usize = 8;
+const COLUMNS: usize = 16;
+const FIRST_CHAR: u8 = b' ';
+
+//5x8 glyphs for ' ' to '~', one byte per column with the top row in the lowest bit
+const BUILTIN_GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], [0x00, 0x00, 0x5F, 0x00, 0x00], [0x00, 0x07, 0x00, 0x07, 0x00], [0x14, 0x7F, 0x14, 0x7F, 0x14],
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], [0x23, 0x13, 0x08, 0x64, 0x62], [0x36, 0x49, 0x56, 0x20, 0x50], [0x00, 0x08, 0x07, 0x03, 0x00],
+    [0x00, 0x1C, 0x22, 0x41, 0x00], [0x00, 0x41, 0x22, 0x1C, 0x00], [0x2A, 0x1C, 0x7F, 0x1C, 0x2A], [0x08, 0x08, 0x3E, 0x08, 0x08],
+    [0x00, 0x80, 0x70, 0x30, 0x00], [0x08, 0x08, 0x08, 0x08, 0x08], [0x00, 0x00, 0x60, 0x60, 0x00], [0x20, 0x10, 0x08, 0x04, 0x02],
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], [0x00, 0x42, 0x7F, 0x40, 0x00], [0x72, 0x49, 0x49, 0x49, 0x46], [0x21, 0x41, 0x49, 0x4D, 0x33],
+    [0x18, 0x14, 0x12, 0x7F, 0x10], [0x27, 0x45, 0x45, 0x45, 0x39], [0x3C, 0x4A, 0x49, 0x49, 0x31], [0x41, 0x21, 0x11, 0x09, 0x07],
+    [0x36, 0x49, 0x49, 0x49, 0x36], [0x46, 0x49, 0x49, 0x29, 0x1E], [0x00, 0x00, 0x14, 0x00, 0x00], [0x00, 0x40, 0x34, 0x00, 0x00],
+    [0x00, 0x08, 0x14, 0x22, 0x41], [0x14, 0x14, 0x14, 0x14, 0x14], [0x00, 0x41, 0x22, 0x14, 0x08], [0x02, 0x01, 0x59, 0x09, 0x06],
+    [0x3E, 0x41, 0x5D, 0x59, 0x4E], [0x7C, 0x12, 0x11, 0x12, 0x7C], [0x7F, 0x49, 0x49, 0x49, 0x36], [0x3E, 0x41, 0x41, 0x41, 0x22],
+    [0x7F, 0x41, 0x41, 0x41, 0x3E], [0x7F, 0x49, 0x49, 0x49, 0x41], [0x7F, 0x09, 0x09, 0x09, 0x01], [0x3E, 0x41, 0x41, 0x51, 0x73],
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], [0x00, 0x41, 0x7F, 0x41, 0x00], [0x20, 0x40, 0x41, 0x3F, 0x01], [0x7F, 0x08, 0x14, 0x22, 0x41],
+    [0x7F, 0x40, 0x40, 0x40, 0x40], [0x7F, 0x02, 0x1C, 0x02, 0x7F], [0x7F, 0x04, 0x08, 0x10, 0x7F], [0x3E, 0x41, 0x41, 0x41, 0x3E],
+    [0x7F, 0x09, 0x09, 0x09, 0x06], [0x3E, 0x41, 0x51, 0x21, 0x5E], [0x7F, 0x09, 0x19, 0x29, 0x46], [0x26, 0x49, 0x49, 0x49, 0x32],
+    [0x03, 0x01, 0x7F, 0x01, 0x03], [0x3F, 0x40, 0x40, 0x40, 0x3F], [0x1F, 0x20, 0x40, 0x20, 0x1F], [0x3F, 0x40, 0x38, 0x40, 0x3F],
+    [0x63, 0x14, 0x08, 0x14, 0x63], [0x03, 0x04, 0x78, 0x04, 0x03], [0x61, 0x59, 0x49, 0x4D, 0x43], [0x00, 0x7F, 0x41, 0x41, 0x41],
+    [0x02, 0x04, 0x08, 0x10, 0x20], [0x00, 0x41, 0x41, 0x41, 0x7F], [0x04, 0x02, 0x01, 0x02, 0x04], [0x40, 0x40, 0x40, 0x40, 0x40],
+    [0x00, 0x03, 0x07, 0x08, 0x00], [0x20, 0x54, 0x54, 0x78, 0x40], [0x7F, 0x28, 0x44, 0x44, 0x38], [0x38, 0x44, 0x44, 0x44, 0x28],
+    [0x38, 0x44, 0x44, 0x28, 0x7F], [0x38, 0x54, 0x54, 0x54, 0x18], [0x00, 0x08, 0x7E, 0x09, 0x02], [0x18, 0xA4, 0xA4, 0x9C, 0x78],
+    [0x7F, 0x08, 0x04, 0x04, 0x78], [0x00, 0x44, 0x7D, 0x40, 0x00], [0x20, 0x40, 0x40, 0x3D, 0x00], [0x7F, 0x10, 0x28, 0x44, 0x00],
+    [0x00, 0x41, 0x7F, 0x40, 0x00], [0x7C, 0x04, 0x78, 0x04, 0x78], [0x7C, 0x08, 0x04, 0x04, 0x78], [0x38, 0x44, 0x44, 0x44, 0x38],
+    [0xFC, 0x18, 0x24, 0x24, 0x18], [0x18, 0x24, 0x24, 0x18, 0xFC], [0x7C, 0x08, 0x04, 0x04, 0x08], [0x48, 0x54, 0x54, 0x54, 0x24],
+    [0x04, 0x04, 0x3F, 0x44, 0x24], [0x3C, 0x40, 0x40, 0x20, 0x7C], [0x1C, 0x20, 0x40, 0x20, 0x1C], [0x3C, 0x40, 0x30, 0x40, 0x3C],
+    [0x44, 0x28, 0x10, 0x28, 0x44], [0x4C, 0x90, 0x90, 0x90, 0x7C], [0x44, 0x64, 0x54, 0x4C, 0x44], [0x00, 0x08, 0x36, 0x41, 0x00],
+    [0x00, 0x00, 0x77, 0x00, 0x00], [0x00, 0x41, 0x36, 0x08, 0x00], [0x02, 0x01, 0x02, 0x04, 0x02],
+];
+
+//one quad of laid out text in line heights, offset is the bottom left of the glyph
+#[derive(Debug, Clone, Copy)]
+pub struct Glyph {
+    pub offset: [f32; 2],
+    pub size: [f32; 2],
+    pub rect: [f32; 4],
+}
+
+//glyph rects in pixels on a spritesheet, like the rects given to RenderData
+#[derive(Debug, Clone)]
+pub struct Font {
+    glyphs: HashMap<char, [f32; 4]>,
+    line_height: f32,
+    texture_size: [f32; 2],
+}
+
+impl Font {
+    //the built in font rasterised into a white texture so text takes its color from the tint
+    pub fn new_builtin(factory: &mut GLFactory) -> (ShaderResourceView<Resources, [f32; 4]>, Canvas, Font) {
+        let (canvas, font) = Font::new_builtin_canvas();
+        match create_texture(factory, &canvas) {
+            Ok(view) => (view, canvas, font),
+            Err(err) => panic!("font new builtin create texture error: {}", err),
+        }
+    }
+
+    //the built in font's pixels without a texture, for the software renderer
+    pub fn new_builtin_canvas() -> (Canvas, Font) {
+        let rows = (BUILTIN_GLYPHS.len() + COLUMNS - 1) / COLUMNS;
+        let (width, height) = (COLUMNS * GLYPH_WIDTH, rows * GLYPH_HEIGHT);
+
+        let mut pixels = vec!(0u8; width * height * 4);
+        let mut glyphs = HashMap::new();
+
+        for (index, columns) in BUILTIN_GLYPHS.iter().enumerate() {
+            let (cell_x, cell_y) = ((index % COLUMNS) * GLYPH_WIDTH, (index / COLUMNS) * GLYPH_HEIGHT);
+
+            for (column, bits) in columns.iter().enumerate() {
+                for row in 0..GLYPH_HEIGHT {
+                    if bits & (1 << row) != 0 {
+                        let pixel = ((cell_y + row) * width + cell_x + column) * 4;
+                        for channel in 0..4 {
+                            pixels[pixel + channel] = 255;
+                        }
+                    }
+                }
+            }
+
+            glyphs.insert((FIRST_CHAR + index as u8) as char, [cell_x as f32, cell_y as f32, GLYPH_WIDTH as f32, GLYPH_HEIGHT as f32]);
+        }
+
+        let canvas = match Canvas::from_pixels(width as u32, height as u32, pixels) {
+            Some(canvas) => canvas,
+            None => panic!("font new builtin canvas pixels do not fit {}x{}", width, height),
+        };
+
+        (canvas, Font {
+            glyphs: glyphs,
+            line_height: GLYPH_HEIGHT as f32,
+            texture_size: [width as f32, height as f32],
+        })
+    }
+
+    pub fn get_texture_size(&self) -> [f32; 2] {
+        self.texture_size
+    }
+
+    pub fn get_rect(&self, c: char) -> Option<[f32; 4]> {
+        self.glyphs.get(&c).cloned()
+    }
+
+    //lays text out left to right with one line height per line going down, unknown chars become '?' or a gap
+    pub fn layout(&self, text: &str) -> Vec<Glyph> {
+        let mut glyphs = vec!();
+
+        if self.line_height <= 0.0 {
+            return glyphs;
+        }
+
+        let (mut x, mut y) = (0.0, 0.0);
+        for c in text.chars() {
+            if c == '\n' {
+                x = 0.0;
+                y -= 1.0;
+                continue;
+            }
+
+            let rect = match self.get_rect(c).or_else(|| self.get_rect('?')) {
+                Some(rect) => rect,
+                None => {
+                    x += 0.5;
+                    continue;
+                },
+            };
+
+            let size = [rect[2] / self.line_height, rect[3] / self.line_height];
+            if c != ' ' {
+                glyphs.push(Glyph {
+                    offset: [x, y],
+                    size: size,
+                    rect: rect,
+                });
+            }
+            x += size[0];
+        }
+
+        glyphs
+    }
+}
+
+//the model of one glyph inside text whose model maps one line height to a unit
+pub fn glyph_model(model: [[f32; 4]; 4], glyph: &Glyph) -> [[f32; 4]; 4] {
+    let mut result = model;
+    for row in 0..4 {
+        result[0][row] = model[0][row] * glyph.size[0];
+        result[1][row] = model[1][row] * glyph.size[1];
+        result[3][row] = model[0][row] * glyph.offset[0] + model[1][row] * glyph.offset[1] + model[3][row];
+    }
+    result
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime};
+
+//polls modification times instead of asking the os so it works the same everywhere
+#[derive(Debug)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    folders: Vec<PathBuf>,
+}
+
+impl Watcher {
+    pub fn new() -> Watcher {
+        Watcher {
+            files: vec!(),
+            folders: vec!(),
+        }
+    }
+
+    pub fn watch<P>(&mut self, path: P)
+    where P: AsRef<Path>
+    {
+        let path = path.as_ref().to_path_buf();
+        if self.files.iter().any(|&(ref watched, _)| *watched == path) {
+            return;
+        }
+        let modified = get_modified(&path);
+        self.files.push((path, modified));
+    }
+
+    //watches every file under folder, files added later show up as changed on the next poll
+    pub fn watch_folder<P>(&mut self, folder: P)
+    where P: AsRef<Path>
+    {
+        let folder = folder.as_ref().to_path_buf();
+        if self.folders.contains(&folder) {
+            return;
+        }
+        self.folders.push(folder.clone());
+        self.scan_folder(&folder, &mut vec!());
+    }
+
+    //every watched file whose modification time moved since the last poll, and every new file in a watched folder
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec!();
+
+        for folder in self.folders.clone() {
+            self.scan_folder(&folder, &mut changed);
+        }
+
+        for &mut (ref path, ref mut modified) in &mut self.files {
+            let now = get_modified(path);
+            if now.is_some() && now != *modified && !changed.contains(path) {
+                *modified = now;
+                changed.push(path.clone());
+            }
+        }
+
+        changed
+    }
+
+    //starts watching any file under folder not seen before and adds it to added
+    fn scan_folder(&mut self, folder: &Path, added: &mut Vec<PathBuf>) {
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("watch folder {} error: {}", folder.display(), err);
+                return;
+            },
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                self.scan_folder(&path, added);
+            } else if !self.files.iter().any(|&(ref watched, _)| *watched == path) {
+                let modified = get_modified(&path);
+                self.files.push((path.clone(), modified));
+                added.push(path);
+            }
+        }
+    }
+}
+
+fn get_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,110 @@
+extern crate graphics;
+extern crate zip;
+
+use std::fs::{File};
+use std::io::{Read};
+use std::path::{PathBuf};
+
+use graphics::atlas::{Atlas, Frame};
+
+//*************************************************************************************************
+
+//read straight out of the bundled legacy assets so the tests follow the real files
+fn read_fixture(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets/explore_legacy_assets.zip");
+    let file = File::open(&path).expect("legacy assets are missing");
+    let mut archive = zip::ZipArchive::new(file).expect("legacy assets are not a zip");
+    let mut entry = archive.by_name(name).expect("fixture is not in the legacy assets");
+
+    let mut bytes = vec!();
+    entry.read_to_end(&mut bytes).expect("fixture read failed");
+    bytes
+}
+
+fn load_xml(name: &str) -> Atlas {
+    Atlas::from_xml(read_fixture(name).as_slice()).expect("xml atlas failed to parse")
+}
+
+fn load_txt(name: &str) -> Atlas {
+    Atlas::from_txt(read_fixture(name).as_slice()).expect("txt atlas failed to parse")
+}
+
+fn load_json(name: &str) -> Atlas {
+    Atlas::from_json(&read_fixture(name)).expect("json atlas failed to parse")
+}
+
+#[test]
+fn hud_xml_frames() {
+    let atlas = load_xml("HUD/hud_spritesheet.xml");
+
+    assert_eq!(atlas.get_frames().len(), 33);
+    assert_eq!(atlas.get_image(), Some("sheet.png"));
+    assert_eq!(atlas.get_rect("hud_0"), Some([230.0, 0.0, 30.0, 38.0]));
+    assert_eq!(atlas.get_rect("hud_2.png"), Some([55.0, 98.0, 32.0, 38.0]));
+    assert_eq!(atlas.get_rect("hud_heartFull"), Some([0.0, 94.0, 53.0, 45.0]));
+    assert!(!atlas.get_frame("hud_0").expect("hud_0 is missing").is_trimmed());
+}
+
+#[test]
+fn tiles_and_items_xml_frames() {
+    let tiles = load_xml("Tiles/tiles_spritesheet.xml");
+    assert_eq!(tiles.get_frames().len(), 172);
+    assert_eq!(tiles.get_rect("box"), Some([0.0, 864.0, 70.0, 70.0]));
+    assert_eq!(tiles.get_rect("grassMid"), Some([504.0, 576.0, 70.0, 70.0]));
+    assert_eq!(tiles.get_rect("signExit"), Some([288.0, 360.0, 70.0, 70.0]));
+
+    let items = load_xml("Items/items_spritesheet.xml");
+    assert_eq!(items.get_frames().len(), 59);
+    assert_eq!(items.get_rect("bomb"), Some([432.0, 432.0, 70.0, 70.0]));
+    assert_eq!(items.get_rect("keyRed"), Some([72.0, 435.0, 70.0, 70.0]));
+}
+
+#[test]
+fn enemies_txt_frames() {
+    let atlas = load_txt("Enemies/enemies_spritesheet.txt");
+
+    assert_eq!(atlas.get_frames().len(), 18);
+    assert_eq!(atlas.get_rect("blockerBody"), Some([203.0, 0.0, 51.0, 51.0]));
+    assert_eq!(atlas.get_rect("fishSwim2"), Some([73.0, 43.0, 62.0, 43.0]));
+    assert_eq!(atlas.get_rect("snailShell_upsidedown"), Some([148.0, 118.0, 44.0, 30.0]));
+}
+
+#[test]
+fn player_txt_walk_sequence_is_in_order() {
+    for player in &["p1", "p2", "p3"] {
+        let atlas = load_txt(&format!("Player/{}_spritesheet.txt", player));
+        assert_eq!(atlas.get_frames().len(), 16, "{}", player);
+
+        let prefix = format!("{}_walk", player);
+        let names: Vec<&str> = atlas.get_sequence(&prefix).into_iter().map(|(name, _)| name).collect();
+        let expected: Vec<String> = (1..12).map(|index| format!("{}{:02}", prefix, index)).collect();
+        assert_eq!(names, expected.iter().map(|name| name.as_str()).collect::<Vec<&str>>());
+    }
+
+    let atlas = load_txt("Player/p1_spritesheet.txt");
+    assert_eq!(atlas.get_rect("p1_front"), Some([0.0, 196.0, 66.0, 92.0]));
+    assert_eq!(atlas.get_rect("p1_walk11"), Some([292.0, 98.0, 72.0, 97.0]));
+}
+
+#[test]
+fn utf16_json_frames_are_trimmed() {
+    let atlas = load_json("Player/p1_walk/p1_walk.json");
+
+    assert_eq!(atlas.get_frames().len(), 11);
+    assert_eq!(atlas.get_image(), Some("p1_walk.png"));
+    assert_eq!(atlas.get_size(), Some([256.0, 512.0]));
+
+    let frame = atlas.get_frame("Symbol 2 instance 10000").expect("first walk frame is missing");
+    assert_eq!(*frame, Frame::new_trimmed([0.0, 0.0, 67.0, 92.0], false, [4.0, 5.0, 73.0, 97.0], [73.0, 97.0]));
+    assert!(frame.is_trimmed());
+}
+
+#[test]
+fn every_json_walk_has_eleven_frames() {
+    for player in &["p1", "p2", "p3"] {
+        let atlas = load_json(&format!("Player/{}_walk/{}_walk.json", player, player));
+
+        assert_eq!(atlas.get_frames().len(), 11, "{}", player);
+        assert_eq!(atlas.get_sequence("Symbol").len(), 11, "{}", player);
+    }
+}
@@ -0,0 +1,91 @@
+extern crate graphics;
+
+use graphics::{Shaders, parse_glsl_line};
+
+//*************************************************************************************************
+
+//the same shape gfx hands back when a shader set fails, describe_error only sees the debug text
+#[derive(Debug)]
+enum CreateShaderError {
+    CompilationFailed(String),
+}
+
+#[derive(Debug)]
+enum ProgramError {
+    Vertex(CreateShaderError),
+    Pixel(CreateShaderError),
+    Link(String),
+}
+
+fn make_shaders() -> Shaders {
+    Shaders::embedded("spritesheet_150_v.glsl", "spritesheet_150_f.glsl").expect("spritesheet shaders are not embedded")
+}
+
+fn describe_pixel(log: &str) -> String {
+    make_shaders().describe_error(&ProgramError::Pixel(CreateShaderError::CompilationFailed(log.to_string())))
+}
+
+#[test]
+fn reads_the_line_from_every_driver_format() {
+    //mesa
+    assert_eq!(parse_glsl_line("0:11(15): error: `texure' undeclared"), Some(11));
+    //nvidia
+    assert_eq!(parse_glsl_line("0(11) : error C1008: undefined variable \"texure\""), Some(11));
+    //amd
+    assert_eq!(parse_glsl_line("ERROR: 0:11: 'texure' : no matching overloaded function found"), Some(11));
+}
+
+#[test]
+fn unrecognized_lines_have_no_number() {
+    assert_eq!(parse_glsl_line("error: something went wrong"), None);
+    assert_eq!(parse_glsl_line("error C1008 at 10:11"), None);
+    assert_eq!(parse_glsl_line("0: no digits follow"), None);
+    assert_eq!(parse_glsl_line(""), None);
+}
+
+#[test]
+fn mesa_errors_name_the_file_and_quote_the_source() {
+    let description = describe_pixel("0:11(15): error: `texure' undeclared\n");
+
+    assert!(description.contains("spritesheet_150_f.glsl line 11: "), "description was {}", description);
+    assert!(description.contains("\n    Target0 = texture(t_Texture, v_Uv) * v_Tint;"), "description was {}", description);
+}
+
+#[test]
+fn nvidia_errors_report_every_line() {
+    let description = describe_pixel("0(3) : error C0000: syntax error, unexpected identifier\n0(11) : error C1008: undefined variable \"v_Uv\"\n");
+
+    assert!(description.contains("spritesheet_150_f.glsl line 3: "), "description was {}", description);
+    assert!(description.contains("\n    in vec2 v_Uv;"), "description was {}", description);
+    assert!(description.contains("spritesheet_150_f.glsl line 11: "), "description was {}", description);
+}
+
+#[test]
+fn amd_vertex_errors_use_the_vertex_source() {
+    let err = ProgramError::Vertex(CreateShaderError::CompilationFailed("ERROR: 0:4: 'a_Uv' : redefinition\nERROR: 1 compilation errors.  No code generated.\n".to_string()));
+    let description = make_shaders().describe_error(&err);
+
+    assert!(description.contains("spritesheet_150_v.glsl line 4: "), "description was {}", description);
+    assert!(description.contains("\n    in vec2 a_Uv;"), "description was {}", description);
+    assert!(!description.contains("spritesheet_150_f.glsl"), "description was {}", description);
+}
+
+#[test]
+fn lines_past_the_source_are_reported_without_a_quote() {
+    let description = describe_pixel("0:0(1): error: bad version\n0:99(1): error: past the end\n");
+
+    assert!(description.contains("line 0: "), "description was {}", description);
+    assert!(description.contains("line 99: "), "description was {}", description);
+    assert!(!description.contains("\n    "), "description was {}", description);
+}
+
+#[test]
+fn unrecognized_logs_keep_their_text() {
+    let description = describe_pixel("internal compiler error\n");
+    assert!(description.contains("spritesheet_150_f.glsl: "), "description was {}", description);
+    assert!(description.contains("internal compiler error"), "description was {}", description);
+
+    let description = make_shaders().describe_error(&ProgramError::Link("fragment output Target0 is not bound".to_string()));
+    assert!(description.starts_with("spritesheet_150_v.glsl and spritesheet_150_f.glsl: "), "description was {}", description);
+    assert!(description.contains("is not bound"), "description was {}", description);
+}
@@ -0,0 +1,258 @@
+extern crate graphics;
+extern crate art;
+
+use std::env;
+
+use graphics::ProjectionData;
+use graphics::backend::Backend;
+use graphics::software::{Canvas, Renderer};
+use graphics::spritesheet::{Instance};
+
+use art::make_square_render;
+
+//*************************************************************************************************
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+fn make_texture(pixels: &[[u8; 4]], width: u32, height: u32) -> Canvas {
+    let bytes = pixels.iter().flat_map(|pixel| pixel.iter().cloned()).collect();
+    Canvas::from_pixels(width, height, bytes).expect("texture pixels did not fit")
+}
+
+//pixels from the bottom left of a width by height output
+fn make_screen_projection(width: u32, height: u32) -> ProjectionData {
+    ProjectionData {
+        view: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+        proj: [
+            [2.0 / width as f32, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / height as f32, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [-1.0, -1.0, 0.0, 1.0],
+        ],
+    }
+}
+
+fn make_quad(x: f32, y: f32, width: f32, height: f32, tint: [f32; 4], rect: [f32; 4], size: [f32; 2], mirror_x: bool) -> Instance {
+    Instance::new(
+        [
+            [width, 0.0, 0.0, 0.0],
+            [0.0, height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x, y, 0.0, 1.0],
+        ],
+        tint,
+        rect,
+        size,
+        mirror_x,
+        false
+    )
+}
+
+fn draw(renderer: &mut Renderer, instances: Vec<Vec<(u8, Instance)>>) {
+    let (width, height) = renderer.get_output_size();
+    renderer.clear(BLACK);
+    renderer.draw_instances(instances, &make_screen_projection(width, height));
+}
+
+#[test]
+fn quad_fills_the_pixels_it_covers() {
+    let mut renderer = Renderer::new(4, 4);
+    let id = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED], 1, 1));
+
+    let mut instances = vec!(vec!());
+    instances[id].push((0, make_quad(0.0, 0.0, 2.0, 4.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false)));
+    draw(&mut renderer, instances);
+
+    let window = renderer.get_window();
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = if x < 2 { RED } else { [0, 0, 0, 255] };
+            assert_eq!(window.get_pixel(x, y), expected, "pixel {} {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn tint_multiplies_and_blends_once() {
+    let mut renderer = Renderer::new(4, 4);
+    let id = renderer.add_spritesheet(&make_square_render(), make_texture(&[[255, 255, 255, 255]], 1, 1));
+
+    //both triangles share the diagonal, which must not be blended twice
+    let mut instances = vec!(vec!());
+    instances[id].push((0, make_quad(0.0, 0.0, 4.0, 4.0, [1.0, 0.0, 0.0, 0.5], [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false)));
+    draw(&mut renderer, instances);
+
+    let window = renderer.get_window();
+    let first = window.get_pixel(0, 0);
+    assert!(first[0] > 120 && first[0] < 135 && first[1] == 0 && first[2] == 0, "blended pixel was {:?}", first);
+    for y in 0..4 {
+        for x in 0..4 {
+            assert_eq!(window.get_pixel(x, y), first, "pixel {} {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn square_mirrors_u_unless_mirror_x() {
+    let texture = make_texture(&[RED, BLUE], 2, 1);
+
+    for &(mirror_x, left, right) in &[(false, BLUE, RED), (true, RED, BLUE)] {
+        let mut renderer = Renderer::new(2, 1);
+        let id = renderer.add_spritesheet(&make_square_render(), texture.clone());
+
+        let mut instances = vec!(vec!());
+        instances[id].push((0, make_quad(0.0, 0.0, 2.0, 1.0, WHITE, [0.0, 0.0, 2.0, 1.0], [2.0, 1.0], mirror_x)));
+        draw(&mut renderer, instances);
+
+        assert_eq!(renderer.get_window().get_pixel(0, 0), left, "mirror x {} left", mirror_x);
+        assert_eq!(renderer.get_window().get_pixel(1, 0), right, "mirror x {} right", mirror_x);
+    }
+}
+
+#[test]
+fn spritesheet_rect_picks_the_frame() {
+    let mut renderer = Renderer::new(1, 1);
+    let id = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED, BLUE], 2, 1));
+
+    let mut instances = vec!(vec!());
+    instances[id].push((0, make_quad(0.0, 0.0, 1.0, 1.0, WHITE, [1.0, 0.0, 1.0, 1.0], [2.0, 1.0], false)));
+    draw(&mut renderer, instances);
+
+    assert_eq!(renderer.get_window().get_pixel(0, 0), BLUE);
+}
+
+#[test]
+fn higher_layers_draw_over_lower_ones() {
+    let mut renderer = Renderer::new(1, 1);
+    let red = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED], 1, 1));
+    let blue = renderer.add_spritesheet(&make_square_render(), make_texture(&[BLUE], 1, 1));
+
+    let full = |layer: u8| (layer, make_quad(0.0, 0.0, 1.0, 1.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false));
+
+    //the red bundle comes first but is on the higher layer
+    let mut instances = vec!(vec!(), vec!());
+    instances[red].push(full(2));
+    instances[blue].push(full(1));
+    draw(&mut renderer, instances);
+    assert_eq!(renderer.get_window().get_pixel(0, 0), RED);
+
+    //on the same layer the later bundle wins
+    let mut instances = vec!(vec!(), vec!());
+    instances[red].push(full(1));
+    instances[blue].push(full(1));
+    draw(&mut renderer, instances);
+    assert_eq!(renderer.get_window().get_pixel(0, 0), BLUE);
+}
+
+#[test]
+fn bundle_output_is_a_texture() {
+    let mut renderer = Renderer::new(1, 1);
+    let red = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED], 1, 1));
+    let target = renderer.add_spritesheet(&make_square_render(), Canvas::new(1, 1));
+
+    renderer.set_output(Some(target));
+    let mut instances = vec!(vec!(), vec!());
+    instances[red].push((0, make_quad(0.0, 0.0, 1.0, 1.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false)));
+    draw(&mut renderer, instances);
+
+    renderer.set_output(None);
+    let mut instances = vec!(vec!(), vec!());
+    instances[target].push((0, make_quad(0.0, 0.0, 1.0, 1.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false)));
+    draw(&mut renderer, instances);
+
+    assert_eq!(renderer.get_texture(target).map(|texture| texture.get_pixel(0, 0)), Some(RED));
+    assert_eq!(renderer.get_window().get_pixel(0, 0), RED);
+}
+
+#[test]
+fn batches_are_uploaded_together_and_drawn_by_index() {
+    let mut renderer = Renderer::new(2, 1);
+    let red = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED], 1, 1));
+    let blue = renderer.add_spritesheet(&make_square_render(), make_texture(&[BLUE], 1, 1));
+
+    let left = (0, make_quad(0.0, 0.0, 1.0, 1.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false));
+    let right = (0, make_quad(1.0, 0.0, 1.0, 1.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false));
+    let mut first = vec!(vec!(), vec!());
+    first[red].push(left);
+    let mut second = vec!(vec!(), vec!());
+    second[blue].push(right);
+    second[red].push(right);
+
+    renderer.clear(BLACK);
+    renderer.upload_instances(vec!(first, second));
+    renderer.draw_batch(1, &make_screen_projection(2, 1));
+    assert_eq!(renderer.get_window().get_pixel(0, 0), [0, 0, 0, 255]);
+    assert_eq!(renderer.get_window().get_pixel(1, 0), BLUE);
+
+    renderer.draw_batch(0, &make_screen_projection(2, 1));
+    assert_eq!(renderer.get_window().get_pixel(0, 0), RED);
+
+    //a batch that was never uploaded draws nothing
+    renderer.draw_batch(2, &make_screen_projection(2, 1));
+    assert_eq!(renderer.get_window().get_pixel(1, 0), BLUE);
+}
+
+#[test]
+fn nearer_sprites_hide_later_ones_behind_them() {
+    let mut renderer = Renderer::new(2, 1);
+    let red = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED], 1, 1));
+    let blue = renderer.add_spritesheet(&make_square_render(), make_texture(&[BLUE], 1, 1));
+
+    //the screen projection looks down z, so higher z is nearer
+    let at_depth = |x: f32, z: f32| {
+        let mut instance = make_quad(x, 0.0, 1.0, 1.0, WHITE, [0.0, 0.0, 1.0, 1.0], [1.0, 1.0], false);
+        instance.model_3[2] = z;
+        instance
+    };
+
+    //blue is on the higher layer but behind red on the left, and past the far plane on the right
+    let mut instances = vec!(vec!(), vec!());
+    instances[red].push((0, at_depth(0.0, 0.5)));
+    instances[blue].push((1, at_depth(0.0, 0.0)));
+    instances[blue].push((1, at_depth(1.0, -2.0)));
+    renderer.clear_depth();
+    draw(&mut renderer, instances);
+    assert_eq!(renderer.get_window().get_pixel(0, 0), RED);
+    assert_eq!(renderer.get_window().get_pixel(1, 0), [0, 0, 0, 255]);
+
+    //the same depth passes, so sprites all at one depth draw in layer order
+    let mut instances = vec!(vec!(), vec!());
+    instances[blue].push((0, at_depth(0.0, 0.5)));
+    draw(&mut renderer, instances);
+    assert_eq!(renderer.get_window().get_pixel(0, 0), BLUE);
+
+    //clearing the depth lets anything through again
+    let mut instances = vec!(vec!(), vec!());
+    instances[red].push((0, at_depth(0.0, 0.0)));
+    renderer.clear_depth();
+    draw(&mut renderer, instances);
+    assert_eq!(renderer.get_window().get_pixel(0, 0), RED);
+}
+
+#[test]
+fn png_round_trip() {
+    let mut renderer = Renderer::new(3, 2);
+    let id = renderer.add_spritesheet(&make_square_render(), make_texture(&[RED, BLUE], 2, 1));
+
+    let mut instances = vec!(vec!());
+    instances[id].push((0, make_quad(0.0, 0.0, 3.0, 1.0, WHITE, [0.0, 0.0, 2.0, 1.0], [2.0, 1.0], true)));
+    draw(&mut renderer, instances);
+
+    let image = renderer.to_image();
+    let path = env::temp_dir().join("explore_software_round_trip.png");
+    image.save(&path).expect("save failed");
+    let loaded = Canvas::load(&path).expect("load failed");
+
+    assert_eq!(loaded.count_differences(&image, 0), Some(0));
+    //the bottom row of the window is the last row of the image
+    assert_eq!(loaded.get_pixel(0, 1), RED);
+    assert_eq!(loaded.get_pixel(0, 0), [0, 0, 0, 255]);
+}
@@ -0,0 +1,89 @@
+extern crate graphics;
+
+use graphics::text::{Font, Glyph, glyph_model};
+
+//*************************************************************************************************
+
+//the built in glyphs are 6x8 pixels, so 0.75 of a line wide
+const ADVANCE: f32 = 0.75;
+
+fn make_font() -> Font {
+    Font::new_builtin_canvas().1
+}
+
+fn offsets(glyphs: &[Glyph]) -> Vec<[f32; 2]> {
+    glyphs.iter().map(|glyph| glyph.offset).collect()
+}
+
+#[test]
+fn builtin_font_covers_printable_ascii() {
+    let font = make_font();
+
+    assert_eq!(font.get_texture_size(), [96.0, 48.0]);
+    assert_eq!(font.get_rect(' '), Some([0.0, 0.0, 6.0, 8.0]));
+    assert_eq!(font.get_rect('a'), Some([6.0, 32.0, 6.0, 8.0]));
+    assert_eq!(font.get_rect('~'), Some([84.0, 40.0, 6.0, 8.0]));
+    assert_eq!(font.get_rect('\t'), None);
+}
+
+#[test]
+fn layout_runs_left_to_right_in_line_heights() {
+    let glyphs = make_font().layout("ab");
+
+    assert_eq!(offsets(&glyphs), vec!([0.0, 0.0], [ADVANCE, 0.0]));
+    assert_eq!(glyphs[0].size, [ADVANCE, 1.0]);
+    assert_eq!(glyphs[1].rect, [12.0, 32.0, 6.0, 8.0]);
+}
+
+#[test]
+fn spaces_move_along_without_a_glyph() {
+    let glyphs = make_font().layout("a  b ");
+
+    assert_eq!(offsets(&glyphs), vec!([0.0, 0.0], [ADVANCE * 3.0, 0.0]));
+}
+
+#[test]
+fn newlines_start_the_next_line_below() {
+    let glyphs = make_font().layout("ab\nc\n\nd");
+
+    assert_eq!(offsets(&glyphs), vec!([0.0, 0.0], [ADVANCE, 0.0], [0.0, -1.0], [0.0, -3.0]));
+}
+
+#[test]
+fn unknown_chars_are_drawn_as_question_marks() {
+    let font = make_font();
+    let glyphs = font.layout("aé");
+
+    assert_eq!(glyphs.len(), 2);
+    assert_eq!(Some(glyphs[1].rect), font.get_rect('?'));
+    assert_eq!(glyphs[1].offset, [ADVANCE, 0.0]);
+}
+
+#[test]
+fn empty_text_has_no_glyphs() {
+    assert!(make_font().layout("").is_empty());
+    assert!(make_font().layout(" \n ").is_empty());
+}
+
+#[test]
+fn glyph_model_scales_and_moves_within_the_text() {
+    let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+    let glyph = Glyph {
+        offset: [1.5, -1.0],
+        size: [ADVANCE, 1.0],
+        rect: [0.0, 0.0, 6.0, 8.0],
+    };
+
+    let model = glyph_model(identity, &glyph);
+    assert_eq!(model[0], [ADVANCE, 0.0, 0.0, 0.0]);
+    assert_eq!(model[1], [0.0, 1.0, 0.0, 0.0]);
+    assert_eq!(model[3], [1.5, -1.0, 0.0, 1.0]);
+
+    //text twice as tall moves and scales its glyphs twice as far
+    let mut scaled = identity;
+    scaled[0][0] = 2.0;
+    scaled[1][1] = 2.0;
+    let model = glyph_model(scaled, &glyph);
+    assert_eq!(model[0], [ADVANCE * 2.0, 0.0, 0.0, 0.0]);
+    assert_eq!(model[3], [3.0, -2.0, 0.0, 1.0]);
+}
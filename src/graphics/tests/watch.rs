@@ -0,0 +1,124 @@
+extern crate graphics;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration};
+
+use graphics::watch::{Watcher};
+
+//*************************************************************************************************
+
+fn make_folder(name: &str) -> PathBuf {
+    let folder = env::temp_dir().join(format!("inception-watch-{}", name));
+    let _ = fs::remove_dir_all(&folder);
+    fs::create_dir_all(&folder).expect("create folder failed");
+    folder
+}
+
+fn write_file(path: &Path, contents: &str) {
+    let mut file = File::create(path).expect("create failed");
+    file.write_all(contents.as_bytes()).expect("write failed");
+}
+
+//rewrites path until the file system hands out a new modification time, coarse clocks can need a while
+fn touch(path: &Path) {
+    let before = fs::metadata(path).and_then(|metadata| metadata.modified()).expect("metadata failed");
+    for attempt in 0..300 {
+        write_file(path, &format!("edit {}", attempt));
+        let after = fs::metadata(path).and_then(|metadata| metadata.modified()).expect("metadata failed");
+        if after != before {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("modification time of {} never moved", path.display());
+}
+
+#[test]
+fn nothing_is_reported_until_a_file_changes() {
+    let folder = make_folder("unchanged");
+    let path = folder.join("tiles.png");
+    write_file(&path, "first");
+
+    let mut watcher = Watcher::new();
+    watcher.watch(&path);
+    assert!(watcher.poll().is_empty());
+
+    touch(&path);
+    assert_eq!(watcher.poll(), vec!(path.clone()));
+    assert!(watcher.poll().is_empty());
+
+    fs::remove_dir_all(&folder).expect("remove failed");
+}
+
+#[test]
+fn a_file_watched_twice_is_reported_once() {
+    let folder = make_folder("twice");
+    let path = folder.join("spritesheet_150_v.glsl");
+    write_file(&path, "first");
+
+    let mut watcher = Watcher::new();
+    watcher.watch(&path);
+    watcher.watch_folder(&folder);
+    watcher.watch_folder(&folder);
+
+    touch(&path);
+    assert_eq!(watcher.poll(), vec!(path.clone()));
+
+    fs::remove_dir_all(&folder).expect("remove failed");
+}
+
+#[test]
+fn every_file_under_a_folder_is_watched() {
+    let folder = make_folder("nested");
+    fs::create_dir_all(folder.join("tiles")).expect("create folder failed");
+    let (top, nested) = (folder.join("wires.png"), folder.join("tiles").join("grass.png"));
+    write_file(&top, "first");
+    write_file(&nested, "first");
+
+    let mut watcher = Watcher::new();
+    watcher.watch_folder(&folder);
+    assert!(watcher.poll().is_empty());
+
+    touch(&nested);
+    assert_eq!(watcher.poll(), vec!(nested.clone()));
+
+    fs::remove_dir_all(&folder).expect("remove failed");
+}
+
+#[test]
+fn files_added_to_a_folder_are_reported_once() {
+    let folder = make_folder("added");
+
+    let mut watcher = Watcher::new();
+    watcher.watch_folder(&folder);
+
+    let path = folder.join("gates.png");
+    write_file(&path, "first");
+    assert_eq!(watcher.poll(), vec!(path.clone()));
+    assert!(watcher.poll().is_empty());
+
+    touch(&path);
+    assert_eq!(watcher.poll(), vec!(path.clone()));
+
+    fs::remove_dir_all(&folder).expect("remove failed");
+}
+
+#[test]
+fn missing_and_removed_files_are_not_reported() {
+    let folder = make_folder("removed");
+    let path = folder.join("wires.png");
+    write_file(&path, "first");
+
+    let mut watcher = Watcher::new();
+    watcher.watch(folder.join("missing.png"));
+    watcher.watch(&path);
+
+    fs::remove_file(&path).expect("remove failed");
+    assert!(watcher.poll().is_empty());
+
+    fs::remove_dir_all(&folder).expect("remove failed");
+}
@@ -9,12 +9,88 @@ extern crate components as comps;
 extern crate math;
 extern crate utils;
 
+use std::env;
+use std::path::{PathBuf};
+
+use core::config::{Config};
+use core::bindings::{Bindings};
+
 fn main() {
     env_logger::init().unwrap_or_else(
         |err|
             panic!("unable to initiate env logger: {}", err)
     );
 
-    core::start();
-    info!("game exited successfully");
+    let mut record = None;
+    let mut replay = None;
+    let mut render = None;
+    let mut level = None;
+    let mut snapshot = PathBuf::from("snapshot.txt");
+    let mut svg = None;
+    let mut svg_values = false;
+    let mut png = None;
+    let mut config_path = Config::get_default_path();
+    let mut bindings_path = None;
+    let mut overrides = vec!();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => record = args.next().map(PathBuf::from),
+            "--replay" => replay = args.next().map(PathBuf::from),
+            "--render" => render = args.next().map(PathBuf::from),
+            "--level" => level = args.next().map(PathBuf::from),
+            "--snapshot" => if let Some(path) = args.next() {
+                snapshot = PathBuf::from(path);
+            },
+            "--svg" => svg = args.next().map(PathBuf::from),
+            "--svg-values" => svg_values = true,
+            "--png" => png = args.next().map(PathBuf::from),
+            "--config" => config_path = args.next().map(PathBuf::from),
+            "--bindings" => bindings_path = args.next().map(PathBuf::from),
+            "--width" | "--height" | "--title" | "--vsync" | "--fov" | "--znear" | "--zfar" | "--move-speed" => match args.next() {
+                Some(value) => overrides.push((arg[2..].replace('-', "_"), value)),
+                None => panic!("{} needs a value", arg),
+            },
+            "--set" => match args.next().as_ref().and_then(|setting| setting.find('=').map(|split| (setting[..split].to_string(), setting[split + 1..].to_string()))) {
+                Some(setting) => overrides.push(setting),
+                None => panic!("--set needs key=value"),
+            },
+            other => panic!("unknown argument: {}", other),
+        }
+    }
+
+    let mut config = match config_path {
+        Some(ref path) => Config::load(path),
+        None => Config::new(),
+    };
+    for &(ref key, ref value) in &overrides {
+        if let Err(err) = config.set_override(key, value) {
+            panic!("argument error: {}", err);
+        }
+    }
+
+    match (replay, render) {
+        (Some(replay), _) => {
+            core::replay(&replay, &snapshot, svg.as_ref().map(|path| (path.as_path(), svg_values)), &config);
+            info!("replay finished successfully");
+        },
+        (None, Some(render)) => {
+            if let Err(err) = core::render_save(&render, svg.as_ref().map(|path| (path.as_path(), svg_values)), png.as_ref().map(|path| path.as_path()), &config) {
+                panic!("render error: {}", err);
+            }
+            info!("render finished successfully");
+        },
+        (None, None) => {
+            //bindings live next to the config unless given explicitly
+            let bindings_path = bindings_path.or_else(|| config_path.as_ref().and_then(|path| path.parent()).map(|folder| folder.join("bindings.txt")));
+            let bindings = match bindings_path {
+                Some(ref path) => Bindings::load(path),
+                None => Bindings::new(),
+            };
+
+            core::start(record.as_ref().map(|path| path.as_path()), level.as_ref().map(|path| path.as_path()), config, bindings);
+            info!("game exited successfully");
+        },
+    }
 }
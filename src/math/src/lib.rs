@@ -11,8 +11,12 @@ use std::convert::{Into};
 use utils::{Coord, CoordI};
 
 pub mod ortho_helper;
+pub mod projection;
+pub mod router;
 
 pub use self::ortho_helper::OrthographicHelper;
+pub use self::projection::ViewProjection;
+pub use self::router::Router;
 
 #[derive(Debug, Clone)]
 pub struct Rect {
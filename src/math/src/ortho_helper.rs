@@ -8,6 +8,7 @@ pub struct OrthographicHelper {
     fov: GfxCoord,
     znear: GfxCoord,
     zfar: GfxCoord,
+    zoom: GfxCoord,
 }
 
 impl OrthographicHelper {
@@ -22,6 +23,7 @@ impl OrthographicHelper {
             fov: fov,
             znear: znear,
             zfar: zfar,
+            zoom: 1.0,
         }
     }
 
@@ -29,6 +31,10 @@ impl OrthographicHelper {
         self.aspect_ratio = aspect_ratio;
     }
 
+    pub fn set_zoom(&mut self, zoom: GfxCoord) {
+        self.zoom = zoom;
+    }
+
     pub fn get_aspect_ratio(&self) -> GfxCoord {
         self.aspect_ratio
     }
@@ -45,11 +51,20 @@ impl OrthographicHelper {
         self.zfar
     }
 
+    pub fn get_zoom(&self) -> GfxCoord {
+        self.zoom
+    }
+
     pub fn get_view_depth(&self) -> GfxCoord {
         self.get_zfar() - self.get_znear()
     }
 
+    //the view width scales with tan(fov / 2) so zooming in narrows the fov instead of rebuilding the extents
+    pub fn get_zoomed_fov(&self) -> GfxCoord {
+        ((self.get_fov() / 2.0).tan() / self.get_zoom()).atan() * 2.0
+    }
+
     pub fn build_matrix(&self) -> OrthographicMatrix3<GfxCoord> {
-        OrthographicMatrix3::from_fov(self.get_aspect_ratio(), self.get_fov(), self.get_znear(), self.get_zfar())
+        OrthographicMatrix3::from_fov(self.get_aspect_ratio(), self.get_zoomed_fov(), self.get_znear(), self.get_zfar())
     }
 }
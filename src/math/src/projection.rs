@@ -0,0 +1,79 @@
+use nalgebra::{self, Isometry3, Matrix4, Vector4, OrthographicMatrix3, ToHomogeneous, Inverse};
+
+//*************************************************************************************************
+
+use utils::{Coord, GfxCoord};
+
+use ::Point2;
+
+//*************************************************************************************************
+
+//maps between the world's z = 0 plane and screen points, which run from (0, 0) at the top left of
+//the window to (1, 1) at the bottom right the same as the mouse location
+#[derive(Debug, Clone)]
+pub struct ViewProjection {
+    matrix: Matrix4<GfxCoord>,
+    inverse: Matrix4<GfxCoord>,
+}
+
+impl ViewProjection {
+    //none when the matrix has no inverse, for example a zero sized projection
+    pub fn new(matrix: Matrix4<GfxCoord>) -> Option<ViewProjection> {
+        matrix.inverse().map(|inverse| ViewProjection {
+            matrix: matrix,
+            inverse: inverse,
+        })
+    }
+
+    pub fn new_look_at(
+        eye: &nalgebra::Point3<GfxCoord>,
+        target: &nalgebra::Point3<GfxCoord>,
+        up: &nalgebra::Vector3<GfxCoord>,
+        proj: &OrthographicMatrix3<GfxCoord>
+    ) -> Option<ViewProjection> {
+        ViewProjection::new(*proj.as_matrix() * Isometry3::look_at_rh(eye, target, up).to_homogeneous())
+    }
+
+    pub fn get_matrix(&self) -> &Matrix4<GfxCoord> {
+        &self.matrix
+    }
+
+    pub fn get_inverse(&self) -> &Matrix4<GfxCoord> {
+        &self.inverse
+    }
+
+    pub fn project(&self, world_point: Point2) -> Point2 {
+        let clip = self.matrix * Vector4::new(world_point.get_x() as GfxCoord, world_point.get_y() as GfxCoord, 0.0, 1.0);
+        let (x, y) = (clip.x / clip.w, clip.y / clip.w);
+        Point2::new(
+            (x as Coord + 1.0) / 2.0,
+            (1.0 - y as Coord) / 2.0
+        )
+    }
+
+    //casts the screen point from the near plane to the far plane and returns where it crosses z = 0
+    pub fn unproject(&self, screen_point: Point2) -> Point2 {
+        let x = (screen_point.get_x() * 2.0 - 1.0) as GfxCoord;
+        let y = (1.0 - screen_point.get_y() * 2.0) as GfxCoord;
+
+        let near = self.unproject_ndc(x, y, -1.0);
+        let far = self.unproject_ndc(x, y, 1.0);
+
+        let depth = near.z - far.z;
+        let t = if depth.abs() > ::std::f32::EPSILON {
+            near.z / depth
+        } else {
+            0.0
+        };
+
+        Point2::new(
+            (near.x + (far.x - near.x) * t) as Coord,
+            (near.y + (far.y - near.y) * t) as Coord
+        )
+    }
+
+    fn unproject_ndc(&self, x: GfxCoord, y: GfxCoord, z: GfxCoord) -> nalgebra::Point3<GfxCoord> {
+        let point = self.inverse * Vector4::new(x, y, z, 1.0);
+        nalgebra::Point3::new(point.x / point.w, point.y / point.w, point.z / point.w)
+    }
+}
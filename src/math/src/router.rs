@@ -0,0 +1,241 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::cmp::Ordering;
+
+//*************************************************************************************************
+
+use utils::CoordI;
+
+use ::{Point2I, Point3I};
+
+//*************************************************************************************************
+
+const DIRECTIONS: [(CoordI, CoordI, CoordI); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+const NO_DIRECTION: usize = 6;
+
+#[derive(Debug, Clone)]
+pub struct Router {
+    bend_cost: u32,
+    layer_cost: u32,
+    layers: Option<(CoordI, CoordI)>,
+    search_limit: usize,
+    bounds: Option<(Point2I, Point2I)>,
+}
+
+impl Router {
+    pub fn new(
+        bend_cost: u32,
+        layer_cost: u32,
+        layers: Option<(CoordI, CoordI)>,
+        search_limit: usize
+    ) -> Router {
+        Router {
+            bend_cost: bend_cost,
+            layer_cost: layer_cost,
+            layers: layers,
+            search_limit: search_limit,
+            bounds: None,
+        }
+    }
+
+    pub fn new_flat() -> Router {
+        Router::new(4, 0, None, 100000)
+    }
+
+    pub fn new_layered(min_layer: CoordI, max_layer: CoordI) -> Router {
+        Router::new(4, 8, Some((min_layer, max_layer)), 100000)
+    }
+
+    pub fn get_bend_cost(&self) -> u32 {
+        self.bend_cost
+    }
+
+    pub fn get_layer_cost(&self) -> u32 {
+        self.layer_cost
+    }
+
+    pub fn get_layers(&self) -> Option<(CoordI, CoordI)> {
+        self.layers
+    }
+
+    pub fn get_search_limit(&self) -> usize {
+        self.search_limit
+    }
+
+    pub fn get_bounds(&self) -> Option<&(Point2I, Point2I)> {
+        self.bounds.as_ref()
+    }
+
+    //the lowest and highest cells a route may pass through, inclusive, so a search with nowhere to go runs dry
+    pub fn set_bounds(&mut self, bounds: Option<(Point2I, Point2I)>) {
+        self.bounds = bounds;
+    }
+
+    //returns every cell from start to goal inclusive, start and goal are never checked against is_blocked
+    pub fn route<F>(&self, start: Point3I, goal: Point3I, is_blocked: F) -> Option<Vec<Point3I>>
+    where F: Fn(&Point3I) -> bool
+    {
+        if start == goal {
+            return Some(vec!(start));
+        }
+
+        if let Some((min_layer, max_layer)) = self.layers {
+            if goal.get_z() < min_layer || goal.get_z() > max_layer {
+                return None;
+            }
+        } else if start.get_z() != goal.get_z() {
+            return None;
+        }
+
+        if !self.is_in_bounds(&goal) {
+            return None;
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<(Point3I, usize), u32> = HashMap::new();
+        let mut came_from: HashMap<(Point3I, usize), (Point3I, usize)> = HashMap::new();
+
+        costs.insert((start.clone(), NO_DIRECTION), 0);
+        open.push(Node {
+            estimate: self.heuristic(&start, &goal),
+            cost: 0,
+            location: start.clone(),
+            direction: NO_DIRECTION,
+        });
+
+        let mut expanded = 0;
+
+        while let Some(node) = open.pop() {
+            if node.location == goal {
+                return Some(self.rebuild_path(&came_from, (node.location, node.direction)));
+            }
+
+            match costs.get(&(node.location.clone(), node.direction)) {
+                Some(&cost) if cost < node.cost => continue,
+                _ => (),
+            }
+
+            expanded += 1;
+            if expanded > self.search_limit {
+                warn!("route search limit of {} reached", self.search_limit);
+                return None;
+            }
+
+            for (direction, &(dx, dy, dz)) in DIRECTIONS.iter().enumerate() {
+                let is_layer_change = dz != 0;
+
+                let next = Point3I::new(
+                    node.location.get_x() + dx,
+                    node.location.get_y() + dy,
+                    node.location.get_z() + dz
+                );
+
+                if is_layer_change {
+                    match self.layers {
+                        Some((min_layer, max_layer)) => if next.get_z() < min_layer || next.get_z() > max_layer {
+                            continue;
+                        },
+                        None => continue,
+                    }
+                }
+
+                if !self.is_in_bounds(&next) {
+                    continue;
+                }
+
+                if next != goal && is_blocked(&next) {
+                    continue;
+                }
+
+                let mut step = 1;
+                if is_layer_change {
+                    step += self.layer_cost;
+                } else if node.direction < 4 && node.direction != direction {
+                    step += self.bend_cost;
+                }
+
+                let cost = node.cost + step;
+                let key = (next.clone(), direction);
+
+                let better = match costs.get(&key) {
+                    Some(&old) => cost < old,
+                    None => true,
+                };
+
+                if better {
+                    costs.insert(key.clone(), cost);
+                    came_from.insert(key, (node.location.clone(), node.direction));
+                    open.push(Node {
+                        estimate: cost + self.heuristic(&next, &goal),
+                        cost: cost,
+                        location: next,
+                        direction: direction,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn is_in_bounds(&self, location: &Point3I) -> bool {
+        match self.bounds {
+            Some((ref min, ref max)) => location.get_x() >= min.get_x() && location.get_x() <= max.get_x() &&
+                location.get_y() >= min.get_y() && location.get_y() <= max.get_y(),
+            None => true,
+        }
+    }
+
+    fn heuristic(&self, from: &Point3I, to: &Point3I) -> u32 {
+        let planar = (from.get_x() - to.get_x()).abs() + (from.get_y() - to.get_y()).abs();
+        let layers = (from.get_z() - to.get_z()).abs() as u32;
+        planar as u32 + layers * (1 + self.layer_cost)
+    }
+
+    fn rebuild_path(&self, came_from: &HashMap<(Point3I, usize), (Point3I, usize)>, end: (Point3I, usize)) -> Vec<Point3I> {
+        let mut path = vec!(end.0.clone());
+        let mut current = end;
+        while let Some(previous) = came_from.get(&current) {
+            path.push(previous.0.clone());
+            current = previous.clone();
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Node {
+    estimate: u32,
+    cost: u32,
+    location: Point3I,
+    direction: usize,
+}
+
+//reversed so the binary heap pops the cheapest estimate first, preferring deeper nodes on ties,
+//the location and direction only break the remaining ties so the order agrees with eq
+impl Ord for Node {
+    fn cmp(&self, other: &Node) -> Ordering {
+        let key = |node: &Node| (node.location.get_x(), node.location.get_y(), node.location.get_z(), node.direction);
+        match other.estimate.cmp(&self.estimate) {
+            Ordering::Equal => match self.cost.cmp(&other.cost) {
+                Ordering::Equal => key(other).cmp(&key(self)),
+                ordering => ordering,
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Node) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
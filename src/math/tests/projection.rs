@@ -0,0 +1,122 @@
+extern crate nalgebra;
+
+extern crate math;
+extern crate utils;
+
+use math::{OrthographicHelper, Point2, ViewProjection};
+
+use utils::{Coord, GfxCoord};
+
+//*************************************************************************************************
+
+const ASPECT_RATIOS: &'static [GfxCoord] = &[0.5, 1.0, 4.0 / 3.0, 16.0 / 9.0, 3.0];
+const ZOOMS: &'static [GfxCoord] = &[0.1, 0.5, 1.0, 2.0, 8.0];
+const FOVS: &'static [GfxCoord] = &[90.0, ::std::f32::consts::FRAC_PI_2];
+const OFFSETS: &'static [(Coord, Coord)] = &[(0.0, 0.0), (12.5, -3.0), (-40.0, 25.0)];
+
+fn make_view_projection(offset: (Coord, Coord), aspect_ratio: GfxCoord, fov: GfxCoord, zoom: GfxCoord) -> ViewProjection {
+    let mut ortho_helper = OrthographicHelper::new(aspect_ratio, fov, 0.0, 10.0);
+    ortho_helper.set_zoom(zoom);
+
+    let (x, y) = (offset.0 as GfxCoord, offset.1 as GfxCoord);
+    ViewProjection::new_look_at(
+        &nalgebra::Point3::new(x, y, 2.0),
+        &nalgebra::Point3::new(x, y, 0.0),
+        &nalgebra::Vector3::new(0.0, 1.0, 0.0),
+        &ortho_helper.build_matrix()
+    ).expect("view projection was not invertible")
+}
+
+fn for_each_view<F: FnMut(&ViewProjection, (Coord, Coord), GfxCoord, GfxCoord)>(mut f: F) {
+    for &aspect_ratio in ASPECT_RATIOS {
+        for &zoom in ZOOMS {
+            for &fov in FOVS {
+                for &offset in OFFSETS {
+                    f(&make_view_projection(offset, aspect_ratio, fov, zoom), offset, aspect_ratio, zoom);
+                }
+            }
+        }
+    }
+}
+
+//single precision matrices, so allow an error relative to how much world the view covers
+fn assert_close(a: &Point2, b: &Point2, scale: Coord, what: &str) {
+    let tolerance = 1e-4 * scale.max(1.0);
+    assert!(
+        (a.get_x() - b.get_x()).abs() <= tolerance && (a.get_y() - b.get_y()).abs() <= tolerance,
+        "{}: ({}, {}) != ({}, {})", what, a.get_x(), a.get_y(), b.get_x(), b.get_y()
+    );
+}
+
+fn view_scale(view_projection: &ViewProjection, offset: (Coord, Coord)) -> Coord {
+    let corner = view_projection.unproject(Point2::new(0.0, 0.0));
+    (corner.get_x() - offset.0).abs().max((corner.get_y() - offset.1).abs()) + offset.0.abs().max(offset.1.abs())
+}
+
+#[test]
+fn screen_round_trip() {
+    for_each_view(|view_projection, offset, aspect_ratio, zoom| {
+        let scale = view_scale(view_projection, offset);
+        for &(x, y) in &[(0.0, 0.0), (1.0, 1.0), (0.5, 0.5), (0.25, 0.8), (0.9, 0.1)] {
+            let screen = Point2::new(x, y);
+            let round_trip = view_projection.project(view_projection.unproject(screen.clone()));
+            assert_close(&round_trip, &screen, scale, &format!("screen aspect {} zoom {}", aspect_ratio, zoom));
+        }
+    });
+}
+
+#[test]
+fn world_round_trip() {
+    for_each_view(|view_projection, offset, aspect_ratio, zoom| {
+        let scale = view_scale(view_projection, offset);
+        for &(x, y) in &[(0.0, 0.0), (1.5, -2.0), (-7.25, 3.5)] {
+            let world = Point2::new(offset.0 + x, offset.1 + y);
+            let round_trip = view_projection.unproject(view_projection.project(world.clone()));
+            assert_close(&round_trip, &world, scale, &format!("world aspect {} zoom {}", aspect_ratio, zoom));
+        }
+    });
+}
+
+#[test]
+fn screen_centre_is_camera_offset() {
+    for_each_view(|view_projection, offset, _, _| {
+        let scale = view_scale(view_projection, offset);
+        let centre = view_projection.unproject(Point2::new(0.5, 0.5));
+        assert_close(&centre, &Point2::new(offset.0, offset.1), scale, "centre");
+    });
+}
+
+#[test]
+fn screen_axes_follow_the_mouse() {
+    for_each_view(|view_projection, _, _, _| {
+        let top_left = view_projection.unproject(Point2::new(0.0, 0.0));
+        let bottom_right = view_projection.unproject(Point2::new(1.0, 1.0));
+        assert!(top_left.get_x() < bottom_right.get_x(), "screen x should grow to the right");
+        assert!(top_left.get_y() > bottom_right.get_y(), "screen y should grow downwards");
+    });
+}
+
+#[test]
+fn view_matches_aspect_ratio() {
+    for_each_view(|view_projection, _, aspect_ratio, _| {
+        let top_left = view_projection.unproject(Point2::new(0.0, 0.0));
+        let bottom_right = view_projection.unproject(Point2::new(1.0, 1.0));
+        let width = bottom_right.get_x() - top_left.get_x();
+        let height = top_left.get_y() - bottom_right.get_y();
+        assert!((width / height - aspect_ratio as Coord).abs() < 1e-3, "{} / {} is not {}", width, height, aspect_ratio);
+    });
+}
+
+#[test]
+fn zoom_shrinks_the_view() {
+    for &aspect_ratio in ASPECT_RATIOS {
+        for &fov in FOVS {
+            let width = |zoom: GfxCoord| {
+                let view_projection = make_view_projection((0.0, 0.0), aspect_ratio, fov, zoom);
+                view_projection.unproject(Point2::new(1.0, 0.5)).get_x() - view_projection.unproject(Point2::new(0.0, 0.5)).get_x()
+            };
+            let (near, far) = (width(4.0), width(1.0));
+            assert!((far / near - 4.0).abs() < 1e-3, "zooming 4x changed the width by {}", far / near);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+extern crate math;
+
+use math::{Point2I, Point3I, Router};
+
+//*************************************************************************************************
+
+fn is_adjacent(a: &Point3I, b: &Point3I) -> bool {
+    (a.get_x() - b.get_x()).abs() + (a.get_y() - b.get_y()).abs() + (a.get_z() - b.get_z()).abs() == 1
+}
+
+fn check_path(path: &[Point3I], start: &Point3I, goal: &Point3I) {
+    assert_eq!(path.first(), Some(start));
+    assert_eq!(path.last(), Some(goal));
+    for pair in path.windows(2) {
+        assert!(is_adjacent(&pair[0], &pair[1]), "{:?} and {:?} are not neighbours", pair[0], pair[1]);
+    }
+}
+
+#[test]
+fn straight_path_has_no_bends() {
+    let start = Point3I::new(0, 0, 0);
+    let goal = Point3I::new(5, 0, 0);
+
+    let path = Router::new_flat().route(start.clone(), goal.clone(), |_| false).expect("no route found");
+
+    check_path(&path, &start, &goal);
+    assert_eq!(path.len(), 6);
+    assert!(path.iter().all(|cell| cell.get_y() == 0 && cell.get_z() == 0));
+}
+
+#[test]
+fn detours_around_an_obstacle() {
+    let start = Point3I::new(0, 0, 0);
+    let goal = Point3I::new(4, 0, 0);
+    //a wall across the straight line, open above and below
+    let is_blocked = |cell: &Point3I| cell.get_x() == 2 && cell.get_y() >= -1 && cell.get_y() <= 1;
+
+    let path = Router::new_flat().route(start.clone(), goal.clone(), &is_blocked).expect("no route found");
+
+    check_path(&path, &start, &goal);
+    assert!(path.iter().all(|cell| !is_blocked(cell)));
+    assert!(path.iter().any(|cell| cell.get_y().abs() == 2));
+}
+
+#[test]
+fn changes_layer_to_cross_a_wall() {
+    let start = Point3I::new(0, 0, 0);
+    let goal = Point3I::new(4, 0, 0);
+    //the whole of layer 0 is walled off at x 2, layer 1 is open
+    let is_blocked = |cell: &Point3I| cell.get_z() == 0 && cell.get_x() == 2;
+
+    let path = Router::new_layered(0, 1).route(start.clone(), goal.clone(), &is_blocked).expect("no route found");
+
+    check_path(&path, &start, &goal);
+    assert!(path.iter().all(|cell| !is_blocked(cell)));
+    assert!(path.iter().any(|cell| cell.get_z() == 1));
+    assert!(path.iter().all(|cell| cell.get_z() >= 0 && cell.get_z() <= 1));
+}
+
+#[test]
+fn flat_router_stays_on_its_layer() {
+    let path = Router::new_flat().route(Point3I::new(0, 0, 0), Point3I::new(3, 0, 1), |_| false);
+
+    assert_eq!(path, None);
+}
+
+#[test]
+fn enclosed_target_is_unreachable() {
+    let goal = Point3I::new(3, 0, 0);
+    //no search limit to fall back on, the bounds alone have to end the search
+    let mut router = Router::new(4, 0, None, usize::max_value());
+    router.set_bounds(Some((Point2I::new(-2, -2), Point2I::new(5, 2))));
+    //every neighbour of the goal is taken, the goal itself is never checked
+    let is_blocked = |cell: &Point3I| {
+        assert!(cell.get_x() >= -2 && cell.get_x() <= 5 && cell.get_y() >= -2 && cell.get_y() <= 2, "{:?} is out of bounds", cell);
+        cell != &goal && (cell.get_x() - 3).abs() + cell.get_y().abs() == 1
+    };
+
+    let path = router.route(Point3I::new(0, 0, 0), goal.clone(), is_blocked);
+
+    assert_eq!(path, None);
+}
+
+#[test]
+fn bounds_close_off_a_detour() {
+    let start = Point3I::new(0, 0, 0);
+    let goal = Point3I::new(4, 0, 0);
+    let is_blocked = |cell: &Point3I| cell.get_x() == 2 && cell.get_y() >= -1 && cell.get_y() <= 1;
+    let mut router = Router::new_flat();
+
+    router.set_bounds(Some((Point2I::new(0, -1), Point2I::new(4, 1))));
+    assert_eq!(router.route(start.clone(), goal.clone(), &is_blocked), None);
+
+    router.set_bounds(Some((Point2I::new(0, -2), Point2I::new(4, 2))));
+    let path = router.route(start.clone(), goal.clone(), &is_blocked).expect("no route found");
+    check_path(&path, &start, &goal);
+    assert!(path.iter().all(|cell| cell.get_y().abs() <= 2 && cell.get_x() >= 0 && cell.get_x() <= 4));
+}
+
+#[test]
+fn goal_out_of_bounds_is_unreachable() {
+    let mut router = Router::new_flat();
+    router.set_bounds(Some((Point2I::new(0, 0), Point2I::new(3, 3))));
+
+    assert_eq!(router.route(Point3I::new(0, 0, 0), Point3I::new(4, 0, 0), |_| false), None);
+}
+
+#[test]
+fn start_is_its_own_route() {
+    let start = Point3I::new(2, 2, 0);
+
+    let path = Router::new_flat().route(start.clone(), start.clone(), |_| true);
+
+    assert_eq!(path, Some(vec!(start)));
+}
@@ -0,0 +1,183 @@
+use std::io::{BufRead};
+
+//*************************************************************************************************
+
+use comps::{GateKind};
+
+use ::{ParseError, NetlistBuilder};
+
+//*************************************************************************************************
+
+pub fn read<R: BufRead>(reader: R) -> Result<::Netlist, ParseError> {
+    let mut builder = NetlistBuilder::new();
+    let mut current: Option<(usize, Vec<String>, Vec<(String, char)>)> = None;
+    let mut pending = String::new();
+    let mut pending_line = 0;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => return Err(ParseError::new(line_number, &format!("read error: {}", err))),
+        };
+
+        let line = match line.find('#') {
+            Some(comment) => line[..comment].to_string(),
+            None => line,
+        };
+
+        if pending.is_empty() {
+            pending_line = line_number;
+        }
+
+        let trimmed = line.trim_right();
+        if trimmed.ends_with('\\') {
+            pending.push_str(&trimmed[..trimmed.len() - 1]);
+            pending.push(' ');
+            continue;
+        }
+        pending.push_str(trimmed);
+
+        let full = pending.clone();
+        pending.clear();
+
+        let tokens: Vec<&str> = full.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        if tokens[0].starts_with('.') {
+            if let Some((start_line, signals, rows)) = current.take() {
+                try!(finish_names(&mut builder, start_line, signals, rows));
+            }
+
+            match tokens[0] {
+                ".model" => (),
+                ".inputs" => for name in &tokens[1..] {
+                    builder.add_input(name);
+                },
+                ".outputs" => for name in &tokens[1..] {
+                    builder.add_output(pending_line, name);
+                },
+                ".names" => {
+                    if tokens.len() < 2 {
+                        return Err(ParseError::new(pending_line, ".names needs at least an output signal"));
+                    }
+                    current = Some((pending_line, tokens[1..].iter().map(|token| token.to_string()).collect(), vec!()));
+                },
+                ".end" => break,
+                other => return Err(ParseError::new(pending_line, &format!("unsupported directive {}", other))),
+            }
+        } else {
+            match current {
+                Some((_, ref signals, ref mut rows)) => {
+                    let (plane, output) = if signals.len() == 1 {
+                        ("", tokens[0])
+                    } else if tokens.len() == 2 {
+                        (tokens[0], tokens[1])
+                    } else {
+                        return Err(ParseError::new(pending_line, "cover rows need an input plane and an output value"));
+                    };
+                    let output = match output {
+                        "1" => '1',
+                        "0" => '0',
+                        _ => return Err(ParseError::new(pending_line, "cover output must be 0 or 1")),
+                    };
+                    rows.push((plane.to_string(), output));
+                },
+                None => return Err(ParseError::new(pending_line, "cover row outside of .names")),
+            }
+        }
+    }
+
+    if let Some((start_line, signals, rows)) = current.take() {
+        try!(finish_names(&mut builder, start_line, signals, rows));
+    }
+
+    builder.build()
+}
+
+fn finish_names(builder: &mut NetlistBuilder, line: usize, mut signals: Vec<String>, rows: Vec<(String, char)>) -> Result<(), ParseError> {
+    let output = match signals.pop() {
+        Some(output) => output,
+        None => return Err(ParseError::new(line, ".names needs an output signal")),
+    };
+
+    //a constant has no inputs, a 1 row makes it high and no rows or a 0 row low, built like verilog's constant assigns
+    if signals.is_empty() {
+        let value = match rows.len() {
+            0 => "0",
+            1 if rows[0].1 == '1' => "1",
+            1 => "0",
+            _ => return Err(ParseError::new(line, &format!("constant {} needs at most one row", output))),
+        };
+        return builder.add_alias(line, &output, value);
+    }
+
+    let kind = match classify(signals.len(), &rows) {
+        Some(kind) => kind,
+        None => return Err(ParseError::new(line, &format!("cover for {} is not a supported gate", output))),
+    };
+
+    builder.add_gate(line, kind, signals, &output)
+}
+
+fn classify(inputs: usize, rows: &[(String, char)]) -> Option<GateKind> {
+    let rows: Vec<(&str, char)> = rows.iter().map(|&(ref plane, output)| (plane.as_str(), output)).collect();
+
+    if inputs == 0 || rows.iter().any(|&(plane, _)| plane.len() != inputs) {
+        return None;
+    }
+
+    let ones: String = (0..inputs).map(|_| '1').collect();
+    let zeros: String = (0..inputs).map(|_| '0').collect();
+
+    if inputs == 1 {
+        if rows.len() != 1 {
+            return None;
+        }
+        return match rows[0] {
+            ("1", '1') | ("0", '0') => Some(GateKind::Buf),
+            ("0", '1') | ("1", '0') => Some(GateKind::Not),
+            _ => None,
+        };
+    }
+
+    if rows.len() == 1 {
+        let (plane, output) = rows[0];
+        if plane == ones {
+            return Some(if output == '1' { GateKind::And } else { GateKind::Nand });
+        }
+        if plane == zeros {
+            return Some(if output == '1' { GateKind::Nor } else { GateKind::Or });
+        }
+    }
+
+    let is_single_literal = |plane: &str, literal: char| {
+        plane.chars().filter(|&c| c == literal).count() == 1 && plane.chars().all(|c| c == literal || c == '-')
+    };
+
+    if rows.len() == inputs {
+        if rows.iter().all(|&(plane, output)| output == '1' && is_single_literal(plane, '1')) {
+            return Some(GateKind::Or);
+        }
+        if rows.iter().all(|&(plane, output)| output == '1' && is_single_literal(plane, '0')) {
+            return Some(GateKind::Nand);
+        }
+    }
+
+    if inputs == 2 {
+        let mut on: Vec<&str> = rows.iter().filter(|&&(_, output)| output == '1').map(|&(plane, _)| plane).collect();
+        on.sort();
+        if on.len() == rows.len() {
+            if on == ["01", "10"] {
+                return Some(GateKind::Xor);
+            }
+            if on == ["00", "11"] {
+                return Some(GateKind::Xnor);
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,187 @@
+use std::collections::{HashSet};
+
+//*************************************************************************************************
+
+use math::{Point3I, Router};
+
+use utils::{CoordI};
+
+use ::{Netlist, NodeKind};
+
+//*************************************************************************************************
+
+const COLUMN_SPACING: CoordI = 4;
+const ROW_SPACING: CoordI = 3;
+
+#[derive(Debug, Clone)]
+pub struct Route {
+    sink: usize,
+    input: usize,
+    path: Vec<Point3I>,
+}
+
+impl Route {
+    pub fn get_sink(&self) -> usize {
+        self.sink
+    }
+
+    pub fn get_input(&self) -> usize {
+        self.input
+    }
+
+    //every cell from the driver to the sink inclusive
+    pub fn get_path(&self) -> &[Point3I] {
+        self.path.as_slice()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    locations: Vec<Point3I>,
+    routes: Vec<Route>,
+}
+
+impl Layout {
+    pub fn get_location(&self, node: usize) -> &Point3I {
+        &self.locations[node]
+    }
+
+    pub fn get_locations(&self) -> &[Point3I] {
+        self.locations.as_slice()
+    }
+
+    pub fn get_routes(&self) -> &[Route] {
+        self.routes.as_slice()
+    }
+
+    pub fn get_route(&self, sink: usize, input: usize) -> Option<&Route> {
+        self.routes.iter().find(|route| route.sink == sink && route.input == input)
+    }
+
+    pub fn get_first_route_from(&self, driver: &Point3I) -> Option<&Route> {
+        self.routes.iter().find(|route| route.path.first() == Some(driver))
+    }
+}
+
+//places inputs in the first column, each gate one column after its latest driver and outputs last,
+//then routes every net with the given router around is_blocked and everything already placed
+pub fn place_and_route<F>(netlist: &Netlist, origin: Point3I, router: &Router, is_blocked: F) -> Result<Layout, String>
+where F: Fn(&Point3I) -> bool
+{
+    let nodes = netlist.get_nodes();
+    let levels = try!(make_levels(netlist));
+
+    let columns = match levels.iter().max() {
+        Some(&max) => max + 1,
+        None => return Ok(Layout {
+            locations: vec!(),
+            routes: vec!(),
+        }),
+    };
+
+    let mut rows = vec!(0.0; nodes.len());
+    let mut locations = vec!(Point3I::zero(); nodes.len());
+
+    for column in 0..columns {
+        let mut members: Vec<usize> = (0..nodes.len()).filter(|&index| levels[index] == column).collect();
+
+        //order each column by the mean row of its drivers to cut down on crossings
+        let barycenters: Vec<f64> = members.iter().map(|&index| {
+            let drivers: Vec<usize> = nodes[index].get_inputs().iter().filter_map(|net| net.as_ref().map(|net| net.get_driver())).collect();
+            if drivers.is_empty() {
+                index as f64
+            } else {
+                drivers.iter().map(|&driver| rows[driver]).sum::<f64>() / drivers.len() as f64
+            }
+        }).collect();
+
+        let mut order: Vec<usize> = (0..members.len()).collect();
+        order.sort_by(|&a, &b| barycenters[a].partial_cmp(&barycenters[b]).unwrap_or(::std::cmp::Ordering::Equal));
+        members = order.into_iter().map(|position| members[position]).collect();
+
+        for (row, &index) in members.iter().enumerate() {
+            rows[index] = row as f64;
+            let location = Point3I::new(
+                origin.get_x() + column as CoordI * COLUMN_SPACING,
+                origin.get_y() - row as CoordI * ROW_SPACING,
+                origin.get_z()
+            );
+            if is_blocked(&location) {
+                return Err(format!("layout cell {:?} is already occupied", location));
+            }
+            locations[index] = location;
+        }
+    }
+
+    let mut occupied: HashSet<Point3I> = locations.iter().cloned().collect();
+    let mut routes = vec!();
+
+    let mut sinks: Vec<usize> = (0..nodes.len()).collect();
+    sinks.sort_by_key(|&index| levels[index]);
+
+    for sink in sinks {
+        for (input, net) in nodes[sink].get_inputs().iter().enumerate() {
+            let net = match *net {
+                Some(ref net) => net,
+                None => continue,
+            };
+
+            let start = locations[net.get_driver()].clone();
+            let goal = locations[sink].clone();
+
+            let path = match router.route(start.clone(), goal.clone(), |location| occupied.contains(location) || is_blocked(location)) {
+                Some(path) => path,
+                None => return Err(format!("could not route from {:?} to {:?}", start, goal)),
+            };
+
+            for location in &path[1..path.len() - 1] {
+                occupied.insert(location.clone());
+            }
+
+            routes.push(Route {
+                sink: sink,
+                input: input,
+                path: path,
+            });
+        }
+    }
+
+    Ok(Layout {
+        locations: locations,
+        routes: routes,
+    })
+}
+
+fn make_levels(netlist: &Netlist) -> Result<Vec<usize>, String> {
+    let nodes = netlist.get_nodes();
+    let mut levels = vec!(0; nodes.len());
+
+    let mut changed = true;
+    let mut passes = 0;
+    while changed {
+        changed = false;
+        passes += 1;
+        if passes > nodes.len() + 1 {
+            return Err("netlist has a combinational loop".to_string());
+        }
+
+        for (index, node) in nodes.iter().enumerate() {
+            if let NodeKind::Gate(_) = *node.get_kind() {
+                let level = 1 + node.get_inputs().iter().filter_map(|net| net.as_ref().map(|net| levels[net.get_driver()])).max().unwrap_or(0);
+                if level != levels[index] {
+                    levels[index] = level;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    let last = levels.iter().max().cloned().unwrap_or(0) + 1;
+    for (index, node) in nodes.iter().enumerate() {
+        if let NodeKind::Output(_) = *node.get_kind() {
+            levels[index] = last;
+        }
+    }
+
+    Ok(levels)
+}
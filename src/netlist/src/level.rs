@@ -0,0 +1,392 @@
+use std::io::{BufRead};
+use std::collections::{HashSet};
+use std::fmt;
+
+//*************************************************************************************************
+
+use math::{Point3I};
+
+use utils::{CoordI};
+
+use sim::{Simulator};
+
+use ::{Netlist, NodeKind, ParseError};
+
+//*************************************************************************************************
+
+const DEFAULT_SETTLE_LIMIT: u32 = 256;
+
+#[derive(Debug, Clone)]
+pub struct Port {
+    name: String,
+    location: Point3I,
+}
+
+impl Port {
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_location(&self) -> &Point3I {
+        &self.location
+    }
+}
+
+//one row of the truth table, values in the order the ports were declared
+#[derive(Debug, Clone)]
+pub struct Case {
+    inputs: Vec<u8>,
+    outputs: Vec<u8>,
+}
+
+impl Case {
+    pub fn get_inputs(&self) -> &[u8] {
+        self.inputs.as_slice()
+    }
+
+    pub fn get_outputs(&self) -> &[u8] {
+        self.outputs.as_slice()
+    }
+}
+
+//a level file is one statement per line:
+//  name <name>
+//  size <width> <height>
+//  input <name> <x> <y>
+//  output <name> <x> <y>
+//  allow <wire|in|out|gate kind> [count]
+//  settle <ticks>
+//  case <input values...> : <output values...>
+//once any allow line is given only the allowed kinds can be placed
+#[derive(Debug, Clone)]
+pub struct Level {
+    name: String,
+    size: (CoordI, CoordI),
+    inputs: Vec<Port>,
+    outputs: Vec<Port>,
+    limits: Vec<(String, Option<u32>)>,
+    cases: Vec<Case>,
+    settle_limit: u32,
+}
+
+impl Level {
+    pub fn read<R: BufRead>(reader: R) -> Result<Level, ParseError> {
+        let mut level = Level {
+            name: "level".to_string(),
+            size: (0, 0),
+            inputs: vec!(),
+            outputs: vec!(),
+            limits: vec!(),
+            cases: vec!(),
+            settle_limit: DEFAULT_SETTLE_LIMIT,
+        };
+
+        //the checks after reading point at the line that caused them
+        let mut size_line = 0;
+        let mut port_lines = (vec!(), vec!());
+        let mut case_lines = vec!();
+        let mut last_line = 0;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line_number = index + 1;
+            last_line = line_number;
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Err(ParseError::new(line_number, &format!("read error: {}", err))),
+            };
+
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => line.as_str(),
+            };
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let number = |index: usize| -> Result<CoordI, ParseError> {
+                match words.get(index).and_then(|word| word.parse().ok()) {
+                    Some(value) => Ok(value),
+                    None => Err(ParseError::new(line_number, &format!("{} expects a number", words[0]))),
+                }
+            };
+            let port = || -> Result<Port, ParseError> {
+                match words.get(1) {
+                    Some(name) => Ok(Port {
+                        name: name.to_string(),
+                        location: Point3I::new(try!(number(2)), try!(number(3)), 0),
+                    }),
+                    None => Err(ParseError::new(line_number, &format!("{} needs a name", words[0]))),
+                }
+            };
+
+            match words.first() {
+                None => (),
+                Some(&"name") => level.name = words[1..].join(" "),
+                Some(&"size") => {
+                    level.size = (try!(number(1)), try!(number(2)));
+                    size_line = line_number;
+                },
+                Some(&"input") => {
+                    level.inputs.push(try!(port()));
+                    port_lines.0.push(line_number);
+                },
+                Some(&"output") => {
+                    level.outputs.push(try!(port()));
+                    port_lines.1.push(line_number);
+                },
+                Some(&"allow") => {
+                    let kind = match words.get(1) {
+                        Some(kind) => kind.to_string(),
+                        None => return Err(ParseError::new(line_number, "allow needs a kind")),
+                    };
+                    let count = match words.get(2) {
+                        Some(_) => Some(try!(number(2)) as u32),
+                        None => None,
+                    };
+                    level.limits.push((kind, count));
+                },
+                Some(&"settle") => level.settle_limit = try!(number(1)) as u32,
+                Some(&"case") => {
+                    let split = match words.iter().position(|&word| word == ":") {
+                        Some(split) => split,
+                        None => return Err(ParseError::new(line_number, "case needs a : between inputs and outputs")),
+                    };
+                    let mut values = vec!();
+                    for word in words[1..].iter().filter(|&&word| word != ":") {
+                        match word.parse::<u8>() {
+                            Ok(value) => values.push(value),
+                            Err(_) => return Err(ParseError::new(line_number, &format!("bad case value {}", word))),
+                        }
+                    }
+                    let outputs = values.split_off(split - 1);
+                    level.cases.push(Case {
+                        inputs: values,
+                        outputs: outputs,
+                    });
+                    case_lines.push(line_number);
+                },
+                Some(other) => return Err(ParseError::new(line_number, &format!("unknown statement {}", other))),
+            }
+        }
+
+        //a missing size is only known once every line is read
+        if level.size.0 <= 0 || level.size.1 <= 0 {
+            return Err(ParseError::new(if size_line > 0 { size_line } else { last_line }, "level needs a size"));
+        }
+
+        let ports = level.inputs.iter().chain(level.outputs.iter());
+        for (port, &line_number) in ports.zip(port_lines.0.iter().chain(port_lines.1.iter())) {
+            if !level.contains(port.get_location()) {
+                return Err(ParseError::new(line_number, &format!("port {} is outside the board", port.get_name())));
+            }
+        }
+
+        for (index, (case, &line_number)) in level.cases.iter().zip(case_lines.iter()).enumerate() {
+            if case.inputs.len() != level.inputs.len() || case.outputs.len() != level.outputs.len() {
+                return Err(ParseError::new(line_number, &format!("case {} does not match the ports", index + 1)));
+            }
+        }
+
+        Ok(level)
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_size(&self) -> (CoordI, CoordI) {
+        self.size
+    }
+
+    pub fn get_inputs(&self) -> &[Port] {
+        self.inputs.as_slice()
+    }
+
+    pub fn get_outputs(&self) -> &[Port] {
+        self.outputs.as_slice()
+    }
+
+    pub fn get_cases(&self) -> &[Case] {
+        self.cases.as_slice()
+    }
+
+    pub fn contains(&self, location: &Point3I) -> bool {
+        location.get_x() >= 0 && location.get_x() < self.size.0 &&
+        location.get_y() >= 0 && location.get_y() < self.size.1
+    }
+
+    //ports are placed by the level and cannot be moved or deleted
+    pub fn is_locked(&self, location: &Point3I) -> bool {
+        self.inputs.iter().chain(self.outputs.iter()).any(|port| port.get_location().get_x() == location.get_x() && port.get_location().get_y() == location.get_y())
+    }
+
+    //checks whether one more of kind may be placed when used are already on the board
+    pub fn check_place(&self, kind: &str, used: u32) -> Result<(), String> {
+        if self.limits.is_empty() {
+            return Ok(());
+        }
+
+        match self.limits.iter().find(|&&(ref allowed, _)| allowed == kind) {
+            Some(&(_, Some(count))) if used >= count => Err(format!("this level allows only {} {}", count, kind)),
+            Some(_) => Ok(()),
+            None => Err(format!("this level does not allow {}", kind)),
+        }
+    }
+
+    //checks everything a load, route or import adds in one go, used counts what of a kind is already on the board
+    pub fn check_additions<'a, I, F>(&self, additions: I, used: F) -> Result<(), String>
+    where I: IntoIterator<Item = (&'a str, &'a Point3I)>, F: Fn(&str) -> u32
+    {
+        let mut added: Vec<(&str, u32)> = vec!();
+        for (kind, location) in additions {
+            if !self.contains(location) {
+                return Err(format!("{:?} is outside the board", location));
+            }
+            if self.is_locked(location) {
+                return Err(format!("{:?} is locked by the level", location));
+            }
+
+            let count = match added.iter().position(|&(name, _)| name == kind) {
+                Some(position) => {
+                    added[position].1 += 1;
+                    added[position].1
+                },
+                None => {
+                    added.push((kind, 1));
+                    1
+                },
+            };
+            try!(self.check_place(kind, used(kind) + count - 1));
+        }
+        Ok(())
+    }
+
+    //runs every case from a cleared state until the circuit settles
+    pub fn verify(&self, netlist: &Netlist) -> Report {
+        let nodes = netlist.get_nodes();
+
+        let mut report = Report {
+            failures: vec!(),
+            components: netlist.get_gates().len() as u32,
+            wire_length: 0,
+            delay: 0,
+        };
+
+        let mut cells = HashSet::new();
+        for node in nodes {
+            for net in node.get_inputs().iter().filter_map(|net| net.as_ref()) {
+                cells.extend(net.get_path().iter().cloned());
+            }
+        }
+        report.wire_length = cells.len() as u32;
+
+        let mut used: Vec<(&str, u32)> = vec!(("wire", report.wire_length));
+        for &index in &netlist.get_gates() {
+            if let NodeKind::Gate(kind) = *nodes[index].get_kind() {
+                match used.iter().position(|&(name, _)| name == kind.get_name()) {
+                    Some(position) => used[position].1 += 1,
+                    None => used.push((kind.get_name(), 1)),
+                }
+            }
+        }
+        for &(kind, count) in &used {
+            if count > 0 {
+                if let Err(err) = self.check_place(kind, count - 1) {
+                    report.failures.push(err);
+                }
+            }
+        }
+
+        let find = |name: &str, output: bool| nodes.iter().position(|node| match *node.get_kind() {
+            NodeKind::Input(ref node_name) if !output => node_name == name,
+            NodeKind::Output(ref node_name) if output => node_name == name,
+            _ => false,
+        });
+
+        let inputs: Vec<Option<usize>> = self.inputs.iter().map(|port| find(port.get_name(), false)).collect();
+        let outputs: Vec<Option<usize>> = self.outputs.iter().map(|port| find(port.get_name(), true)).collect();
+
+        for (port, node) in self.inputs.iter().chain(self.outputs.iter()).zip(inputs.iter().chain(outputs.iter())) {
+            if node.is_none() {
+                report.failures.push(format!("port {} is missing", port.get_name()));
+            }
+        }
+        if !report.failures.is_empty() {
+            return report;
+        }
+
+        for (index, case) in self.cases.iter().enumerate() {
+            let mut simulator = Simulator::new(netlist);
+            simulator.clear();
+            for (node, &value) in inputs.iter().zip(case.inputs.iter()) {
+                if let Some(node) = *node {
+                    simulator.set_value(node, value);
+                }
+            }
+
+            match simulator.settle(netlist, self.settle_limit) {
+                Some(ticks) => if ticks > report.delay {
+                    report.delay = ticks;
+                },
+                None => {
+                    report.failures.push(format!("case {} never settled", index + 1));
+                    continue;
+                },
+            }
+
+            for ((port, node), &expected) in self.outputs.iter().zip(outputs.iter()).zip(case.outputs.iter()) {
+                if let Some(node) = *node {
+                    let actual = simulator.get_value(node);
+                    if actual != expected {
+                        report.failures.push(format!("case {}: {} was {} expected {}", index + 1, port.get_name(), actual, expected));
+                    }
+                }
+            }
+        }
+
+        report
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    failures: Vec<String>,
+    components: u32,
+    wire_length: u32,
+    delay: u32,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn get_failures(&self) -> &[String] {
+        self.failures.as_slice()
+    }
+
+    pub fn get_components(&self) -> u32 {
+        self.components
+    }
+
+    pub fn get_wire_length(&self) -> u32 {
+        self.wire_length
+    }
+
+    pub fn get_delay(&self) -> u32 {
+        self.delay
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}: {} components, {} wire length, {} tick delay",
+            if self.passed() { "pass" } else { "fail" },
+            self.components,
+            self.wire_length,
+            self.delay
+        ));
+        for failure in &self.failures {
+            try!(write!(f, "\n  {}", failure));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+extern crate specs;
+#[macro_use]
+extern crate log;
+extern crate env_logger;
+
+extern crate utils;
+extern crate components as comps;
+extern crate math;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use specs::{World, Entity};
+
+//*************************************************************************************************
+
+use comps::{Wire, WireIn, WireOut, Gate, GateKind};
+use comps::non_components::{Map, Link};
+
+use math::{Point3I};
+
+//*************************************************************************************************
+
+pub mod blif;
+pub mod layout;
+pub mod level;
+pub mod save;
+pub mod sim;
+pub mod svg;
+pub mod verilog;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Input(String),
+    Output(String),
+    Gate(GateKind),
+}
+
+#[derive(Debug, Clone)]
+pub struct Net {
+    driver: usize,
+    path: Vec<Point3I>,
+}
+
+impl Net {
+    pub fn new(driver: usize, path: Vec<Point3I>) -> Net {
+        Net {
+            driver: driver,
+            path: path,
+        }
+    }
+
+    pub fn get_driver(&self) -> usize {
+        self.driver
+    }
+
+    //wire cells from the driver towards the sink, not including either end
+    pub fn get_path(&self) -> &[Point3I] {
+        self.path.as_slice()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    kind: NodeKind,
+    location: Point3I,
+    inputs: Vec<Option<Net>>,
+    value: u8,
+}
+
+impl Node {
+    pub fn new(kind: NodeKind, location: Point3I, inputs: Vec<Option<Net>>) -> Node {
+        Node {
+            kind: kind,
+            location: location,
+            inputs: inputs,
+            value: 0,
+        }
+    }
+
+    pub fn set_value(&mut self, value: u8) {
+        self.value = value;
+    }
+
+    pub fn get_mut_inputs(&mut self) -> &mut Vec<Option<Net>> {
+        &mut self.inputs
+    }
+
+    pub fn get_kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    pub fn get_location(&self) -> &Point3I {
+        &self.location
+    }
+
+    pub fn get_inputs(&self) -> &[Option<Net>] {
+        self.inputs.as_slice()
+    }
+
+    pub fn get_value(&self) -> u8 {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Netlist {
+    nodes: Vec<Node>,
+}
+
+impl Netlist {
+    pub fn new() -> Netlist {
+        Netlist {
+            nodes: vec!(),
+        }
+    }
+
+    pub fn from_world(world: &World) -> Netlist {
+        use specs::Join;
+
+        let entities = world.entities();
+        let wires = world.read::<Wire>();
+        let wires_in = world.read::<WireIn>();
+        let wires_out = world.read::<WireOut>();
+        let gates = world.read::<Gate>();
+        let map = world.read_resource::<Map>();
+
+        let mut netlist = Netlist::new();
+        let mut drivers = HashMap::new();
+        let mut sinks = vec!();
+
+        for (entity, wire_in) in (&entities, &wires_in).iter() {
+            let location = wire_in.get_me().get_slow().clone();
+            let name = match wire_in.get_name() {
+                Some(name) => name.to_string(),
+                None => default_name("in", &location),
+            };
+            let index = netlist.add_node(NodeKind::Input(name), location, vec!());
+            netlist.nodes[index].set_value(wire_in.get_value());
+            drivers.insert(entity, index);
+        }
+
+        for (entity, gate) in (&entities, &gates).iter() {
+            let index = netlist.add_node(NodeKind::Gate(gate.get_kind()), gate.get_me().get_slow().clone(), vec!());
+            netlist.nodes[index].set_value(gate.get_value());
+            drivers.insert(entity, index);
+            sinks.push((index, gate.get_inputs().iter().map(|input| resolve(input, &map)).collect::<Vec<_>>()));
+        }
+
+        for wire_out in (&wires_out).iter() {
+            let location = wire_out.get_me().get_slow().clone();
+            let name = match wire_out.get_name() {
+                Some(name) => name.to_string(),
+                None => default_name("out", &location),
+            };
+            let index = netlist.add_node(NodeKind::Output(name), location, vec!());
+            netlist.nodes[index].set_value(wire_out.get_value());
+            sinks.push((index, vec!(resolve(wire_out.get_input(), &map))));
+        }
+
+        let mut wire_graph = HashMap::new();
+        for (entity, wire) in (&entities, &wires).iter() {
+            wire_graph.insert(entity, (wire.get_me().get_slow().clone(), resolve(wire.get_input(), &map)));
+        }
+
+        for (index, inputs) in sinks {
+            let nets = inputs.into_iter().map(|input| trace(input, &wire_graph, &drivers)).collect();
+            netlist.nodes[index].inputs = nets;
+        }
+
+        netlist
+    }
+
+    pub fn add_node(&mut self, kind: NodeKind, location: Point3I, inputs: Vec<Option<Net>>) -> usize {
+        self.nodes.push(Node::new(kind, location, inputs));
+        self.nodes.len() - 1
+    }
+
+    pub fn get_mut_node(&mut self, index: usize) -> &mut Node {
+        &mut self.nodes[index]
+    }
+
+    pub fn get_node(&self, index: usize) -> &Node {
+        &self.nodes[index]
+    }
+
+    pub fn get_nodes(&self) -> &[Node] {
+        self.nodes.as_slice()
+    }
+
+    pub fn get_inputs(&self) -> Vec<usize> {
+        self.nodes.iter().enumerate().filter(|&(_, node)| match *node.get_kind() {
+            NodeKind::Input(_) => true,
+            _ => false,
+        }).map(|(index, _)| index).collect()
+    }
+
+    pub fn get_outputs(&self) -> Vec<usize> {
+        self.nodes.iter().enumerate().filter(|&(_, node)| match *node.get_kind() {
+            NodeKind::Output(_) => true,
+            _ => false,
+        }).map(|(index, _)| index).collect()
+    }
+
+    pub fn get_gates(&self) -> Vec<usize> {
+        self.nodes.iter().enumerate().filter(|&(_, node)| match *node.get_kind() {
+            NodeKind::Gate(_) => true,
+            _ => false,
+        }).map(|(index, _)| index).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    line: usize,
+    message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: &str) -> ParseError {
+        ParseError {
+            line: line,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    pub fn get_message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+//collects named signals from a text netlist and resolves them into nodes once everything is declared
+#[derive(Debug)]
+pub struct NetlistBuilder {
+    inputs: Vec<String>,
+    outputs: Vec<(usize, String)>,
+    gates: Vec<(usize, GateKind, Vec<String>, String)>,
+    aliases: HashMap<String, String>,
+}
+
+impl NetlistBuilder {
+    pub fn new() -> NetlistBuilder {
+        NetlistBuilder {
+            inputs: vec!(),
+            outputs: vec!(),
+            gates: vec!(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    pub fn add_input(&mut self, name: &str) {
+        self.inputs.push(name.to_string());
+    }
+
+    pub fn add_output(&mut self, line: usize, name: &str) {
+        self.outputs.push((line, name.to_string()));
+    }
+
+    pub fn add_gate(&mut self, line: usize, kind: GateKind, inputs: Vec<String>, output: &str) -> Result<(), ParseError> {
+        if kind.is_unary() && inputs.len() != 1 {
+            return Err(ParseError::new(line, &format!("{} gate needs exactly one input", kind.get_name())));
+        }
+        self.gates.push((line, kind, inputs, output.to_string()));
+        Ok(())
+    }
+
+    pub fn add_alias(&mut self, line: usize, target: &str, source: &str) -> Result<(), ParseError> {
+        if self.aliases.insert(target.to_string(), source.to_string()).is_some() {
+            return Err(ParseError::new(line, &format!("{} is assigned more than once", target)));
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> Result<Netlist, ParseError> {
+        let mut netlist = Netlist::new();
+        let mut signals = HashMap::new();
+
+        for name in &self.inputs {
+            let index = netlist.add_node(NodeKind::Input(name.clone()), Point3I::zero(), vec!());
+            signals.insert(name.clone(), index);
+        }
+
+        let mut gate_nodes = vec!();
+        for &(line, kind, _, ref output) in &self.gates {
+            let index = netlist.add_node(NodeKind::Gate(kind), Point3I::zero(), vec!());
+            if signals.insert(output.clone(), index).is_some() {
+                return Err(ParseError::new(line, &format!("{} has more than one driver", output)));
+            }
+            gate_nodes.push(index);
+        }
+
+        let mut high = None;
+
+        for (&(line, _, ref inputs, _), &index) in self.gates.iter().zip(gate_nodes.iter()) {
+            let mut nets = vec!();
+            for input in inputs {
+                let driver = try!(self.find_driver(&signals, input, line));
+                nets.push(connect(&mut netlist, &mut high, driver));
+            }
+            netlist.nodes[index].inputs = nets;
+        }
+
+        for &(line, ref name) in &self.outputs {
+            let driver = try!(self.find_driver(&signals, name, line));
+            let net = connect(&mut netlist, &mut high, driver);
+            netlist.add_node(NodeKind::Output(name.clone()), Point3I::zero(), vec!(net));
+        }
+
+        Ok(netlist)
+    }
+
+    fn find_driver(&self, signals: &HashMap<String, usize>, name: &str, line: usize) -> Result<Driver, ParseError> {
+        let mut current = name;
+        for _ in 0..self.aliases.len() + 1 {
+            if let Some(&index) = signals.get(current) {
+                return Ok(Driver::Node(index));
+            }
+            if let Some(driver) = parse_constant(current) {
+                return Ok(driver);
+            }
+            match self.aliases.get(current) {
+                Some(source) => current = source.as_str(),
+                None => break,
+            }
+        }
+        Err(ParseError::new(line, &format!("signal {} has no driver", name)))
+    }
+}
+
+//what a signal name in a text netlist resolves to
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Driver {
+    Node(usize),
+    Floating,
+    High,
+}
+
+//constants like 1'b0, 1'bz or a bare 1, anything else is a signal name
+fn parse_constant(name: &str) -> Option<Driver> {
+    let value = match name.find('\'') {
+        Some(quote) => {
+            let (width, rest) = (&name[..quote], &name[quote + 1..]);
+            if !width.chars().all(|c| c.is_digit(10)) || rest.len() != 2 {
+                return None;
+            }
+            match rest.chars().next() {
+                Some('b') | Some('B') | Some('d') | Some('D') | Some('h') | Some('H') | Some('o') | Some('O') => &rest[1..],
+                _ => return None,
+            }
+        },
+        None => name,
+    };
+
+    match value {
+        "0" | "z" | "Z" | "x" | "X" | "?" => Some(Driver::Floating),
+        "1" => Some(Driver::High),
+        _ => None,
+    }
+}
+
+//the board has no constant cells, so low and floating signals are left unconnected since they read as 0,
+//and high comes from a not gate on a floating input that everything tied high shares
+fn connect(netlist: &mut Netlist, high: &mut Option<usize>, driver: Driver) -> Option<Net> {
+    match driver {
+        Driver::Node(index) => Some(Net::new(index, vec!())),
+        Driver::Floating => None,
+        Driver::High => {
+            let index = match *high {
+                Some(index) => index,
+                None => {
+                    let index = netlist.add_node(NodeKind::Gate(GateKind::Not), Point3I::zero(), vec!(None));
+                    *high = Some(index);
+                    index
+                },
+            };
+            Some(Net::new(index, vec!()))
+        },
+    }
+}
+
+pub fn default_name(prefix: &str, location: &Point3I) -> String {
+    let coord = |value: i32| if value < 0 {
+        format!("m{}", -value)
+    } else {
+        format!("{}", value)
+    };
+    format!("{}_{}_{}_{}", prefix, coord(location.get_x()), coord(location.get_y()), coord(location.get_z()))
+}
+
+//floating links stay unconnected whatever sits in the map
+fn resolve(link: &Link, map: &Map) -> Option<Entity> {
+    if link.is_floating() {
+        return None;
+    }
+
+    match link.get_fast() {
+        Some(entity) => Some(*entity),
+        None => map.get_map().get(link.get_slow()).cloned(),
+    }
+}
+
+fn trace(
+    start: Option<Entity>,
+    wire_graph: &HashMap<Entity, (Point3I, Option<Entity>)>,
+    drivers: &HashMap<Entity, usize>
+) -> Option<Net> {
+    let mut entity = match start {
+        Some(entity) => entity,
+        None => return None,
+    };
+
+    let mut path = vec!();
+    let mut visited = HashSet::new();
+
+    loop {
+        if let Some(&driver) = drivers.get(&entity) {
+            path.reverse();
+            return Some(Net::new(driver, path));
+        }
+
+        if !visited.insert(entity) {
+            warn!("wire loop found while tracing net");
+            return None;
+        }
+
+        match wire_graph.get(&entity) {
+            Some(&(ref location, input)) => {
+                path.push(location.clone());
+                entity = match input {
+                    Some(input) => input,
+                    None => return None,
+                };
+            },
+            None => return None,
+        }
+    }
+}
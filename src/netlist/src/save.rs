@@ -0,0 +1,255 @@
+use std::io::{self, BufRead, Write};
+
+use specs::{World, Entity};
+
+//*************************************************************************************************
+
+use comps::{Wire, WireIn, WireOut, Gate, GateKind};
+use comps::non_components::link::{get_floating_cell};
+
+use math::{Point3I};
+
+use ::ParseError;
+
+//*************************************************************************************************
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Wire(Point3I, Point3I, Point3I),
+    WireIn(Point3I, Vec<Point3I>, Option<String>),
+    WireOut(Point3I, Point3I, Option<String>),
+    Gate(GateKind, Point3I, Vec<Point3I>, Vec<Point3I>),
+}
+
+impl Item {
+    pub fn get_location(&self) -> &Point3I {
+        match *self {
+            Item::Wire(ref me, _, _) |
+            Item::WireIn(ref me, _, _) |
+            Item::WireOut(ref me, _, _) |
+            Item::Gate(_, ref me, _, _) => me,
+        }
+    }
+
+    //the item an entity on the board stands for, looked up by its components so callers can go through the Map
+    pub fn from_entity(world: &World, entity: Entity) -> Option<Item> {
+        if let Some(wire) = world.read::<Wire>().get(entity) {
+            return Some(make_wire_item(wire));
+        }
+        if let Some(wire_in) = world.read::<WireIn>().get(entity) {
+            return Some(make_wire_in_item(wire_in));
+        }
+        if let Some(wire_out) = world.read::<WireOut>().get(entity) {
+            return Some(make_wire_out_item(wire_out));
+        }
+        world.read::<Gate>().get(entity).map(make_gate_item)
+    }
+
+    //the name levels and the place command use for it
+    pub fn get_kind_name(&self) -> &'static str {
+        match *self {
+            Item::Wire(..) => "wire",
+            Item::WireIn(..) => "in",
+            Item::WireOut(..) => "out",
+            Item::Gate(kind, _, _, _) => kind.get_name(),
+        }
+    }
+}
+
+//one item per line:
+//  wire <me> <input> <output>
+//  wire_in <me> <outputs> [name]
+//  wire_out <me> <input> [name]
+//  gate <kind> <me> <outputs> <inputs...>
+//with every location written as x,y,z and a fanned out signal's outputs joined by ;
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    items: Vec<Item>,
+}
+
+impl Circuit {
+    pub fn new() -> Circuit {
+        Circuit {
+            items: vec!(),
+        }
+    }
+
+    pub fn from_world(world: &World) -> Circuit {
+        use specs::Join;
+
+        let wires = world.read::<Wire>();
+        let wires_in = world.read::<WireIn>();
+        let wires_out = world.read::<WireOut>();
+        let gates = world.read::<Gate>();
+
+        let mut circuit = Circuit::new();
+
+        for wire_in in (&wires_in).iter() {
+            circuit.add_item(make_wire_in_item(wire_in));
+        }
+
+        for gate in (&gates).iter() {
+            circuit.add_item(make_gate_item(gate));
+        }
+
+        for wire in (&wires).iter() {
+            circuit.add_item(make_wire_item(wire));
+        }
+
+        for wire_out in (&wires_out).iter() {
+            circuit.add_item(make_wire_out_item(wire_out));
+        }
+
+        circuit
+    }
+
+    pub fn add_item(&mut self, item: Item) {
+        self.items.push(item);
+    }
+
+    pub fn get_items(&self) -> &[Item] {
+        self.items.as_slice()
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for item in &self.items {
+            let line = match *item {
+                Item::Wire(ref me, ref input, ref output) => format!("wire {} {} {}", point(me), point(input), point(output)),
+                Item::WireIn(ref me, ref outputs, ref name) => format!("wire_in {} {}{}", point(me), points(outputs), name_suffix(name)),
+                Item::WireOut(ref me, ref input, ref name) => format!("wire_out {} {}{}", point(me), point(input), name_suffix(name)),
+                Item::Gate(kind, ref me, ref outputs, ref inputs) => {
+                    let inputs: Vec<String> = inputs.iter().map(point).collect();
+                    format!("gate {} {} {} {}", kind.get_name(), point(me), points(outputs), inputs.join(" "))
+                },
+            };
+            try!(writeln!(writer, "{}", line.trim_right()));
+        }
+        Ok(())
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> Result<Circuit, ParseError> {
+        let mut circuit = Circuit::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line_number = index + 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Err(ParseError::new(line_number, &format!("read error: {}", err))),
+            };
+
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => line.as_str(),
+            };
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+
+            circuit.add_item(try!(parse_item(&words, line_number)));
+        }
+
+        Ok(circuit)
+    }
+}
+
+pub fn parse_item(words: &[&str], line: usize) -> Result<Item, ParseError> {
+    let location = |index: usize| match words.get(index) {
+        Some(word) => parse_point(word, line),
+        None => Err(ParseError::new(line, "missing location")),
+    };
+    let locations = |index: usize| -> Result<Vec<Point3I>, ParseError> {
+        match words.get(index) {
+            Some(word) => word.split(';').map(|word| parse_point(word, line)).collect(),
+            None => Err(ParseError::new(line, "missing location")),
+        }
+    };
+    let name = |index: usize| words.get(index).map(|name| name.to_string());
+
+    match words.first() {
+        Some(&"wire") => Ok(Item::Wire(try!(location(1)), try!(location(2)), try!(location(3)))),
+        Some(&"wire_in") => Ok(Item::WireIn(try!(location(1)), try!(locations(2)), name(3))),
+        Some(&"wire_out") => Ok(Item::WireOut(try!(location(1)), try!(location(2)), name(3))),
+        Some(&"gate") => {
+            let kind = match words.get(1).and_then(|kind| GateKind::from_name(kind)) {
+                Some(kind) => kind,
+                None => return Err(ParseError::new(line, "unknown gate kind")),
+            };
+            let mut inputs = vec!();
+            for index in 4..words.len() {
+                inputs.push(try!(location(index)));
+            }
+            Ok(Item::Gate(kind, try!(location(2)), try!(locations(3)), inputs))
+        },
+        Some(other) => Err(ParseError::new(line, &format!("unknown item {}", other))),
+        None => Err(ParseError::new(line, "empty item")),
+    }
+}
+
+//none is a pin left unconnected on purpose
+pub fn parse_point(word: &str, line: usize) -> Result<Point3I, ParseError> {
+    if word == "none" {
+        return Ok(get_floating_cell());
+    }
+    let coords: Vec<i32> = match word.split(',').map(|coord| coord.parse()).collect() {
+        Ok(coords) => coords,
+        Err(_) => return Err(ParseError::new(line, &format!("bad location {}", word))),
+    };
+    if coords.len() != 3 {
+        return Err(ParseError::new(line, &format!("location {} needs three coordinates", word)));
+    }
+    Ok(Point3I::new(coords[0], coords[1], coords[2]))
+}
+
+fn make_wire_item(wire: &Wire) -> Item {
+    Item::Wire(
+        wire.get_me().get_slow().clone(),
+        wire.get_input().get_slow().clone(),
+        wire.get_output().get_slow().clone()
+    )
+}
+
+fn make_wire_in_item(wire_in: &WireIn) -> Item {
+    Item::WireIn(
+        wire_in.get_me().get_slow().clone(),
+        wire_in.get_outputs().iter().map(|output| output.get_slow().clone()).collect(),
+        wire_in.get_name().map(|name| name.to_string())
+    )
+}
+
+fn make_wire_out_item(wire_out: &WireOut) -> Item {
+    Item::WireOut(
+        wire_out.get_me().get_slow().clone(),
+        wire_out.get_input().get_slow().clone(),
+        wire_out.get_name().map(|name| name.to_string())
+    )
+}
+
+fn make_gate_item(gate: &Gate) -> Item {
+    Item::Gate(
+        gate.get_kind(),
+        gate.get_me().get_slow().clone(),
+        gate.get_outputs().iter().map(|output| output.get_slow().clone()).collect(),
+        gate.get_inputs().iter().map(|input| input.get_slow().clone()).collect()
+    )
+}
+
+fn point(location: &Point3I) -> String {
+    if *location == get_floating_cell() {
+        return "none".to_string();
+    }
+    format!("{},{},{}", location.get_x(), location.get_y(), location.get_z())
+}
+
+fn points(locations: &[Point3I]) -> String {
+    let locations: Vec<String> = locations.iter().map(point).collect();
+    locations.join(";")
+}
+
+fn name_suffix(name: &Option<String>) -> String {
+    match *name {
+        Some(ref name) => format!(" {}", name),
+        None => String::new(),
+    }
+}
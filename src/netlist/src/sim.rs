@@ -0,0 +1,85 @@
+use ::{Netlist, Net, NodeKind};
+
+//*************************************************************************************************
+
+//gates read the values from the previous tick so every gate adds one tick of delay,
+//outputs follow their driver within the same tick
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    values: Vec<u8>,
+}
+
+impl Simulator {
+    pub fn new(netlist: &Netlist) -> Simulator {
+        Simulator {
+            values: netlist.get_nodes().iter().map(|node| node.get_value()).collect(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for value in self.values.iter_mut() {
+            *value = 0;
+        }
+    }
+
+    pub fn set_value(&mut self, node: usize, value: u8) {
+        self.values[node] = value;
+    }
+
+    pub fn get_value(&self, node: usize) -> u8 {
+        self.values[node]
+    }
+
+    pub fn get_values(&self) -> &[u8] {
+        self.values.as_slice()
+    }
+
+    pub fn get_net_value(&self, net: &Option<Net>) -> u8 {
+        match *net {
+            Some(ref net) => self.values[net.get_driver()],
+            None => 0,
+        }
+    }
+
+    //returns true if any value changed
+    pub fn step(&mut self, netlist: &Netlist) -> bool {
+        let previous = self.values.clone();
+
+        for (index, node) in netlist.get_nodes().iter().enumerate() {
+            if let NodeKind::Gate(kind) = *node.get_kind() {
+                let inputs: Vec<u8> = node.get_inputs().iter().map(|net| match *net {
+                    Some(ref net) => previous[net.get_driver()],
+                    None => 0,
+                }).collect();
+                self.values[index] = kind.evaluate(&inputs);
+            }
+        }
+
+        for (index, node) in netlist.get_nodes().iter().enumerate() {
+            if let NodeKind::Output(_) = *node.get_kind() {
+                self.values[index] = match node.get_inputs().first() {
+                    Some(net) => self.get_net_value(net),
+                    None => 0,
+                };
+            }
+        }
+
+        previous != self.values
+    }
+
+    pub fn run(&mut self, netlist: &Netlist, ticks: u32) {
+        for _ in 0..ticks {
+            self.step(netlist);
+        }
+    }
+
+    //steps until nothing changes, returning how many ticks that took or None if it never settled
+    pub fn settle(&mut self, netlist: &Netlist, limit: u32) -> Option<u32> {
+        for tick in 0..limit {
+            if !self.step(netlist) {
+                return Some(tick);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,270 @@
+use std::io::{self, Write};
+
+//*************************************************************************************************
+
+use comps::{GateKind};
+
+use math::{Point3I};
+
+use utils::{CoordI};
+
+use ::{Netlist, Node, NodeKind};
+
+//*************************************************************************************************
+
+//pixels per board cell, symbols are drawn inside the cell they sit on
+const CELL: f64 = 32.0;
+//cells left around everything
+const MARGIN: CoordI = 1;
+const SYMBOL: f64 = 0.34 * CELL;
+const BUBBLE: f64 = 0.08 * CELL;
+const VIA: f64 = 0.1 * CELL;
+const FONT_SIZE: f64 = 0.32 * CELL;
+const PIN_FONT_SIZE: f64 = 0.2 * CELL;
+//between a pin and its label
+const PIN_GAP: f64 = 0.06 * CELL;
+
+const BACKGROUND: &'static str = "#ffffff";
+const STROKE: &'static str = "#202020";
+const WIRE: &'static str = "#4060a0";
+const WIRE_LOW: &'static str = "#606060";
+const WIRE_HIGH: &'static str = "#e04020";
+const FILL: &'static str = "#ffffff";
+const LABEL: &'static str = "#000000";
+
+//draws the circuit as a schematic from where its nodes and wires sit on the board, with each net
+//as a polyline from its driver through the wire cells to the sink and every gate pin named, values colors
+//the nets by their current signal and writes it beside every input, output and pin
+pub fn write<W: Write>(netlist: &Netlist, values: bool, writer: &mut W) -> io::Result<()> {
+    let (min_x, min_y, max_x, max_y) = match find_bounds(netlist) {
+        Some(bounds) => bounds,
+        None => (0, 0, 0, 0),
+    };
+    let (min_x, min_y, max_x, max_y) = (min_x - MARGIN, min_y - MARGIN, max_x + MARGIN, max_y + MARGIN);
+
+    //board y grows up and svg y grows down
+    let center = |location: &Point3I| (
+        (location.get_x() - min_x) as f64 * CELL + CELL / 2.0,
+        (max_y - location.get_y()) as f64 * CELL + CELL / 2.0
+    );
+
+    let width = (max_x - min_x + 1) as f64 * CELL;
+    let height = (max_y - min_y + 1) as f64 * CELL;
+
+    try!(writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    try!(writeln!(writer, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">", num(width), num(height), num(width), num(height)));
+    try!(writeln!(writer, "  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", BACKGROUND));
+
+    //wires first so the symbols cover their ends
+    try!(writeln!(writer, "  <g fill=\"none\" stroke-width=\"2\" stroke-linejoin=\"round\" stroke-linecap=\"round\">"));
+    for node in netlist.get_nodes() {
+        for net in node.get_inputs().iter().filter_map(|input| input.as_ref()) {
+            let driver = netlist.get_node(net.get_driver());
+
+            let mut cells = vec!(driver.get_location());
+            cells.extend(net.get_path().iter());
+            cells.push(node.get_location());
+
+            let points: Vec<String> = cells.iter().map(|cell| {
+                let (x, y) = center(*cell);
+                format!("{},{}", num(x), num(y))
+            }).collect();
+
+            let color = if !values {
+                WIRE
+            } else if driver.get_value() != 0 {
+                WIRE_HIGH
+            } else {
+                WIRE_LOW
+            };
+            try!(writeln!(writer, "    <polyline stroke=\"{}\" points=\"{}\"/>", color, points.join(" ")));
+
+            //a dot wherever the net changes layer
+            for pair in cells.windows(2) {
+                if pair[0].get_z() != pair[1].get_z() {
+                    let (x, y) = center(pair[1]);
+                    try!(writeln!(writer, "    <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>", num(x), num(y), num(VIA), color));
+                }
+            }
+        }
+    }
+    try!(writeln!(writer, "  </g>"));
+
+    try!(writeln!(writer, "  <g fill=\"{}\" stroke=\"{}\" stroke-width=\"1.5\" stroke-linejoin=\"round\">", FILL, STROKE));
+    for node in netlist.get_nodes() {
+        let (x, y) = center(node.get_location());
+        try!(write_symbol(writer, node, x, y));
+    }
+    try!(writeln!(writer, "  </g>"));
+
+    try!(writeln!(writer, "  <g fill=\"{}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\">", LABEL, num(FONT_SIZE)));
+    for node in netlist.get_nodes() {
+        let (x, y) = center(node.get_location());
+        let label = match *node.get_kind() {
+            NodeKind::Input(ref name) | NodeKind::Output(ref name) => if values {
+                format!("{}={}", name, node.get_value())
+            } else {
+                name.clone()
+            },
+            NodeKind::Gate(kind) => kind.get_name().to_string(),
+        };
+        try!(writeln!(writer, "    <text x=\"{}\" y=\"{}\">{}</text>", num(x), num(y + SYMBOL + FONT_SIZE), escape(&label)));
+    }
+    try!(writeln!(writer, "  </g>"));
+
+    try!(writeln!(writer, "  <g fill=\"{}\" font-family=\"monospace\" font-size=\"{}\">", LABEL, num(PIN_FONT_SIZE)));
+    for node in netlist.get_nodes() {
+        if let NodeKind::Gate(kind) = *node.get_kind() {
+            let (x, y) = center(node.get_location());
+            try!(write_pins(writer, netlist, node, kind, values, x, y));
+        }
+    }
+    try!(writeln!(writer, "  </g>"));
+
+    writeln!(writer, "</svg>")
+}
+
+//every symbol points left to right with its inputs on the left
+fn write_symbol<W: Write>(writer: &mut W, node: &Node, x: f64, y: f64) -> io::Result<()> {
+    let s = SYMBOL;
+    let title = match *node.get_kind() {
+        NodeKind::Input(ref name) => format!("input {}", name),
+        NodeKind::Output(ref name) => format!("output {}", name),
+        NodeKind::Gate(kind) => kind.get_name().to_string(),
+    };
+
+    let (path, bubble) = match *node.get_kind() {
+        //a flag pointing into the circuit
+        NodeKind::Input(_) => (
+            format!("M {} {} L {} {} L {} {} L {} {} L {} {} Z", num(x - s), num(y - s / 2.0), num(x + s / 2.0), num(y - s / 2.0), num(x + s), num(y), num(x + s / 2.0), num(y + s / 2.0), num(x - s), num(y + s / 2.0)),
+            false
+        ),
+        //a flag with a notch where the circuit comes in
+        NodeKind::Output(_) => (
+            format!("M {} {} L {} {} L {} {} L {} {} L {} {} Z", num(x - s), num(y - s / 2.0), num(x + s), num(y - s / 2.0), num(x + s), num(y + s / 2.0), num(x - s), num(y + s / 2.0), num(x - s / 2.0), num(y)),
+            false
+        ),
+        NodeKind::Gate(kind) => {
+            let body = match kind {
+                GateKind::Buf | GateKind::Not => format!(
+                    "M {} {} L {} {} L {} {} Z",
+                    num(x - s), num(y - s), num(x + s), num(y), num(x - s), num(y + s)
+                ),
+                GateKind::And | GateKind::Nand => format!(
+                    "M {} {} L {} {} A {} {} 0 0 1 {} {} L {} {} Z",
+                    num(x - s), num(y - s), num(x), num(y - s), num(s), num(s), num(x), num(y + s), num(x - s), num(y + s)
+                ),
+                GateKind::Or | GateKind::Nor | GateKind::Xor | GateKind::Xnor => format!(
+                    "M {} {} Q {} {} {} {} Q {} {} {} {} Q {} {} {} {} Z",
+                    num(x - s), num(y - s),
+                    num(x + s / 2.0), num(y - s), num(x + s), num(y),
+                    num(x + s / 2.0), num(y + s), num(x - s), num(y + s),
+                    num(x - s / 2.0), num(y), num(x - s), num(y - s)
+                ),
+            };
+            //xor gets the extra curve behind its inputs
+            let body = match kind {
+                GateKind::Xor | GateKind::Xnor => format!(
+                    "{} M {} {} Q {} {} {} {}",
+                    body, num(x - s * 1.3), num(y - s), num(x - s * 0.8), num(y), num(x - s * 1.3), num(y + s)
+                ),
+                _ => body,
+            };
+            (body, has_bubble(kind))
+        },
+    };
+
+    try!(writeln!(writer, "    <g><title>{}</title>", escape(&title)));
+    try!(writeln!(writer, "      <path d=\"{}\"/>", path));
+    if bubble {
+        try!(writeln!(writer, "      <circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>", num(x + s + BUBBLE), num(y), num(BUBBLE)));
+    }
+    writeln!(writer, "    </g>")
+}
+
+//inputs are spread down the left side of the symbol and named from a, the output is y at its tip,
+//each label sits just above its pin so the wire coming in does not cross it
+fn write_pins<W: Write>(writer: &mut W, netlist: &Netlist, node: &Node, kind: GateKind, values: bool, x: f64, y: f64) -> io::Result<()> {
+    let s = SYMBOL;
+    let inputs = node.get_inputs();
+
+    for (index, input) in inputs.iter().enumerate() {
+        let pin_y = y - s + 2.0 * s * (index + 1) as f64 / (inputs.len() + 1) as f64;
+        let label = match *input {
+            Some(ref net) if values => format!("{}={}", pin_name(index), netlist.get_node(net.get_driver()).get_value()),
+            _ => pin_name(index),
+        };
+        try!(writeln!(writer, "    <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>", num(x - s - PIN_GAP), num(pin_y - PIN_GAP), escape(&label)));
+    }
+
+    let tip = if has_bubble(kind) {
+        x + s + BUBBLE * 2.0
+    } else {
+        x + s
+    };
+    let label = if values {
+        format!("y={}", node.get_value())
+    } else {
+        "y".to_string()
+    };
+    writeln!(writer, "    <text x=\"{}\" y=\"{}\" text-anchor=\"start\">{}</text>", num(tip + PIN_GAP), num(y - PIN_GAP), escape(&label))
+}
+
+fn pin_name(index: usize) -> String {
+    if index < 24 {
+        ((b'a' + index as u8) as char).to_string()
+    } else {
+        format!("i{}", index)
+    }
+}
+
+fn has_bubble(kind: GateKind) -> bool {
+    match kind {
+        GateKind::Not | GateKind::Nand | GateKind::Nor | GateKind::Xnor => true,
+        _ => false,
+    }
+}
+
+//min and max cells over every node and wire cell
+fn find_bounds(netlist: &Netlist) -> Option<(CoordI, CoordI, CoordI, CoordI)> {
+    let mut bounds: Option<(CoordI, CoordI, CoordI, CoordI)> = None;
+    let cells = netlist.get_nodes().iter().flat_map(|node| {
+        let paths = node.get_inputs().iter().filter_map(|input| input.as_ref()).flat_map(|net| net.get_path().iter());
+        Some(node.get_location()).into_iter().chain(paths)
+    });
+
+    for cell in cells {
+        let (x, y) = (cell.get_x(), cell.get_y());
+        bounds = Some(match bounds {
+            Some((min_x, min_y, max_x, max_y)) => (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+            None => (x, y, x, y),
+        });
+    }
+
+    bounds
+}
+
+//no trailing zeros so the file stays small and diffs cleanly
+fn num(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_right_matches('0').trim_right_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
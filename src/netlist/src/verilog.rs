@@ -0,0 +1,259 @@
+use std::io::{self, Read, Write};
+use std::collections::{HashSet};
+
+//*************************************************************************************************
+
+use comps::{GateKind};
+
+use ::{Netlist, Net, NodeKind, NetlistBuilder, ParseError};
+
+//*************************************************************************************************
+
+//the board reads an unconnected input as 0, so undriven nets are written low rather than as z,
+//which an hdl simulator would turn into x through any gate
+const UNDRIVEN: &'static str = "1'b0";
+
+const KEYWORDS: &'static [&'static str] = &[
+    "always", "and", "assign", "begin", "buf", "bufif0", "bufif1", "case", "casex", "casez", "cmos",
+    "deassign", "default", "defparam", "disable", "edge", "else", "end", "endcase", "endfunction",
+    "endmodule", "endprimitive", "endspecify", "endtable", "endtask", "event", "for", "force", "forever",
+    "fork", "function", "generate", "genvar", "highz0", "highz1", "if", "initial", "inout", "input",
+    "integer", "join", "large", "localparam", "macromodule", "medium", "module", "nand", "negedge",
+    "nmos", "nor", "not", "notif0", "notif1", "or", "output", "parameter", "pmos", "posedge",
+    "primitive", "pull0", "pull1", "pulldown", "pullup", "rcmos", "real", "realtime", "reg",
+    "release", "repeat", "rnmos", "rpmos", "rtran", "rtranif0", "rtranif1", "scalared", "signed",
+    "small", "specify", "specparam", "strong0", "strong1", "supply0", "supply1", "table", "task",
+    "time", "tran", "tranif0", "tranif1", "tri", "tri0", "tri1", "triand", "trior", "trireg",
+    "vectored", "wait", "wand", "weak0", "weak1", "while", "wire", "wor", "xnor", "xor",
+];
+
+//one flat module, the board has no sub-circuits to become module instances
+pub fn write<W: Write>(netlist: &Netlist, module_name: &str, writer: &mut W) -> io::Result<()> {
+    let (names, instances) = make_names(netlist);
+
+    let net_name = |net: &Option<Net>| match *net {
+        Some(ref net) => names[net.get_driver()].clone(),
+        None => UNDRIVEN.to_string(),
+    };
+
+    let inputs = netlist.get_inputs();
+    let outputs = netlist.get_outputs();
+    let gates = netlist.get_gates();
+
+    let ports: Vec<String> = inputs.iter().chain(outputs.iter()).map(|&index| names[index].clone()).collect();
+
+    try!(writeln!(writer, "module {}({});", sanitize(module_name), ports.join(", ")));
+
+    for &index in &inputs {
+        try!(writeln!(writer, "    input {};", names[index]));
+    }
+
+    for &index in &outputs {
+        try!(writeln!(writer, "    output {};", names[index]));
+    }
+
+    for &index in &gates {
+        try!(writeln!(writer, "    wire {};", names[index]));
+    }
+
+    try!(writeln!(writer, ""));
+
+    for &index in &gates {
+        let node = netlist.get_node(index);
+        let kind = match *node.get_kind() {
+            NodeKind::Gate(kind) => kind,
+            _ => continue,
+        };
+
+        let mut args = vec!(names[index].clone());
+        if node.get_inputs().is_empty() {
+            args.push(UNDRIVEN.to_string());
+        }
+        for input in node.get_inputs() {
+            args.push(net_name(input));
+        }
+
+        try!(writeln!(writer, "    {} {}({});", kind.get_name(), instances[index], args.join(", ")));
+    }
+
+    for &index in &outputs {
+        let node = netlist.get_node(index);
+        let source = match node.get_inputs().first() {
+            Some(input) => net_name(input),
+            None => UNDRIVEN.to_string(),
+        };
+        try!(writeln!(writer, "    assign {} = {};", names[index], source));
+    }
+
+    writeln!(writer, "endmodule")
+}
+
+//reads the subset written by write: one module of scalar ports, gate primitives and assigns
+pub fn read<R: Read>(mut reader: R) -> Result<Netlist, ParseError> {
+    let mut source = String::new();
+    if let Err(err) = reader.read_to_string(&mut source) {
+        return Err(ParseError::new(0, &format!("read error: {}", err)));
+    }
+
+    let source = strip_comments(&source);
+
+    let mut builder = NetlistBuilder::new();
+    let mut line = 1;
+
+    for statement in source.split(';') {
+        let leading = statement.len() - statement.trim_left().len();
+        let start_line = line + statement[..leading].matches('\n').count();
+        line += statement.matches('\n').count();
+
+        let statement = statement.trim();
+        if statement.is_empty() {
+            continue;
+        }
+
+        let (head, args) = match statement.find('(') {
+            Some(open) => {
+                let close = match statement.rfind(')') {
+                    Some(close) if close > open => close,
+                    _ => return Err(ParseError::new(start_line, "unclosed parenthesis")),
+                };
+                (&statement[..open], Some(&statement[open + 1..close]))
+            },
+            None => (statement, None),
+        };
+
+        let words: Vec<&str> = head.split_whitespace().collect();
+        let keyword = match words.first() {
+            Some(keyword) => *keyword,
+            None => return Err(ParseError::new(start_line, "statement has no keyword")),
+        };
+
+        if keyword == "endmodule" {
+            break;
+        }
+
+        match (keyword, args) {
+            ("module", _) => (),
+            ("input", None) | ("output", None) | ("wire", None) => {
+                let rest = head.trim_left()[keyword.len()..].trim();
+                if rest.starts_with('[') {
+                    return Err(ParseError::new(start_line, "vector ports are not supported"));
+                }
+                for name in rest.split(',').map(|name| name.trim()).filter(|name| !name.is_empty()) {
+                    match keyword {
+                        "input" => builder.add_input(name),
+                        "output" => builder.add_output(start_line, name),
+                        _ => (),
+                    }
+                }
+            },
+            ("assign", None) => {
+                let rest = head.trim_left()[keyword.len()..].trim();
+                let mut sides = rest.splitn(2, '=');
+                match (sides.next(), sides.next()) {
+                    (Some(target), Some(source)) => try!(builder.add_alias(start_line, target.trim(), source.trim())),
+                    _ => return Err(ParseError::new(start_line, "assign needs a target and a source")),
+                }
+            },
+            (primitive, Some(args)) => {
+                let kind = match GateKind::from_name(primitive) {
+                    Some(kind) => kind,
+                    None => return Err(ParseError::new(start_line, &format!("unsupported primitive or module {}", primitive))),
+                };
+                let mut args: Vec<String> = args.split(',').map(|arg| arg.trim().to_string()).collect();
+                if args.len() < 2 {
+                    return Err(ParseError::new(start_line, "primitives need an output and at least one input"));
+                }
+                let output = args.remove(0);
+                try!(builder.add_gate(start_line, kind, args, &output));
+            },
+            (other, None) => return Err(ParseError::new(start_line, &format!("unsupported statement {}", other))),
+        }
+    }
+
+    builder.build()
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '/' && chars.peek() == Some(&'/') {
+            while let Some(&next) = chars.peek() {
+                if next == '\n' {
+                    break;
+                }
+                chars.next();
+            }
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut last = ' ';
+            while let Some(next) = chars.next() {
+                if next == '\n' {
+                    result.push('\n');
+                }
+                if last == '*' && next == '/' {
+                    break;
+                }
+                last = next;
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+//net names for every node and instance names for the gates, all unique since verilog keeps them in one namespace
+fn make_names(netlist: &Netlist) -> (Vec<String>, Vec<String>) {
+    let mut used = HashSet::new();
+
+    let names = netlist.get_nodes().iter().enumerate().map(|(index, node)| {
+        let base = match *node.get_kind() {
+            NodeKind::Input(ref name) | NodeKind::Output(ref name) => sanitize(name),
+            NodeKind::Gate(_) => format!("n{}", index),
+        };
+        make_unique(&mut used, base)
+    }).collect();
+
+    let instances = netlist.get_nodes().iter().enumerate().map(|(index, node)| match *node.get_kind() {
+        NodeKind::Gate(_) => make_unique(&mut used, format!("g{}", index)),
+        _ => String::new(),
+    }).collect();
+
+    (names, instances)
+}
+
+fn make_unique(used: &mut HashSet<String>, base: String) -> String {
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while !used.insert(name.clone()) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+fn sanitize(name: &str) -> String {
+    let mut result: String = name.chars().map(|c| match c {
+        'a'...'z' | 'A'...'Z' | '0'...'9' | '_' => c,
+        _ => '_',
+    }).collect();
+
+    let starts_valid = match result.chars().next() {
+        Some('a'...'z') | Some('A'...'Z') | Some('_') => true,
+        _ => false,
+    };
+
+    if !starts_valid {
+        result.insert(0, '_');
+    }
+
+    //a port called input or wire would read as the keyword
+    if KEYWORDS.contains(&result.as_str()) {
+        result.push('_');
+    }
+
+    result
+}
@@ -0,0 +1,147 @@
+extern crate components;
+extern crate netlist;
+
+use components::GateKind;
+
+use netlist::{Netlist, NodeKind, ParseError};
+use netlist::blif;
+
+//*************************************************************************************************
+
+fn read(source: &str) -> Result<Netlist, ParseError> {
+    blif::read(source.as_bytes())
+}
+
+fn read_ok(source: &str) -> Netlist {
+    match read(source) {
+        Ok(netlist) => netlist,
+        Err(err) => panic!("read failed: {}", err),
+    }
+}
+
+fn read_err(source: &str) -> ParseError {
+    match read(source) {
+        Ok(_) => panic!("read of\n{}\nshould have failed", source),
+        Err(err) => err,
+    }
+}
+
+//the kind of the only gate in a single cover model
+fn single_gate(cover: &str) -> GateKind {
+    let netlist = read_ok(&format!(".model m\n.inputs a b\n.outputs y\n.names a b y\n{}\n.end\n", cover));
+    let gates = netlist.get_gates();
+    assert_eq!(gates.len(), 1);
+    match *netlist.get_node(gates[0]).get_kind() {
+        NodeKind::Gate(kind) => kind,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn reads_ports_and_connects_the_output() {
+    let netlist = read_ok("
+.model half
+.inputs a b
+.outputs sum carry
+.names a b sum
+01 1
+10 1
+.names a b carry
+11 1
+.end
+");
+
+    let inputs = netlist.get_inputs();
+    let outputs = netlist.get_outputs();
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(*netlist.get_node(outputs[0]).get_kind(), NodeKind::Output("sum".to_string()));
+
+    let sum = netlist.get_node(outputs[0]).get_inputs()[0].as_ref().expect("sum is not driven").get_driver();
+    assert_eq!(*netlist.get_node(sum).get_kind(), NodeKind::Gate(GateKind::Xor));
+    let drivers: Vec<usize> = netlist.get_node(sum).get_inputs().iter().map(|net| net.as_ref().expect("floating input").get_driver()).collect();
+    assert_eq!(drivers, inputs);
+}
+
+#[test]
+fn classifies_two_input_covers() {
+    assert_eq!(single_gate("11 1"), GateKind::And);
+    assert_eq!(single_gate("11 0"), GateKind::Nand);
+    assert_eq!(single_gate("00 0"), GateKind::Or);
+    assert_eq!(single_gate("00 1"), GateKind::Nor);
+    assert_eq!(single_gate("1- 1\n-1 1"), GateKind::Or);
+    assert_eq!(single_gate("0- 1\n-0 1"), GateKind::Nand);
+    assert_eq!(single_gate("01 1\n10 1"), GateKind::Xor);
+    assert_eq!(single_gate("00 1\n11 1"), GateKind::Xnor);
+}
+
+#[test]
+fn classifies_one_input_covers() {
+    let kind = |cover: &str| {
+        let netlist = read_ok(&format!(".inputs a\n.outputs y\n.names a y\n{}\n", cover));
+        match *netlist.get_node(netlist.get_gates()[0]).get_kind() {
+            NodeKind::Gate(kind) => kind,
+            _ => unreachable!(),
+        }
+    };
+
+    assert_eq!(kind("1 1"), GateKind::Buf);
+    assert_eq!(kind("0 1"), GateKind::Not);
+}
+
+#[test]
+fn joins_continued_lines_and_skips_comments() {
+    let netlist = read_ok(".model m # the model\n.inputs a \\\n b\n.outputs y\n.names a \\\n b y\n11 1\n");
+
+    assert_eq!(netlist.get_inputs().len(), 2);
+    assert_eq!(netlist.get_gates().len(), 1);
+}
+
+#[test]
+fn unsupported_cover_reports_its_names_line() {
+    let err = read_err(".inputs a b c\n.outputs y\n\n.names a b c y\n1-0 1\n");
+
+    assert_eq!(err.get_line(), 4);
+}
+
+#[test]
+fn unknown_directive_reports_its_line() {
+    let err = read_err(".inputs a\n.latch a y\n");
+
+    assert_eq!(err.get_line(), 2);
+}
+
+#[test]
+fn undriven_output_reports_its_line() {
+    let err = read_err(".inputs a\n\n.outputs y\n.end\n");
+
+    assert_eq!(err.get_line(), 3);
+    assert!(err.get_message().contains("y"), "message was {}", err.get_message());
+}
+
+#[test]
+fn undriven_gate_input_reports_its_line() {
+    let err = read_err(".inputs a\n.outputs y\n.names a b y\n11 1\n");
+
+    assert_eq!(err.get_line(), 3);
+}
+
+#[test]
+fn zero_input_covers_are_constants() {
+    let netlist = read_ok(".inputs a\n.outputs y z w\n.names y\n1\n.names z\n.names w\n0\n");
+
+    //the high constant is the shared tie-high gate, the low ones float as the board reads them
+    let outputs = netlist.get_outputs();
+    let y = netlist.get_node(outputs[0]).get_inputs()[0].as_ref().expect("y is not driven").get_driver();
+    assert_eq!(*netlist.get_node(y).get_kind(), NodeKind::Gate(GateKind::Not));
+    assert!(netlist.get_node(outputs[1]).get_inputs()[0].is_none());
+    assert!(netlist.get_node(outputs[2]).get_inputs()[0].is_none());
+    assert_eq!(netlist.get_gates(), vec!(y));
+}
+
+#[test]
+fn constant_with_several_rows_reports_its_line() {
+    let err = read_err(".outputs y\n.names y\n1\n0\n");
+
+    assert_eq!(err.get_line(), 2);
+}
@@ -0,0 +1,157 @@
+extern crate math;
+extern crate netlist;
+
+use math::Point3I;
+
+use netlist::ParseError;
+use netlist::level::{Level};
+
+//*************************************************************************************************
+
+fn read(source: &str) -> Result<Level, ParseError> {
+    Level::read(source.as_bytes())
+}
+
+fn read_ok(source: &str) -> Level {
+    match read(source) {
+        Ok(level) => level,
+        Err(err) => panic!("read failed: {}", err),
+    }
+}
+
+fn read_err(source: &str) -> ParseError {
+    match read(source) {
+        Ok(_) => panic!("read of\n{}\nshould have failed", source),
+        Err(err) => err,
+    }
+}
+
+const HALF_ADDER: &'static str = "
+name half adder # the first level
+size 8 6
+input a 0 1
+input b 0 3
+output sum 7 1
+output carry 7 3
+allow wire
+allow xor 1
+allow and 1
+settle 16
+case 0 0 : 0 0
+case 1 0 : 1 0
+case 0 1 : 1 0
+case 1 1 : 0 1
+";
+
+#[test]
+fn reads_every_statement() {
+    let level = read_ok(HALF_ADDER);
+
+    assert_eq!(level.get_name(), "half adder");
+    assert_eq!(level.get_size(), (8, 6));
+
+    let inputs: Vec<(&str, Point3I)> = level.get_inputs().iter().map(|port| (port.get_name(), port.get_location().clone())).collect();
+    assert_eq!(inputs, vec!(("a", Point3I::new(0, 1, 0)), ("b", Point3I::new(0, 3, 0))));
+    let outputs: Vec<&str> = level.get_outputs().iter().map(|port| port.get_name()).collect();
+    assert_eq!(outputs, vec!("sum", "carry"));
+
+    let cases = level.get_cases();
+    assert_eq!(cases.len(), 4);
+    assert_eq!(cases[3].get_inputs().to_vec(), vec!(1, 1));
+    assert_eq!(cases[3].get_outputs().to_vec(), vec!(0, 1));
+}
+
+#[test]
+fn unknown_statement_reports_its_line() {
+    let err = read_err("size 4 4\n\nportal 1 1\n");
+
+    assert_eq!(err.get_line(), 3);
+}
+
+#[test]
+fn malformed_numbers_report_their_line() {
+    assert_eq!(read_err("name x\nsize 4 four\n").get_line(), 2);
+    assert_eq!(read_err("size 4 4\ninput a 1\n").get_line(), 2);
+    assert_eq!(read_err("size 4 4\ncase 1 0\n").get_line(), 2);
+    assert_eq!(read_err("size 4 4\ncase 1 x : 0\n").get_line(), 2);
+}
+
+#[test]
+fn missing_size_reports_the_last_line() {
+    let err = read_err("name x\ninput a 0 0\n");
+
+    assert_eq!(err.get_line(), 2);
+}
+
+#[test]
+fn empty_size_reports_its_line() {
+    let err = read_err("name x\nsize 0 4\ninput a 0 0\n");
+
+    assert_eq!(err.get_line(), 2);
+}
+
+#[test]
+fn port_outside_the_board_reports_its_line() {
+    let err = read_err("size 4 4\ninput a 0 0\ninput b 0 1\noutput y 4 0\n");
+
+    assert_eq!(err.get_line(), 4);
+    assert!(err.get_message().contains("y"), "message was {}", err.get_message());
+}
+
+#[test]
+fn mismatched_case_reports_its_line() {
+    let err = read_err("size 4 4\ninput a 0 0\noutput y 3 0\ncase 0 : 1\ncase 1 1 : 0\n");
+
+    assert_eq!(err.get_line(), 5);
+}
+
+#[test]
+fn bounds_and_locks() {
+    let level = read_ok(HALF_ADDER);
+
+    assert!(level.contains(&Point3I::new(0, 0, 0)));
+    assert!(level.contains(&Point3I::new(7, 5, 2)));
+    assert!(!level.contains(&Point3I::new(8, 0, 0)));
+    assert!(!level.contains(&Point3I::new(0, -1, 0)));
+
+    //ports lock their cell on every layer
+    assert!(level.is_locked(&Point3I::new(0, 1, 0)));
+    assert!(level.is_locked(&Point3I::new(7, 3, 1)));
+    assert!(!level.is_locked(&Point3I::new(1, 1, 0)));
+}
+
+#[test]
+fn anything_goes_without_allow_lines() {
+    let level = read_ok("size 4 4\n");
+
+    assert_eq!(level.check_place("nand", 1000), Ok(()));
+}
+
+#[test]
+fn check_place_applies_the_limits() {
+    let level = read_ok(HALF_ADDER);
+
+    assert_eq!(level.check_place("wire", 500), Ok(()));
+    assert_eq!(level.check_place("xor", 0), Ok(()));
+    assert!(level.check_place("xor", 1).is_err());
+    assert!(level.check_place("or", 0).is_err());
+    assert!(level.check_place("in", 0).is_err());
+}
+
+#[test]
+fn check_additions_counts_within_the_batch() {
+    let level = read_ok(HALF_ADDER);
+    let (first, second) = (Point3I::new(2, 2, 0), Point3I::new(3, 2, 0));
+
+    assert_eq!(level.check_additions(vec!(("xor", &first), ("wire", &second)), |_| 0), Ok(()));
+    assert!(level.check_additions(vec!(("xor", &first), ("xor", &second)), |_| 0).is_err());
+    assert!(level.check_additions(vec!(("and", &first)), |kind| if kind == "and" { 1 } else { 0 }).is_err());
+}
+
+#[test]
+fn check_additions_refuses_cells_off_the_board_or_locked() {
+    let level = read_ok(HALF_ADDER);
+
+    assert!(level.check_additions(vec!(("wire", &Point3I::new(8, 2, 0))), |_| 0).is_err());
+    assert!(level.check_additions(vec!(("wire", &Point3I::new(0, 1, 1))), |_| 0).is_err());
+}
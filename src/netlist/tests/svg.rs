@@ -0,0 +1,188 @@
+extern crate components;
+extern crate math;
+extern crate netlist;
+
+use components::GateKind;
+
+use math::Point3I;
+
+use netlist::{Net, Netlist, NodeKind};
+use netlist::svg;
+
+//*************************************************************************************************
+
+const WIRE: &'static str = "stroke=\"#4060a0\"";
+const WIRE_LOW: &'static str = "stroke=\"#606060\"";
+const WIRE_HIGH: &'static str = "stroke=\"#e04020\"";
+
+fn connect(driver: usize) -> Option<Net> {
+    Some(Net::new(driver, vec!()))
+}
+
+//two named inputs through one gate to a named output
+fn make_gate_netlist(kind: GateKind) -> Netlist {
+    let mut netlist = Netlist::new();
+    let a = netlist.add_node(NodeKind::Input("a".to_string()), Point3I::new(0, 0, 0), vec!());
+    let b = netlist.add_node(NodeKind::Input("b".to_string()), Point3I::new(0, 1, 0), vec!());
+    let inputs = if kind.is_unary() {
+        vec!(connect(a))
+    } else {
+        vec!(connect(a), connect(b))
+    };
+    let gate = netlist.add_node(NodeKind::Gate(kind), Point3I::new(1, 0, 0), inputs);
+    netlist.add_node(NodeKind::Output("y".to_string()), Point3I::new(2, 0, 0), vec!(connect(gate)));
+    netlist
+}
+
+//an input wired to an output along the path
+fn make_path_netlist(path: Vec<Point3I>, output: Point3I) -> Netlist {
+    let mut netlist = Netlist::new();
+    let a = netlist.add_node(NodeKind::Input("a".to_string()), Point3I::new(0, 0, 0), vec!());
+    netlist.add_node(NodeKind::Output("y".to_string()), output, vec!(Some(Net::new(a, path))));
+    netlist
+}
+
+fn write_string(netlist: &Netlist, values: bool) -> String {
+    let mut output = vec!();
+    svg::write(netlist, values, &mut output).expect("write failed");
+    String::from_utf8(output).expect("svg was not utf8")
+}
+
+fn lines_with<'a>(source: &'a str, start: &str) -> Vec<&'a str> {
+    source.lines().map(|line| line.trim()).filter(|line| line.starts_with(start)).collect()
+}
+
+//the lines from the title of the symbol up to the end of its group
+fn symbol_lines<'a>(source: &'a str, title: &str) -> Vec<&'a str> {
+    let title = format!("<g><title>{}</title>", title);
+    source.lines()
+        .map(|line| line.trim())
+        .skip_while(|line| *line != title)
+        .skip(1)
+        .take_while(|line| *line != "</g>")
+        .collect()
+}
+
+#[test]
+fn every_gate_kind_has_its_own_symbol() {
+    let mut paths = vec!();
+    for &kind in GateKind::all() {
+        let source = write_string(&make_gate_netlist(kind), false);
+        let symbol = symbol_lines(&source, kind.get_name());
+        assert!(!symbol.is_empty(), "{} has no symbol in\n{}", kind.get_name(), source);
+
+        //inverting gates end in a bubble
+        let bubbles = symbol.iter().filter(|line| line.starts_with("<circle")).count();
+        let inverts = match kind {
+            GateKind::Not | GateKind::Nand | GateKind::Nor | GateKind::Xnor => 1,
+            _ => 0,
+        };
+        assert_eq!(bubbles, inverts, "{}", kind.get_name());
+
+        //xor draws a second curve behind its inputs
+        let path = symbol.iter().find(|line| line.starts_with("<path")).expect("symbol has no path").to_string();
+        let curves = match kind {
+            GateKind::Xor | GateKind::Xnor => 2,
+            _ => 1,
+        };
+        assert_eq!(path.matches("M ").count(), curves, "{}", kind.get_name());
+
+        //the bubble aside, gates that only differ by it share a body
+        paths.push((kind, path));
+    }
+
+    let body = |kind: GateKind| paths.iter().find(|&&(other, _)| other == kind).map(|&(_, ref path)| path.clone());
+    assert_eq!(body(GateKind::And), body(GateKind::Nand));
+    assert_eq!(body(GateKind::Buf), body(GateKind::Not));
+    assert!(body(GateKind::And) != body(GateKind::Or));
+    assert!(body(GateKind::Or) != body(GateKind::Xor));
+}
+
+#[test]
+fn inputs_and_outputs_have_symbols() {
+    let source = write_string(&make_gate_netlist(GateKind::And), false);
+
+    assert_eq!(symbol_lines(&source, "input a").len(), 1);
+    assert_eq!(symbol_lines(&source, "input b").len(), 1);
+    assert_eq!(symbol_lines(&source, "output y").len(), 1);
+}
+
+#[test]
+fn every_net_is_one_polyline() {
+    let source = write_string(&make_gate_netlist(GateKind::Xor), false);
+
+    assert_eq!(lines_with(&source, "<polyline").len(), 3);
+}
+
+#[test]
+fn nets_run_through_their_path_with_y_down() {
+    let netlist = make_path_netlist(vec!(Point3I::new(1, 0, 0), Point3I::new(1, 1, 0)), Point3I::new(2, 1, 0));
+    let source = write_string(&netlist, false);
+
+    //a cell of margin all round, so the board spans -1,-1 to 3,2
+    assert!(source.contains("width=\"160\" height=\"128\" viewBox=\"0 0 160 128\""), "source was\n{}", source);
+    assert_eq!(lines_with(&source, "<polyline"), vec!(format!("<polyline {} points=\"48,80 80,80 80,48 112,48\"/>", WIRE).as_str()));
+}
+
+#[test]
+fn vias_mark_every_change_of_layer() {
+    let flat = make_path_netlist(vec!(Point3I::new(1, 0, 0)), Point3I::new(2, 0, 0));
+    assert!(lines_with(&write_string(&flat, false), "<circle").is_empty());
+
+    //up to the next layer and back down again at the output
+    let netlist = make_path_netlist(vec!(Point3I::new(1, 0, 1)), Point3I::new(2, 0, 0));
+    let source = write_string(&netlist, false);
+    assert_eq!(lines_with(&source, "<circle"), vec!(
+        "<circle cx=\"80\" cy=\"48\" r=\"3.2\" fill=\"#4060a0\"/>",
+        "<circle cx=\"112\" cy=\"48\" r=\"3.2\" fill=\"#4060a0\"/>",
+    ));
+}
+
+#[test]
+fn values_color_nets_by_their_driver() {
+    let mut netlist = make_gate_netlist(GateKind::And);
+    netlist.get_mut_node(0).set_value(1);
+
+    let plain = write_string(&netlist, false);
+    assert_eq!(plain.matches(WIRE).count(), 3);
+    assert!(!plain.contains(WIRE_HIGH) && !plain.contains(WIRE_LOW));
+
+    let source = write_string(&netlist, true);
+    assert_eq!(source.matches(WIRE_HIGH).count(), 1);
+    assert_eq!(source.matches(WIRE_LOW).count(), 2);
+    assert!(!source.contains(WIRE));
+    assert!(source.contains(">a=1</text>") && source.contains(">b=0</text>") && source.contains(">y=0</text>"), "source was\n{}", source);
+}
+
+#[test]
+fn gate_pins_are_labelled() {
+    let mut netlist = make_gate_netlist(GateKind::Nand);
+    netlist.get_mut_node(1).set_value(1);
+    netlist.get_mut_node(2).set_value(1);
+
+    let source = write_string(&netlist, false);
+    let ends: Vec<&str> = lines_with(&source, "<text").into_iter().filter(|line| line.contains("text-anchor=\"end\"")).collect();
+    assert_eq!(ends.len(), 2);
+    assert!(ends[0].ends_with(">a</text>") && ends[1].ends_with(">b</text>"), "pins were {:?}", ends);
+    let starts = lines_with(&source, "<text").into_iter().filter(|line| line.contains("text-anchor=\"start\"")).collect::<Vec<&str>>();
+    assert_eq!(starts.len(), 1);
+    assert!(starts[0].ends_with(">y</text>"), "pins were {:?}", starts);
+
+    let source = write_string(&netlist, true);
+    assert!(source.contains("text-anchor=\"end\">a=0</text>"), "source was\n{}", source);
+    assert!(source.contains("text-anchor=\"end\">b=1</text>"), "source was\n{}", source);
+    assert!(source.contains("text-anchor=\"start\">y=1</text>"), "source was\n{}", source);
+}
+
+#[test]
+fn labels_are_escaped() {
+    let mut netlist = Netlist::new();
+    let a = netlist.add_node(NodeKind::Input("<a & \"b\">".to_string()), Point3I::new(0, 0, 0), vec!());
+    netlist.add_node(NodeKind::Output("c>d".to_string()), Point3I::new(1, 0, 0), vec!(connect(a)));
+    let source = write_string(&netlist, false);
+
+    assert!(source.contains(">&lt;a &amp; &quot;b&quot;&gt;</text>"), "source was\n{}", source);
+    assert!(source.contains("<title>input &lt;a &amp; &quot;b&quot;&gt;</title>"), "source was\n{}", source);
+    assert!(source.contains("<title>output c&gt;d</title>"), "source was\n{}", source);
+    assert!(!source.contains("<a "), "source was\n{}", source);
+}
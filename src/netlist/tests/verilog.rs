@@ -0,0 +1,246 @@
+extern crate components;
+extern crate math;
+extern crate netlist;
+
+use components::GateKind;
+
+use math::Point3I;
+
+use netlist::{Net, Netlist, NodeKind};
+use netlist::sim::{Simulator};
+use netlist::verilog;
+
+//*************************************************************************************************
+
+fn connect(driver: usize) -> Option<Net> {
+    Some(Net::new(driver, vec!()))
+}
+
+//two named inputs through one gate to a named output
+fn make_gate_netlist(kind: GateKind, a: &str, b: &str, y: &str) -> Netlist {
+    let mut netlist = Netlist::new();
+    let a = netlist.add_node(NodeKind::Input(a.to_string()), Point3I::new(0, 0, 0), vec!());
+    let b = netlist.add_node(NodeKind::Input(b.to_string()), Point3I::new(0, 1, 0), vec!());
+    let gate = netlist.add_node(NodeKind::Gate(kind), Point3I::new(1, 0, 0), vec!(connect(a), connect(b)));
+    netlist.add_node(NodeKind::Output(y.to_string()), Point3I::new(2, 0, 0), vec!(connect(gate)));
+    netlist
+}
+
+fn write_string(netlist: &Netlist, module_name: &str) -> String {
+    let mut output = vec!();
+    verilog::write(netlist, module_name, &mut output).expect("write failed");
+    String::from_utf8(output).expect("verilog was not utf8")
+}
+
+fn lines(source: &str) -> Vec<&str> {
+    source.lines().map(|line| line.trim()).collect()
+}
+
+fn read_string(source: &str) -> Netlist {
+    match verilog::read(source.as_bytes()) {
+        Ok(netlist) => netlist,
+        Err(err) => panic!("read failed: {}", err),
+    }
+}
+
+fn port_names(netlist: &Netlist, indices: Vec<usize>) -> Vec<String> {
+    indices.into_iter().map(|index| match *netlist.get_node(index).get_kind() {
+        NodeKind::Input(ref name) | NodeKind::Output(ref name) => name.clone(),
+        NodeKind::Gate(_) => panic!("node {} is not a port", index),
+    }).collect()
+}
+
+fn gate_kinds(netlist: &Netlist) -> Vec<&'static str> {
+    let mut kinds: Vec<&'static str> = netlist.get_gates().into_iter().map(|index| match *netlist.get_node(index).get_kind() {
+        NodeKind::Gate(kind) => kind.get_name(),
+        _ => unreachable!(),
+    }).collect();
+    kinds.sort();
+    kinds
+}
+
+//every output for every combination of inputs, once the circuit has settled
+fn truth_table(netlist: &Netlist) -> Vec<Vec<u8>> {
+    let inputs = netlist.get_inputs();
+    let outputs = netlist.get_outputs();
+
+    (0..1 << inputs.len()).map(|combination: usize| {
+        let mut simulator = Simulator::new(netlist);
+        for (bit, &index) in inputs.iter().enumerate() {
+            simulator.set_value(index, ((combination >> bit) & 1) as u8);
+        }
+        simulator.settle(netlist, 100).expect("circuit did not settle");
+        outputs.iter().map(|&index| simulator.get_value(index)).collect()
+    }).collect()
+}
+
+fn assert_round_trip(netlist: &Netlist) {
+    let source = write_string(netlist, "round_trip");
+    let read = read_string(&source);
+
+    assert_eq!(port_names(&read, read.get_inputs()), port_names(netlist, netlist.get_inputs()), "inputs of\n{}", source);
+    assert_eq!(port_names(&read, read.get_outputs()), port_names(netlist, netlist.get_outputs()), "outputs of\n{}", source);
+    assert_eq!(gate_kinds(&read), gate_kinds(netlist), "gates of\n{}", source);
+    assert_eq!(truth_table(&read), truth_table(netlist), "truth table of\n{}", source);
+}
+
+#[test]
+fn writes_ports_gates_and_assigns() {
+    let source = write_string(&make_gate_netlist(GateKind::And, "a", "b", "y"), "half");
+    let lines = lines(&source);
+
+    assert_eq!(lines[0], "module half(a, b, y);");
+    assert!(lines.contains(&"input a;"));
+    assert!(lines.contains(&"input b;"));
+    assert!(lines.contains(&"output y;"));
+    assert!(lines.contains(&"wire n2;"));
+    assert!(lines.contains(&"and g2(n2, a, b);"));
+    assert!(lines.contains(&"assign y = n2;"));
+    assert_eq!(lines.last(), Some(&"endmodule"));
+}
+
+#[test]
+fn names_are_made_into_identifiers() {
+    let source = write_string(&make_gate_netlist(GateKind::Or, "in 1", "2nd", "out-put"), "my circuit");
+    let lines = lines(&source);
+
+    assert_eq!(lines[0], "module my_circuit(in_1, _2nd, out_put);");
+    assert!(lines.contains(&"or g2(n2, in_1, _2nd);"));
+}
+
+#[test]
+fn keywords_are_escaped() {
+    let source = write_string(&make_gate_netlist(GateKind::Xor, "input", "wire", "output"), "module");
+    let lines = lines(&source);
+
+    assert_eq!(lines[0], "module module_(input_, wire_, output_);");
+    assert!(lines.contains(&"input input_;"));
+    assert!(lines.contains(&"input wire_;"));
+    assert!(lines.contains(&"output output_;"));
+    assert!(lines.contains(&"xor g2(n2, input_, wire_);"));
+}
+
+#[test]
+fn instance_names_avoid_port_names() {
+    let source = write_string(&make_gate_netlist(GateKind::Nand, "g2", "n2", "y"), "clash");
+    let lines = lines(&source);
+
+    //the gate's net and instance both move out of the way of the ports
+    assert!(lines.contains(&"wire n2_1;"));
+    assert!(lines.contains(&"nand g2_1(n2_1, g2, n2);"));
+    assert!(lines.contains(&"assign y = n2_1;"));
+}
+
+#[test]
+fn duplicate_port_names_get_suffixes() {
+    let source = write_string(&make_gate_netlist(GateKind::And, "a", "a", "y"), "twice");
+    let lines = lines(&source);
+
+    assert_eq!(lines[0], "module twice(a, a_1, y);");
+    assert!(lines.contains(&"and g2(n2, a, a_1);"));
+}
+
+#[test]
+fn floating_inputs_are_written_low_as_the_board_reads_them() {
+    let mut netlist = Netlist::new();
+    let gate = netlist.add_node(NodeKind::Gate(GateKind::Not), Point3I::new(0, 0, 0), vec!(None));
+    netlist.add_node(NodeKind::Output("y".to_string()), Point3I::new(1, 0, 0), vec!(connect(gate)));
+    netlist.add_node(NodeKind::Output("z".to_string()), Point3I::new(1, 1, 0), vec!(None));
+    //the tie high gate has no input at all, and must still read 1 in a simulator as it does on the board
+    let high = netlist.add_node(NodeKind::Gate(GateKind::Not), Point3I::new(0, 2, 0), vec!());
+    netlist.add_node(NodeKind::Output("w".to_string()), Point3I::new(1, 2, 0), vec!(connect(high)));
+
+    let source = write_string(&netlist, "floating");
+    let lines = lines(&source);
+
+    assert!(lines.contains(&"not g0(n0, 1'b0);"));
+    assert!(lines.contains(&"not g3(n3, 1'b0);"));
+    assert!(lines.contains(&"assign z = 1'b0;"));
+    assert!(!source.contains("1'bz"));
+}
+
+#[test]
+fn round_trips_a_half_adder() {
+    let mut netlist = Netlist::new();
+    let a = netlist.add_node(NodeKind::Input("a".to_string()), Point3I::new(0, 0, 0), vec!());
+    let b = netlist.add_node(NodeKind::Input("b".to_string()), Point3I::new(0, 1, 0), vec!());
+    //both inputs fan out to both gates
+    let sum = netlist.add_node(NodeKind::Gate(GateKind::Xor), Point3I::new(1, 0, 0), vec!(connect(a), connect(b)));
+    let carry = netlist.add_node(NodeKind::Gate(GateKind::And), Point3I::new(1, 1, 0), vec!(connect(a), connect(b)));
+    netlist.add_node(NodeKind::Output("sum".to_string()), Point3I::new(2, 0, 0), vec!(connect(sum)));
+    netlist.add_node(NodeKind::Output("carry".to_string()), Point3I::new(2, 1, 0), vec!(connect(carry)));
+
+    assert_round_trip(&netlist);
+}
+
+#[test]
+fn round_trips_floating_inputs() {
+    let mut netlist = Netlist::new();
+    let a = netlist.add_node(NodeKind::Input("a".to_string()), Point3I::new(0, 0, 0), vec!());
+    let or = netlist.add_node(NodeKind::Gate(GateKind::Or), Point3I::new(1, 0, 0), vec!(connect(a), None));
+    let not = netlist.add_node(NodeKind::Gate(GateKind::Not), Point3I::new(1, 1, 0), vec!(None));
+    netlist.add_node(NodeKind::Output("y".to_string()), Point3I::new(2, 0, 0), vec!(connect(or)));
+    netlist.add_node(NodeKind::Output("high".to_string()), Point3I::new(2, 1, 0), vec!(connect(not)));
+    netlist.add_node(NodeKind::Output("open".to_string()), Point3I::new(2, 2, 0), vec!(None));
+
+    assert_round_trip(&netlist);
+
+    let read = read_string(&write_string(&netlist, "floating"));
+    let or = read.get_gates().into_iter().find(|&index| *read.get_node(index).get_kind() == NodeKind::Gate(GateKind::Or)).expect("or was lost");
+    assert!(read.get_node(or).get_inputs()[1].is_none());
+}
+
+#[test]
+fn constants_are_not_signal_names() {
+    let netlist = read_string("
+        module constants(a, low, high, open, masked);
+            input a;
+            output low, high, open, masked;
+            wire n;
+            and g0(n, a, 1'b0);
+            assign low = 1'b0;
+            assign high = 1'b1;
+            assign open = 1'bz;
+            assign masked = n;
+        endmodule
+    ");
+
+    let outputs = port_names(&netlist, netlist.get_outputs());
+    assert_eq!(outputs, vec!("low", "high", "open", "masked"));
+    //a tied high signal is the only gate added, and every row reads the same constants
+    assert_eq!(gate_kinds(&netlist), vec!("and", "not"));
+    assert_eq!(truth_table(&netlist), vec!(vec!(0, 1, 0, 0), vec!(0, 1, 0, 0)));
+}
+
+#[test]
+fn missing_output_driver_reports_its_line() {
+    let result = verilog::read("module m(a, y);\ninput a;\n\noutput y;\nendmodule\n".as_bytes());
+
+    match result {
+        Ok(_) => panic!("an undriven output was accepted"),
+        Err(err) => {
+            assert_eq!(err.get_line(), 4);
+            assert!(err.get_message().contains("y"), "message was {}", err.get_message());
+        },
+    }
+}
+
+#[test]
+fn missing_gate_driver_reports_its_line() {
+    let result = verilog::read("module m(a, y);\ninput a;\noutput y;\nand g0(n, a, b);\nassign y = n;\nendmodule\n".as_bytes());
+
+    match result {
+        Ok(_) => panic!("an undriven gate input was accepted"),
+        Err(err) => assert_eq!(err.get_line(), 4),
+    }
+}
+
+#[test]
+fn comments_keep_line_numbers() {
+    let result = verilog::read("module m(y);\n/* a\ncomment */ output y; // y\nfoo bar;\nendmodule\n".as_bytes());
+
+    match result {
+        Ok(_) => panic!("an unknown statement was accepted"),
+        Err(err) => assert_eq!(err.get_line(), 4),
+    }
+}
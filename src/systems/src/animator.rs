@@ -0,0 +1,38 @@
+use specs::{self, RunArg};
+
+use comps::{Animation, RenderData};
+
+use utils::Delta;
+
+pub struct System {
+
+}
+
+impl System {
+    pub fn new() -> System {
+        System {
+
+        }
+    }
+}
+
+impl specs::System<Delta> for System {
+    fn run(&mut self, arg: RunArg, delta: Delta) {
+        use specs::Join;
+
+        let (mut animations, mut render_datas) = arg.fetch(|w|
+            (
+                w.write::<Animation>(),
+                w.write::<RenderData>()
+            )
+        );
+
+        for (animation, render_data) in (&mut animations, &mut render_datas).iter() {
+            if animation.advance(delta) {
+                if let Some(rect) = animation.get_rect() {
+                    render_data.set_spritesheet_rect(rect);
+                }
+            }
+        }
+    }
+}
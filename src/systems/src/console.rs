@@ -0,0 +1,815 @@
+use std::sync::mpsc::{TryRecvError, Sender, Receiver};
+use std::fs::{File};
+use std::io::{BufReader};
+use std::str::{FromStr};
+
+use specs::{self, RunArg, World};
+
+//*************************************************************************************************
+
+use comps::{RenderId, Camera, Wire, WireIn, WireOut, Gate, GateKind};
+use comps::non_components::{Map, Console, Selection};
+
+use math::{Point2, Point3I, Rect};
+
+use netlist::{Netlist, NodeKind, svg, verilog};
+use netlist::level::{Level};
+use netlist::save::{Circuit, Item};
+use netlist::sim::{Simulator};
+
+use utils::{Delta, Coord, CoordI, GfxCoord};
+
+use ::router;
+use ::spawn;
+
+use art;
+
+//*************************************************************************************************
+
+const COMMANDS: &'static [&'static str] = &[
+    "help", "goto", "zoom", "fit", "minimap", "split", "layer", "place", "delete", "rotate", "undo", "redo", "route", "set", "tick", "save", "load", "export", "svg", "export_board", "screenshot", "verify", "config", "quit",
+];
+
+//tiles left around the board when framing it
+const FIT_MARGIN: Coord = 1.0;
+
+const PLACE_KINDS: &'static [&'static str] = &["wire", "in", "out"];
+
+pub type Channel = (
+    Sender<SendEvent>,
+    Receiver<RecvEvent>
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecvEvent {
+    Toggle,
+    Char(char),
+    Backspace,
+    Submit,
+    Complete,
+    HistoryPrev,
+    HistoryNext,
+    Command(String),
+    //the main thread's answer to a config, screenshot or export_board command
+    Reply(Result<String, String>),
+    Exit,
+}
+
+#[derive(Debug)]
+pub enum SendEvent {
+    Quit,
+    //reads a setting, or changes and saves it when a value is given
+    Config(String, Option<String>),
+    //saves the next frame as the software renderer draws it, to a timestamped file when no path is given
+    Screenshot(Option<String>),
+    //saves the board rect at pixels per tile
+    ExportBoard(String, Rect, u32),
+}
+
+//a board change kept so undo can apply its inverse
+#[derive(Debug, Clone)]
+enum Edit {
+    Placed(Item),
+    Deleted(Item),
+    Replaced(Item, Item),
+    //several edits made by one command, undone together
+    Group(Vec<Edit>),
+}
+
+impl Edit {
+    fn inverse(&self) -> Edit {
+        match *self {
+            Edit::Placed(ref item) => Edit::Deleted(item.clone()),
+            Edit::Deleted(ref item) => Edit::Placed(item.clone()),
+            Edit::Replaced(ref old, ref new) => Edit::Replaced(new.clone(), old.clone()),
+            Edit::Group(ref edits) => Edit::Group(edits.iter().rev().map(|edit| edit.inverse()).collect()),
+        }
+    }
+}
+
+//submitted lines, walked back and forth with the up and down keys
+#[derive(Debug)]
+pub struct History {
+    lines: Vec<String>,
+    index: Option<usize>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            lines: vec!(),
+            index: None,
+        }
+    }
+
+    //blank lines are not kept, either way the walk starts again from the newest
+    pub fn push(&mut self, line: String) {
+        if !line.trim().is_empty() {
+            self.lines.push(line);
+        }
+        self.index = None;
+    }
+
+    //the line before the one shown, stopping at the oldest
+    pub fn prev(&mut self) -> Option<String> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        let index = match self.index {
+            Some(index) if index > 0 => index - 1,
+            Some(index) => index,
+            None => self.lines.len() - 1,
+        };
+        self.index = Some(index);
+        Some(self.lines[index].clone())
+    }
+
+    //the line after the one shown, an empty input past the newest and nothing when not walking
+    pub fn next(&mut self) -> Option<String> {
+        match self.index {
+            Some(index) if index + 1 < self.lines.len() => {
+                self.index = Some(index + 1);
+                Some(self.lines[index + 1].clone())
+            },
+            Some(_) => {
+                self.index = None;
+                Some(String::new())
+            },
+            None => None,
+        }
+    }
+}
+
+pub struct System {
+    channel: Channel,
+    wires_render: RenderId,
+    layer: CoordI,
+    level: Option<Level>,
+    history: History,
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    exited: bool,
+}
+
+impl System {
+    pub fn new(channel: Channel, wires_render: RenderId, level: Option<Level>) -> System {
+        System {
+            channel: channel,
+            wires_render: wires_render,
+            layer: 0,
+            level: level,
+            history: History::new(),
+            undo: vec!(),
+            redo: vec!(),
+            exited: false,
+        }
+    }
+
+    fn check_input(&mut self) -> Vec<RecvEvent> {
+        let mut events = vec!();
+        loop {
+            match self.channel.1.try_recv() {
+                Ok(RecvEvent::Exit) => {
+                    self.exited = true;
+                    return events;
+                },
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Empty) => return events,
+                Err(err) => {
+                    error!("console check input channel try recv error: {}", err);
+                    self.exited = true;
+                    return events;
+                },
+            }
+        }
+    }
+
+    fn process(&mut self, world: &World, event: RecvEvent) {
+        match event {
+            RecvEvent::Toggle => {
+                let mut console = world.write_resource::<Console>();
+                let open = !console.get_open();
+                *console.get_mut_open() = open;
+            },
+            RecvEvent::Char(c) => world.write_resource::<Console>().get_mut_input().push(c),
+            RecvEvent::Backspace => {
+                world.write_resource::<Console>().get_mut_input().pop();
+            },
+            RecvEvent::Submit => {
+                let line = world.write_resource::<Console>().get_mut_input().drain(..).collect::<String>();
+                self.history.push(line.clone());
+                self.process(world, RecvEvent::Command(line));
+            },
+            RecvEvent::Command(line) => {
+                world.write_resource::<Console>().push_line(format!("> {}", line));
+
+                if line.trim().is_empty() {
+                    return;
+                }
+
+                let output = match self.execute(world, &line) {
+                    Ok(output) => {
+                        info!("console {}: {}", line, output);
+                        output
+                    },
+                    Err(err) => {
+                        warn!("console {} error: {}", line, err);
+                        format!("error: {}", err)
+                    },
+                };
+
+                if !output.is_empty() {
+                    world.write_resource::<Console>().push_line(output);
+                }
+            },
+            RecvEvent::Reply(result) => {
+                let output = match result {
+                    Ok(output) => output,
+                    Err(err) => format!("error: {}", err),
+                };
+                world.write_resource::<Console>().push_line(output);
+            },
+            RecvEvent::Complete => {
+                let mut console = world.write_resource::<Console>();
+                let (input, options) = complete(console.get_input());
+                *console.get_mut_input() = input;
+                if options.len() > 1 {
+                    console.push_line(options.join(" "));
+                }
+            },
+            RecvEvent::HistoryPrev => if let Some(input) = self.history.prev() {
+                *world.write_resource::<Console>().get_mut_input() = input;
+            },
+            RecvEvent::HistoryNext => if let Some(input) = self.history.next() {
+                *world.write_resource::<Console>().get_mut_input() = input;
+            },
+            RecvEvent::Exit => self.exited = true,
+        }
+    }
+
+    fn execute(&mut self, world: &World, line: &str) -> Result<String, String> {
+        use specs::Join;
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.first() {
+            None => Ok(String::new()),
+            Some(&"help") => Ok(format!("commands: {}", COMMANDS.join(" "))),
+            Some(&"goto") => {
+                let x: Coord = try!(parse_word(&words, 1, "goto <x> <y>"));
+                let y: Coord = try!(parse_word(&words, 2, "goto <x> <y>"));
+                for mut camera in (&mut world.write::<Camera>()).iter() {
+                    if camera.is_main() {
+                        let zoom = camera.get_zoom();
+                        camera.move_to(Point2::new(x, y), zoom);
+                    }
+                }
+                Ok(format!("camera at {} {}", x, y))
+            },
+            Some(&"zoom") => {
+                let zoom: GfxCoord = try!(parse_word(&words, 1, "zoom <factor>"));
+                if zoom <= 0.0 {
+                    return Err("zoom must be positive".to_string());
+                }
+                for mut camera in (&mut world.write::<Camera>()).iter() {
+                    if camera.is_main() {
+                        let offset = camera.get_offset();
+                        camera.move_to(offset, zoom);
+                    }
+                }
+                Ok(format!("zoom {}", zoom))
+            },
+            Some(&"fit") => {
+                let map = world.read_resource::<Map>();
+                let rect = try!(make_board_rect(&map).ok_or("nothing on the board to fit".to_string()));
+                for mut camera in (&mut world.write::<Camera>()).iter() {
+                    if camera.is_main() {
+                        let (offset, zoom) = camera.get_fit(&rect);
+                        camera.move_to(offset, zoom);
+                    }
+                }
+                Ok(format!("framed {} cells", map.get_map().len()))
+            },
+            Some(&"minimap") => {
+                let mut visible = false;
+                for mut camera in (&mut world.write::<Camera>()).iter() {
+                    if camera.is_minimap() {
+                        visible = !camera.is_visible();
+                        camera.set_visible(visible);
+                    }
+                }
+                Ok(format!("minimap {}", if visible { "shown" } else { "hidden" }))
+            },
+            Some(&"split") => {
+                //split on its own toggles the second view, split <x> <y> opens it looking at x y
+                let at = if words.len() > 1 {
+                    Some(Point2::new(
+                        try!(parse_word(&words, 1, "split [x y]")),
+                        try!(parse_word(&words, 2, "split [x y]"))
+                    ))
+                } else {
+                    None
+                };
+
+                let mut cameras = world.write::<Camera>();
+                let main_offset = match (&cameras).iter().find(|camera| camera.is_main()) {
+                    Some(camera) => camera.get_offset(),
+                    None => return Err("there is no main camera".to_string()),
+                };
+
+                let mut split = false;
+                for mut camera in (&mut cameras).iter() {
+                    if !camera.is_main() && !camera.is_minimap() {
+                        split = at.is_some() || !camera.is_visible();
+                        camera.set_visible(split);
+                        if split {
+                            camera.set_offset(at.clone().unwrap_or(main_offset.clone()));
+                        }
+                    }
+                }
+
+                let viewport = if split {
+                    art::camera::SPLIT_MAIN_VIEWPORT
+                } else {
+                    art::camera::FULL_VIEWPORT
+                };
+                for mut camera in (&mut cameras).iter() {
+                    if camera.is_main() {
+                        camera.set_viewport(Rect::new_from_coords(viewport[0], viewport[1], viewport[2], viewport[3]));
+                    }
+                }
+                Ok(format!("split view {}", if split { "on" } else { "off" }))
+            },
+            Some(&"layer") => {
+                self.layer = try!(parse_word(&words, 1, "layer <n>"));
+                world.write_resource::<Selection>().set_layer(self.layer);
+                Ok(format!("placing on layer {}", self.layer))
+            },
+            Some(&"place") => self.place(world, &words),
+            Some(&"delete") => {
+                let location = try!(self.parse_location(&words, 1, "delete <x> <y>"));
+                if self.level.as_ref().map_or(false, |level| level.is_locked(&location)) {
+                    return Err(format!("{:?} is locked by the level", location));
+                }
+                let item = try!(find_item(world, &location).ok_or(format!("nothing at {:?}", location)));
+                spawn::despawn_at(world, &location);
+                self.push_edit(Edit::Deleted(item));
+                Ok(format!("deleted {:?}", location))
+            },
+            Some(&"rotate") => {
+                let location = try!(self.parse_location(&words, 1, "rotate <x> <y>"));
+                if self.level.as_ref().map_or(false, |level| level.is_locked(&location)) {
+                    return Err(format!("{:?} is locked by the level", location));
+                }
+                let item = try!(find_item(world, &location).ok_or(format!("nothing at {:?}", location)));
+                let rotated = rotate_item(&item);
+                let edit = Edit::Replaced(item, rotated);
+                try!(self.apply_edit(world, &edit));
+                self.push_edit(edit);
+                Ok(format!("rotated {:?}", location))
+            },
+            Some(&"undo") => {
+                let edit = try!(self.undo.pop().ok_or("nothing to undo".to_string()));
+                try!(self.apply_edit(world, &edit.inverse()));
+                self.redo.push(edit);
+                Ok(format!("undid, {} left", self.undo.len()))
+            },
+            Some(&"redo") => {
+                let edit = try!(self.redo.pop().ok_or("nothing to redo".to_string()));
+                try!(self.apply_edit(world, &edit));
+                self.undo.push(edit);
+                Ok(format!("redid, {} left", self.redo.len()))
+            },
+            Some(&"route") => self.route(world, &words),
+            Some(&"set") => {
+                let usage = "set <x> <y> <z> <value>";
+                let location = Point3I::new(
+                    try!(parse_word(&words, 1, usage)),
+                    try!(parse_word(&words, 2, usage)),
+                    try!(parse_word(&words, 3, usage))
+                );
+                let value: u8 = try!(parse_word(&words, 4, usage));
+
+                let entity = match world.read_resource::<Map>().get_map().get(&location) {
+                    Some(&entity) => entity,
+                    None => return Err(format!("nothing at {:?}", location)),
+                };
+
+                match world.write::<WireIn>().get_mut(entity) {
+                    Some(wire_in) => {
+                        *wire_in.get_mut_value() = value;
+                        *wire_in.get_mut_dirty() = true;
+                        Ok(format!("{:?} = {}", location, value))
+                    },
+                    None => Err(format!("{:?} is not an input", location)),
+                }
+            },
+            Some(&"tick") => {
+                let ticks: u32 = try!(parse_word(&words, 1, "tick <n>"));
+                let netlist = Netlist::from_world(world);
+                let mut simulator = Simulator::new(&netlist);
+                simulator.run(&netlist, ticks);
+                write_values(world, &netlist, &simulator);
+                Ok(format!("ran {} ticks over {} nodes", ticks, netlist.get_nodes().len()))
+            },
+            Some(&"save") => {
+                let path = *try!(words.get(1).ok_or("usage: save <file>".to_string()));
+                let circuit = Circuit::from_world(world);
+                let mut file = try!(File::create(path).map_err(|err| format!("create {} error: {}", path, err)));
+                try!(circuit.write(&mut file).map_err(|err| format!("write {} error: {}", path, err)));
+                Ok(format!("saved {} items to {}", circuit.get_items().len(), path))
+            },
+            Some(&"load") => {
+                let path = *try!(words.get(1).ok_or("usage: load <file>".to_string()));
+                let file = try!(File::open(path).map_err(|err| format!("open {} error: {}", path, err)));
+                let circuit = try!(Circuit::read(BufReader::new(file)).map_err(|err| format!("{} {}", path, err)));
+
+                let mut placed = 0;
+                let mut skipped = 0;
+                let mut refused = 0;
+                for item in circuit.get_items() {
+                    let occupied = world.read_resource::<Map>().get_map().contains_key(item.get_location());
+                    if occupied {
+                        skipped += 1;
+                    } else if let Err(err) = self.check_level(world, item.get_kind_name(), item.get_location()) {
+                        warn!("load {} refused {:?}: {}", path, item.get_location(), err);
+                        refused += 1;
+                    } else {
+                        spawn::spawn_item(world, item, self.wires_render);
+                        placed += 1;
+                    }
+                }
+                Ok(format!("loaded {} items from {}, skipped {} occupied and {} refused by the level", placed, path, skipped, refused))
+            },
+            Some(&"export") => {
+                let path = *try!(words.get(1).ok_or("usage: export <file.v>".to_string()));
+                let module_name = path.split('/').last().unwrap_or(path).split('.').next().unwrap_or("circuit");
+                let netlist = Netlist::from_world(world);
+                let mut file = try!(File::create(path).map_err(|err| format!("create {} error: {}", path, err)));
+                try!(verilog::write(&netlist, module_name, &mut file).map_err(|err| format!("write {} error: {}", path, err)));
+                Ok(format!("exported {} nodes to {}", netlist.get_nodes().len(), path))
+            },
+            Some(&"svg") => {
+                let usage = "usage: svg <file.svg> [values]";
+                let path = *try!(words.get(1).ok_or(usage.to_string()));
+                let values = match words.get(2) {
+                    Some(&"values") => true,
+                    Some(_) => return Err(usage.to_string()),
+                    None => false,
+                };
+                let netlist = Netlist::from_world(world);
+                let mut file = try!(File::create(path).map_err(|err| format!("create {} error: {}", path, err)));
+                try!(svg::write(&netlist, values, &mut file).map_err(|err| format!("write {} error: {}", path, err)));
+                Ok(format!("drew {} nodes to {}", netlist.get_nodes().len(), path))
+            },
+            Some(&"screenshot") => {
+                let path = words.get(1).map(|path| path.to_string());
+                match self.channel.0.send(SendEvent::Screenshot(path)) {
+                    Ok(()) => Ok(String::new()),
+                    Err(err) => Err(format!("screenshot send error: {}", err)),
+                }
+            },
+            Some(&"export_board") => {
+                let usage = "export_board <file.png> [pixels per tile]";
+                let path = *try!(words.get(1).ok_or(format!("usage: {}", usage)));
+                let pixels_per_tile: u32 = if words.len() > 2 {
+                    try!(parse_word(&words, 2, usage))
+                } else {
+                    art::camera::EXPORT_PIXELS_PER_TILE
+                };
+                if pixels_per_tile == 0 {
+                    return Err("pixels per tile must be positive".to_string());
+                }
+
+                let rect = try!(make_board_rect(&world.read_resource::<Map>()).ok_or("nothing on the board to export".to_string()));
+                let max = art::camera::EXPORT_MAX_SIZE;
+                let (width, height) = try!(make_export_size(&rect, pixels_per_tile)
+                    .ok_or(format!("the board is past the {} pixel limit, use fewer pixels per tile", max)));
+                if width > max || height > max {
+                    return Err(format!("{}x{} is past the {} pixel limit, use fewer pixels per tile", width, height, max));
+                }
+
+                match self.channel.0.send(SendEvent::ExportBoard(path.to_string(), rect, pixels_per_tile)) {
+                    Ok(()) => Ok(format!("exporting {}x{} to {}", width, height, path)),
+                    Err(err) => Err(format!("export board send error: {}", err)),
+                }
+            },
+            Some(&"verify") => {
+                let level = try!(self.level.as_ref().ok_or("no level is loaded".to_string()));
+                let report = level.verify(&Netlist::from_world(world));
+                if report.passed() {
+                    Ok(report.to_string())
+                } else {
+                    Err(report.to_string())
+                }
+            },
+            Some(&"config") => {
+                let key = *try!(words.get(1).ok_or("usage: config <setting> [value]".to_string()));
+                let value = if words.len() > 2 {
+                    Some(words[2..].join(" "))
+                } else {
+                    None
+                };
+                //the main thread owns the config and replies once it has handled it
+                match self.channel.0.send(SendEvent::Config(key.to_string(), value)) {
+                    Ok(()) => Ok(String::new()),
+                    Err(err) => Err(format!("config send error: {}", err)),
+                }
+            },
+            Some(&"quit") => {
+                match self.channel.0.send(SendEvent::Quit) {
+                    Ok(()) => Ok("quitting".to_string()),
+                    Err(err) => Err(format!("quit send error: {}", err)),
+                }
+            },
+            Some(other) => Err(format!("unknown command {}, try help", other)),
+        }
+    }
+
+    fn place(&mut self, world: &World, words: &[&str]) -> Result<String, String> {
+        let usage = "place <wire|in|out|gate kind> <x> <y> [name]";
+        let what = try!(words.get(1).ok_or(format!("usage: {}", usage)));
+        let location = try!(self.parse_location(words, 2, usage));
+        let name = words.get(4).map(|name| name.to_string());
+
+        let west = Point3I::new(location.get_x() - 1, location.get_y(), location.get_z());
+        let east = Point3I::new(location.get_x() + 1, location.get_y(), location.get_z());
+        let south = Point3I::new(location.get_x(), location.get_y() - 1, location.get_z());
+
+        let occupied = world.read_resource::<Map>().get_map().contains_key(&location);
+        if occupied {
+            return Err(format!("{:?} is already occupied", location));
+        }
+
+        try!(self.check_level(world, what, &location));
+
+        let item = match *what {
+            "wire" => Item::Wire(location.clone(), west, east),
+            "in" => Item::WireIn(location.clone(), vec!(east), name),
+            "out" => Item::WireOut(location.clone(), west, name),
+            kind => match GateKind::from_name(kind) {
+                Some(kind) => {
+                    let inputs = if kind.is_unary() {
+                        vec!(west)
+                    } else {
+                        vec!(west, south)
+                    };
+                    Item::Gate(kind, location.clone(), vec!(east), inputs)
+                },
+                None => return Err(format!("cannot place {}", kind)),
+            },
+        };
+
+        spawn::spawn_item(world, &item, self.wires_render);
+        self.push_edit(Edit::Placed(item));
+
+        Ok(format!("placed {} at {:?}", what, location))
+    }
+
+    //wires along a free path between the two cells, the ends rewired to lead into it, all as one edit
+    fn route(&mut self, world: &World, words: &[&str]) -> Result<String, String> {
+        let usage = "route <x0> <y0> <x1> <y1>";
+        let from = try!(self.parse_location(words, 1, usage));
+        let to = try!(self.parse_location(words, 3, usage));
+        if let Some(ref level) = self.level {
+            for location in &[&from, &to] {
+                if !level.contains(location) {
+                    return Err(format!("{:?} is outside the board", location));
+                }
+            }
+        }
+
+        let path = {
+            let map = world.read_resource::<Map>();
+            try!(router::find_path(&from, &to, &map, self.level.as_ref()).ok_or(format!("no route found from {:?} to {:?}", from, to)))
+        };
+        if path.len() < 2 {
+            return Err(format!("{:?} is already routed to itself", from));
+        }
+
+        let last = path.len() - 1;
+        if let Some(ref level) = self.level {
+            let added = path[1..last].iter().map(|location| ("wire", location));
+            try!(level.check_additions(added, |kind| count_placed(world, kind)));
+        }
+
+        let mut edits: Vec<Edit> = (1..last)
+            .map(|i| Edit::Placed(Item::Wire(path[i].clone(), path[i - 1].clone(), path[i + 1].clone())))
+            .collect();
+        if let Some(item) = find_item(world, &path[0]) {
+            let connected = router::connect_output(&item, &path[1], &world.read_resource::<Map>());
+            if let Some(connected) = connected {
+                edits.push(Edit::Replaced(item, connected));
+            }
+        }
+        if let Some(item) = find_item(world, &path[last]) {
+            if let Some(connected) = router::connect_input(&item, &path[last - 1]) {
+                edits.push(Edit::Replaced(item, connected));
+            }
+        }
+
+        let edit = Edit::Group(edits);
+        try!(self.apply_edit(world, &edit));
+        self.push_edit(edit);
+
+        Ok(format!("routed {:?} to {:?} with {} wires", from, to, last - 1))
+    }
+
+    //bounds, locks and part limits for one more of what at location
+    fn check_level(&self, world: &World, what: &str, location: &Point3I) -> Result<(), String> {
+        match self.level {
+            Some(ref level) => level.check_additions(vec!((what, location)), |kind| count_placed(world, kind)),
+            None => Ok(()),
+        }
+    }
+
+    //a new edit makes anything undone unreachable
+    fn push_edit(&mut self, edit: Edit) {
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+
+    fn apply_edit(&self, world: &World, edit: &Edit) -> Result<(), String> {
+        let (remove, add) = match *edit {
+            Edit::Placed(ref item) => (None, Some(item)),
+            Edit::Deleted(ref item) => (Some(item), None),
+            Edit::Replaced(ref old, ref new) => (Some(old), Some(new)),
+            Edit::Group(ref edits) => {
+                for edit in edits {
+                    try!(self.apply_edit(world, edit));
+                }
+                return Ok(());
+            },
+        };
+
+        if let Some(item) = remove {
+            if spawn::despawn_at(world, item.get_location()).is_none() {
+                return Err(format!("nothing at {:?}", item.get_location()));
+            }
+        }
+
+        if let Some(item) = add {
+            let occupied = world.read_resource::<Map>().get_map().contains_key(item.get_location());
+            if occupied {
+                return Err(format!("{:?} is already occupied", item.get_location()));
+            }
+            spawn::spawn_item(world, item, self.wires_render);
+        }
+
+        Ok(())
+    }
+
+    fn parse_location(&self, words: &[&str], index: usize, usage: &str) -> Result<Point3I, String> {
+        Ok(Point3I::new(
+            try!(parse_word(words, index, usage)),
+            try!(parse_word(words, index + 1, usage)),
+            self.layer
+        ))
+    }
+}
+
+impl specs::System<Delta> for System {
+    fn run(&mut self, arg: RunArg, _: Delta) {
+        let events = self.check_input();
+
+        if self.exited {
+            arg.fetch(|_| ());
+            return;
+        }
+
+        arg.fetch(|w| {
+            for event in events {
+                self.process(w, event);
+            }
+        });
+    }
+}
+
+pub fn parse_word<T: FromStr>(words: &[&str], index: usize, usage: &str) -> Result<T, String> {
+    match words.get(index).and_then(|word| word.parse().ok()) {
+        Some(value) => Ok(value),
+        None => Err(format!("usage: {}", usage)),
+    }
+}
+
+//completes the word being typed, returning the new input and every option that matched
+pub fn complete(input: &str) -> (String, Vec<&'static str>) {
+    let words: Vec<&str> = input.split(' ').collect();
+
+    let candidates: Vec<&'static str> = match (words.len(), words[0]) {
+        (1, _) => COMMANDS.to_vec(),
+        (2, "place") => PLACE_KINDS.iter().cloned().chain(GateKind::all().iter().map(|kind| kind.get_name())).collect(),
+        _ => return (input.to_string(), vec!()),
+    };
+
+    let prefix = words[words.len() - 1];
+    let options: Vec<&'static str> = candidates.into_iter().filter(|candidate| candidate.starts_with(prefix)).collect();
+
+    let completed = match options.len() {
+        0 => return (input.to_string(), options),
+        1 => format!("{} ", options[0]),
+        _ => {
+            let mut common = options[0].to_string();
+            for option in &options[1..] {
+                while !option.starts_with(common.as_str()) {
+                    common.pop();
+                }
+            }
+            common
+        },
+    };
+
+    let mut result = words[..words.len() - 1].join(" ");
+    if !result.is_empty() {
+        result.push(' ');
+    }
+    result.push_str(&completed);
+    (result, options)
+}
+
+//the occupied cells with a margin so the edges stay off the window border, cells span one tile from their location
+pub fn make_board_rect(map: &Map) -> Option<Rect> {
+    map.get_bounds().map(|(min, max)| Rect::new_from_coords(
+        min.get_x() as Coord - FIT_MARGIN,
+        min.get_y() as Coord - FIT_MARGIN,
+        max.get_x() as Coord + 1.0 + FIT_MARGIN,
+        max.get_y() as Coord + 1.0 + FIT_MARGIN
+    ))
+}
+
+//the image size of a board rect, none when it would not fit in a u32
+pub fn make_export_size(rect: &Rect, pixels_per_tile: u32) -> Option<(u32, u32)> {
+    let width = (rect.get_top_right().get_x() - rect.get_bot_left().get_x()) as u32;
+    let height = (rect.get_top_right().get_y() - rect.get_bot_left().get_y()) as u32;
+    match (width.checked_mul(pixels_per_tile), height.checked_mul(pixels_per_tile)) {
+        (Some(width), Some(height)) => Some((width, height)),
+        _ => None,
+    }
+}
+
+fn find_item(world: &World, location: &Point3I) -> Option<Item> {
+    let entity = world.read_resource::<Map>().get_map().get(location).cloned();
+    entity.and_then(|entity| Item::from_entity(world, entity))
+}
+
+//turns every connection a quarter turn counter clockwise around the item
+fn rotate_item(item: &Item) -> Item {
+    let turn = |me: &Point3I, point: &Point3I| Point3I::new(
+        me.get_x() - (point.get_y() - me.get_y()),
+        me.get_y() + (point.get_x() - me.get_x()),
+        point.get_z()
+    );
+
+    match *item {
+        Item::Wire(ref me, ref input, ref output) => Item::Wire(me.clone(), turn(me, input), turn(me, output)),
+        Item::WireIn(ref me, ref outputs, ref name) => Item::WireIn(me.clone(), outputs.iter().map(|output| turn(me, output)).collect(), name.clone()),
+        Item::WireOut(ref me, ref input, ref name) => Item::WireOut(me.clone(), turn(me, input), name.clone()),
+        Item::Gate(kind, ref me, ref outputs, ref inputs) => Item::Gate(kind, me.clone(), outputs.iter().map(|output| turn(me, output)).collect(), inputs.iter().map(|input| turn(me, input)).collect()),
+    }
+}
+
+pub fn count_placed(world: &World, what: &str) -> u32 {
+    use specs::Join;
+
+    let count = match what {
+        "wire" => (&world.read::<Wire>()).iter().count(),
+        "in" => (&world.read::<WireIn>()).iter().count(),
+        "out" => (&world.read::<WireOut>()).iter().count(),
+        kind => (&world.read::<Gate>()).iter().filter(|gate| gate.get_kind().get_name() == kind).count(),
+    };
+    count as u32
+}
+
+//copies simulated values back onto the gates, outputs and every wire along each net
+fn write_values(world: &World, netlist: &Netlist, simulator: &Simulator) {
+    let map = world.read_resource::<Map>();
+    let mut wires = world.write::<Wire>();
+    let mut wires_out = world.write::<WireOut>();
+    let mut gates = world.write::<Gate>();
+
+    for (index, node) in netlist.get_nodes().iter().enumerate() {
+        if let Some(&entity) = map.get_map().get(node.get_location()) {
+            match *node.get_kind() {
+                NodeKind::Gate(_) => if let Some(gate) = gates.get_mut(entity) {
+                    *gate.get_mut_value() = simulator.get_value(index);
+                    *gate.get_mut_dirty() = true;
+                },
+                NodeKind::Output(_) => if let Some(wire_out) = wires_out.get_mut(entity) {
+                    *wire_out.get_mut_value() = simulator.get_value(index);
+                    *wire_out.get_mut_dirty() = true;
+                },
+                NodeKind::Input(_) => (),
+            }
+        }
+
+        for net in node.get_inputs().iter().filter_map(|net| net.as_ref()) {
+            let value = simulator.get_value(net.get_driver());
+            for location in net.get_path() {
+                if let Some(wire) = map.get_map().get(location).and_then(|&entity| wires.get_mut(entity)) {
+                    *wire.get_mut_value() = value;
+                    *wire.get_mut_dirty() = true;
+                }
+            }
+        }
+    }
+}
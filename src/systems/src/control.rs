@@ -6,11 +6,15 @@ use specs::{self, RunArg};
 
 //*************************************************************************************************
 
-use math::{OrthographicHelper, Point2};
+use math::{OrthographicHelper, Point2, Point2I};
 
-use comps::{Transform, Camera, Clickable};
+use comps::{Transform, Camera, Overlay, Text};
+use comps::camera::{MIN_ZOOM, MAX_ZOOM};
+use comps::non_components::{Selection, Map};
 
-use utils::{Delta, GfxCoord, Coord};
+use utils::{Delta, GfxCoord, Coord, CoordI};
+
+use ::console;
 
 //*************************************************************************************************
 
@@ -19,18 +23,79 @@ pub type Channel = (
     Receiver<RecvEvent>
 );
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RecvEvent {
     Right(bool),
     Left(bool),
     Up(bool),
     Down(bool),
     Resize(u32, u32),
-    MouseMoved(u32, u32),
+    //pixels from the top left, outside the window while a drag holds the cursor
+    MouseMoved(i32, i32),
+    //wheel lines scrolled, positive zooms in
+    Zoom(GfxCoord),
+    //edge scrolling only runs while the window has focus
+    Focused(bool),
+    //left selects and right routes, kept so older recordings still replay
     MouseInput(bool, MouseButton),
+    Action(Action, bool),
     Exit,
 }
 
+//what a bound key or mouse button does under the cursor, true while pressed
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    Select,
+    Route,
+    Place,
+    Erase,
+    Rotate,
+    ZoomIn,
+    ZoomOut,
+    Undo,
+    Redo,
+    //drags the board along with the mouse while held
+    Pan,
+}
+
+const ACTIONS: &'static [(&'static str, Action)] = &[
+    ("select", Action::Select),
+    ("route", Action::Route),
+    ("place", Action::Place),
+    ("erase", Action::Erase),
+    ("rotate", Action::Rotate),
+    ("zoom_in", Action::ZoomIn),
+    ("zoom_out", Action::ZoomOut),
+    ("undo", Action::Undo),
+    ("redo", Action::Redo),
+    ("pan", Action::Pan),
+];
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|&&(action_name, _)| action_name == name).map(|&(_, action)| action)
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match ACTIONS.iter().find(|&&(_, action)| action == *self) {
+            Some(&(name, _)) => name,
+            None => unreachable!(),
+        }
+    }
+}
+
+//zoom factor for one wheel line or key press
+const ZOOM_STEP: GfxCoord = 1.25;
+//how quickly the zoom closes in on its target, per second
+const ZOOM_RATE: Delta = 12.0;
+//how quickly panning reaches full speed and how quickly it coasts to a stop, per second
+const ACCELERATION: Delta = 10.0;
+const DECELERATION: Delta = 5.0;
+//slower than this in tiles per second counts as stopped
+const STOP_SPEED: Coord = 0.01;
+//the mouse scrolls the board within this fraction of the window edge
+const EDGE_MARGIN: Coord = 0.01;
+
 #[derive(Debug)]
 pub enum SendEvent {
     Resize,
@@ -50,11 +115,19 @@ pub struct System {
     move_h: Sign,
     move_v: Sign,
     move_speed_mult: Point2,
+    velocity: Point2,
+    drag: Option<Point2>,
+    focused: bool,
+    mouse_inside: bool,
     resize: Vec<(u32, u32)>,
     mouse_location: Point2,
-    mouse_button: Vec<(bool, MouseButton)>,
+    actions: Vec<(Action, bool)>,
+    zoom_lines: GfxCoord,
+    zoom_target: Option<GfxCoord>,
+    minimap_bounds: Option<(Point2I, Point2I)>,
     screen_resolution: Point2,
     ortho_helper: OrthographicHelper,
+    console: Sender<console::RecvEvent>,
     exited: bool,
 }
 
@@ -65,21 +138,37 @@ impl System {
         mouse_location: Point2,
         screen_resolution: Point2,
         ortho_helper: OrthographicHelper,
+        console: Sender<console::RecvEvent>,
     ) -> System {
         System {
             channel: channel,
             move_h: Sign::Zero,
             move_v: Sign::Zero,
             move_speed_mult: move_speed_mult,
+            velocity: Point2::zero(),
+            drag: None,
+            focused: true,
+            mouse_inside: false,
             resize: vec!(),
             mouse_location: mouse_location,
-            mouse_button: vec!(),
+            actions: vec!(),
+            zoom_lines: 0.0,
+            zoom_target: None,
+            minimap_bounds: None,
             screen_resolution: screen_resolution,
             ortho_helper: ortho_helper,
+            console: console,
             exited: false,
         }
     }
 
+    fn send_command(&mut self, command: String) {
+        match self.console.send(console::RecvEvent::Command(command)) {
+            Ok(()) => (),
+            Err(err) => error!("control console send error: {}", err),
+        }
+    }
+
     fn check_input(&mut self) {
         loop {
             match self.channel.1.try_recv() {
@@ -89,8 +178,22 @@ impl System {
                             x as Coord / self.screen_resolution.get_x(),
                             y as Coord / self.screen_resolution.get_y()
                         );
+                        let (x, y) = (self.mouse_location.get_x(), self.mouse_location.get_y());
+                        self.mouse_inside = x >= 0.0 && x <= 1.0 && y >= 0.0 && y <= 1.0;
+                    },
+                    RecvEvent::MouseInput(pressed, MouseButton::Left) => self.actions.push((Action::Select, pressed)),
+                    RecvEvent::MouseInput(pressed, MouseButton::Right) => self.actions.push((Action::Route, pressed)),
+                    RecvEvent::MouseInput(_, _) => (),
+                    RecvEvent::Action(Action::Pan, pressed) => {
+                        self.drag = if pressed {
+                            Some(self.mouse_location.clone())
+                        } else {
+                            None
+                        };
                     },
-                    RecvEvent::MouseInput(pressed, mouse_button) => self.mouse_button.push((pressed, mouse_button)),
+                    RecvEvent::Action(action, pressed) => self.actions.push((action, pressed)),
+                    RecvEvent::Zoom(lines) => self.zoom_lines += lines,
+                    RecvEvent::Focused(focused) => self.focused = focused,
                     RecvEvent::Right(pressed) => {
                         if pressed {
                             self.move_h = Sign::Pos;
@@ -159,66 +262,219 @@ impl specs::System<Delta> for System {
             return;
         }
 
-        let (transforms, mut cameras, mut clickables) = arg.fetch(|w|
+        let (entities, mut transforms, mut cameras, overlays, texts, map, mut selection) = arg.fetch(|w|
             (
-                w.read::<Transform>(),
+                w.entities(),
+                w.write::<Transform>(),
                 w.write::<Camera>(),
-                w.write::<Clickable>(),
+                w.read::<Overlay>(),
+                w.read::<Text>(),
+                w.read_resource::<Map>(),
+                w.write_resource::<Selection>(),
             )
         );
 
-        let mut camera_opt = None;
-
-        for mut c in (&mut cameras).iter() {
-            if c.is_main() {
-                match (self.move_h, self.move_v) {
-                    (Sign::Zero, Sign::Zero) => (),
-                    (h, v) => {
-                        let move_h = match h {
-                            Sign::Pos => 1.0,
-                            Sign::Zero => 0.0,
-                            Sign::Neg => -1.0,
-                        };
-                        let move_v = match v {
-                            Sign::Pos => 1.0,
-                            Sign::Zero => 0.0,
-                            Sign::Neg => -1.0,
-                        };
-                        let offset = c.get_offset();
-                        c.set_offset(Point2::new(
-                            move_h * delta_time * self.move_speed_mult.get_x() + offset.get_x(),
-                            move_v * delta_time * self.move_speed_mult.get_y() + offset.get_y()
-                        ));
-                    },
-                }
-                for &(width, height) in &self.resize {
-                    self.ortho_helper.set_aspect_ratio(width as GfxCoord / height as GfxCoord);
-                    c.set_proj(&self.ortho_helper);
-                    self.screen_resolution = Point2::new(width as Coord, height as Coord);
-                }
-                camera_opt = Some(c);
-                break;
+        let resize: Vec<(u32, u32)> = self.resize.drain(..).collect();
+        for &(width, height) in &resize {
+            self.ortho_helper.set_aspect_ratio(width as GfxCoord / height as GfxCoord);
+            self.screen_resolution = Point2::new(width as Coord, height as Coord);
+        }
+
+        let mut views: Vec<&mut Camera> = (&mut cameras).iter().collect();
+
+        if !resize.is_empty() {
+            for view in views.iter_mut() {
+                view.set_proj(&self.ortho_helper);
             }
+            self.minimap_bounds = None;
         }
 
-        let camera = match camera_opt {
-            Some(c) => c,
+        let main = match views.iter().position(|view| view.is_main()) {
+            Some(main) => main,
             None => panic!("run camera opt was none"),
         };
 
-        if let Some(input) = self.mouse_button.pop() {
-            match input {
-                (true, MouseButton::Left) => {
-                    for (t, mut c) in (&transforms, &mut clickables).iter() {
-                        if c.get_hitbox().check_collide_point(camera.screen_to_world_point(self.mouse_location.clone()) + t.get_gui_offset()) {
-                            *c.get_mut_clicked() = true;
-                        } else if c.get_clicked() {
-                            *c.get_mut_clicked() = false;
-                        }
-                    }
+        //dragging keeps the world point under the mouse where it is, and lets go with the drag's speed
+        if let Some(last) = self.drag.take() {
+            let moved = views[main].screen_to_world_point(last) - views[main].screen_to_world_point(self.mouse_location.clone());
+            let offset = views[main].get_offset();
+            views[main].set_offset(offset + moved.clone());
+            views[main].stop_move();
+            if delta_time > 0.0 {
+                self.velocity = moved / delta_time;
+            }
+            self.drag = Some(self.mouse_location.clone());
+        } else {
+            let sign = |sign: Sign| match sign {
+                Sign::Pos => 1.0,
+                Sign::Zero => 0.0,
+                Sign::Neg => -1.0,
+            };
+            let (mut move_h, mut move_v) = (sign(self.move_h), sign(self.move_v));
+
+            //screen y grows downwards, world y upwards, the mouse only counts while it was last seen inside the window
+            if self.focused && self.mouse_inside {
+                if self.mouse_location.get_x() < EDGE_MARGIN {
+                    move_h = -1.0;
+                } else if self.mouse_location.get_x() > 1.0 - EDGE_MARGIN {
+                    move_h = 1.0;
+                }
+                if self.mouse_location.get_y() < EDGE_MARGIN {
+                    move_v = 1.0;
+                } else if self.mouse_location.get_y() > 1.0 - EDGE_MARGIN {
+                    move_v = -1.0;
+                }
+            }
+
+            //speed is in tiles at no zoom so panning covers the same part of the screen at any zoom
+            let zoom = views[main].get_zoom() as Coord;
+            let wanted = Point2::new(
+                move_h * self.move_speed_mult.get_x() / zoom,
+                move_v * self.move_speed_mult.get_y() / zoom
+            );
+            let rate = if move_h == 0.0 && move_v == 0.0 {
+                DECELERATION
+            } else {
+                views[main].stop_move();
+                ACCELERATION
+            };
+            let blend = 1.0 - (-delta_time * rate).exp();
+            self.velocity = self.velocity.clone() + (wanted - self.velocity.clone()) * blend;
+
+            if self.velocity.length() < STOP_SPEED {
+                self.velocity = Point2::zero();
+            } else {
+                let offset = views[main].get_offset();
+                views[main].set_offset(offset + self.velocity.clone() * delta_time);
+            }
+        }
+
+        for view in views.iter_mut() {
+            view.step_move(delta_time);
+        }
+
+        //the minimap always frames the whole board, refitted whenever the board grows or shrinks
+        let bounds = map.get_bounds();
+        if bounds != self.minimap_bounds {
+            if let Some(rect) = console::make_board_rect(&map) {
+                for view in views.iter_mut().filter(|view| view.is_minimap()) {
+                    view.zoom_to_fit(&rect);
+                }
+            }
+            self.minimap_bounds = bounds;
+        }
+
+        //overlay elements are laid out in window pixels, text is scaled by its line height only
+        for (entity, overlay, transform) in (&entities, &overlays, &mut transforms).iter() {
+            let (pos, size) = overlay.get_screen_rect(&self.screen_resolution);
+            transform.set_position(pos);
+            if texts.get(entity).is_some() {
+                transform.set_scale(Point2::new(size.get_y(), size.get_y()));
+            } else {
+                transform.set_scale(size);
+            }
+        }
+
+        let actions: Vec<(Action, bool)> = self.actions.drain(..).collect();
+        for (action, pressed) in actions {
+            if !pressed {
+                continue;
+            }
+
+            //zooming, undo and redo need no cell so they work anywhere, even over the overlay
+            match action {
+                Action::ZoomIn => {
+                    self.zoom_lines += 1.0;
+                    continue;
+                },
+                Action::ZoomOut => {
+                    self.zoom_lines -= 1.0;
+                    continue;
+                },
+                //board edits go through the console so they share its checks and undo history
+                Action::Undo => {
+                    self.send_command("undo".to_string());
+                    continue;
+                },
+                Action::Redo => {
+                    self.send_command("redo".to_string());
+                    continue;
                 },
                 _ => (),
             }
+
+            //only clicks are taken by the overlay and the minimap, keyed edits reach the board cell under the cursor
+            let click = action == Action::Select || action == Action::Route;
+
+            if click {
+                //anything clicked on the overlay never reaches the world below it
+                let pixel = Point2::new(
+                    self.mouse_location.get_x() * self.screen_resolution.get_x(),
+                    (1.0 - self.mouse_location.get_y()) * self.screen_resolution.get_y()
+                );
+                if (&overlays).iter().any(|overlay| overlay.contains(&self.screen_resolution, &pixel)) {
+                    continue;
+                }
+
+                //the minimap moves the main camera instead of picking
+                if let Some(index) = views.iter().position(|view| view.is_minimap() && view.contains_screen_point(&self.mouse_location)) {
+                    if action == Action::Select {
+                        let world_point = views[index].screen_to_world_point(self.mouse_location.clone());
+                        let zoom = views[main].get_zoom();
+                        views[main].move_to(world_point, zoom);
+                        self.velocity = Point2::zero();
+                    }
+                    continue;
+                }
+            }
+
+            //the split view is drawn over the main camera so it takes the cell first
+            let under = views.iter().position(|view| !view.is_main() && !view.is_minimap() && view.contains_screen_point(&self.mouse_location));
+            let world_point = views[under.unwrap_or(main)].screen_to_world_point(self.mouse_location.clone());
+            let (x, y) = (world_point.get_x().floor() as CoordI, world_point.get_y().floor() as CoordI);
+
+            match action {
+                Action::Select => {
+                    //empty space has no entities so the grid cell is picked from the coordinates alone
+                    selection.set_cell(Some(Point2I::new(x, y)));
+                },
+                //routes on the console's placing layer like every other board edit, the first end waits on the selection for the hud
+                Action::Route => match selection.get_route_start().cloned() {
+                    Some(start) => {
+                        selection.set_route_start(None);
+                        self.send_command(format!("route {} {} {} {}", start.get_x(), start.get_y(), x, y));
+                    },
+                    None => selection.set_route_start(Some(Point2I::new(x, y))),
+                },
+                Action::Place => self.send_command(format!("place wire {} {}", x, y)),
+                Action::Erase => self.send_command(format!("delete {} {}", x, y)),
+                Action::Rotate => self.send_command(format!("rotate {} {}", x, y)),
+                Action::ZoomIn | Action::ZoomOut | Action::Pan | Action::Undo | Action::Redo => (),
+            }
+        }
+
+        //each step multiplies the target so scrolling quickly keeps adding up while the camera eases towards it
+        if self.zoom_lines != 0.0 {
+            views[main].stop_move();
+            let target = self.zoom_target.unwrap_or(views[main].get_zoom()) * ZOOM_STEP.powf(self.zoom_lines);
+            self.zoom_target = Some(target.max(MIN_ZOOM).min(MAX_ZOOM));
+            self.zoom_lines = 0.0;
+        }
+
+        //a move started since, from the minimap or the console, owns the zoom until it lands
+        if views[main].is_moving() {
+            self.zoom_target = None;
+        }
+
+        if let Some(target) = self.zoom_target {
+            let zoom = views[main].get_zoom();
+            let step = (delta_time * ZOOM_RATE).min(1.0) as GfxCoord;
+            let mut next = zoom * (target / zoom).powf(step);
+            if (next / target - 1.0).abs() < 0.001 {
+                next = target;
+                self.zoom_target = None;
+            }
+            views[main].zoom_at(next, self.mouse_location.clone());
         }
     }
 }
@@ -0,0 +1,121 @@
+use specs::{self, RunArg, Entity};
+
+use comps::{Text, Wire, WireIn, WireOut};
+use comps::non_components::{Console, Map, Selection};
+
+use math::{Point3I};
+
+use utils::Delta;
+use utils::fps_counter::{FpsCounter};
+
+use art;
+
+//keeps the screen text showing frame rate, the selected cell, the edit mode and the console up to date,
+//along with the labels on the board that show a wire's value
+pub struct System {
+    fps_counter: FpsCounter,
+    fps_text: Entity,
+    selection_text: Entity,
+    mode_text: Entity,
+    console_text: Entity,
+    value_labels: Vec<(Entity, Point3I, String)>,
+}
+
+impl System {
+    pub fn new(fps_text: Entity, selection_text: Entity, mode_text: Entity, console_text: Entity) -> System {
+        System {
+            fps_counter: FpsCounter::new(),
+            fps_text: fps_text,
+            selection_text: selection_text,
+            mode_text: mode_text,
+            console_text: console_text,
+            value_labels: vec!(),
+        }
+    }
+
+    //label's text becomes name followed by the value of whatever wire sits at location
+    pub fn add_value_label(&mut self, label: Entity, location: Point3I, name: &str) {
+        self.value_labels.push((label, location, name.to_string()));
+    }
+}
+
+//the layer edits go on and the first end of a route waiting for its second
+pub fn make_mode_text(selection: &Selection) -> String {
+    match selection.get_route_start() {
+        Some(start) => format!("layer {}, routing from {}, {}", selection.get_layer(), start.get_x(), start.get_y()),
+        None => format!("layer {}", selection.get_layer()),
+    }
+}
+
+//a wire with no value yet, or nothing there at all, shows a dash
+pub fn make_value_text(name: &str, value: Option<u8>) -> String {
+    match value {
+        Some(value) => format!("{} {}", name, value),
+        None => format!("{} -", name),
+    }
+}
+
+//the newest output lines followed by the input line, nothing while the console is closed
+pub fn make_console_text(console: &Console, lines: usize) -> String {
+    if !console.get_open() {
+        return String::new();
+    }
+
+    let output = console.get_lines();
+    let mut text = String::new();
+    for line in output.iter().skip(output.len().saturating_sub(lines)) {
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.push_str("> ");
+    text.push_str(console.get_input());
+    text
+}
+
+impl specs::System<Delta> for System {
+    fn run(&mut self, arg: RunArg, delta: Delta) {
+        self.fps_counter.frame(delta);
+
+        let (mut texts, selection, console, map, wires, wire_ins, wire_outs) = arg.fetch(|w|
+            (
+                w.write::<Text>(),
+                w.read_resource::<Selection>(),
+                w.read_resource::<Console>(),
+                w.read_resource::<Map>(),
+                w.read::<Wire>(),
+                w.read::<WireIn>(),
+                w.read::<WireOut>()
+            )
+        );
+
+        if let Some(text) = texts.get_mut(self.fps_text) {
+            text.set_text(&format!("FPS {}", self.fps_counter.get_fps()));
+        }
+
+        if let Some(text) = texts.get_mut(self.selection_text) {
+            match selection.get_cell() {
+                Some(cell) => text.set_text(&format!("{}, {}", cell.get_x(), cell.get_y())),
+                None => text.set_text(""),
+            }
+        }
+
+        if let Some(text) = texts.get_mut(self.mode_text) {
+            text.set_text(&make_mode_text(&selection));
+        }
+
+        if let Some(text) = texts.get_mut(self.console_text) {
+            text.set_text(&make_console_text(&console, art::text::CONSOLE_LINES));
+        }
+
+        for &(label, ref location, ref name) in &self.value_labels {
+            let value = map.get_map().get(location).and_then(|&entity|
+                wires.get(entity).map(|wire| wire.get_value())
+                    .or_else(|| wire_ins.get(entity).map(|wire_in| wire_in.get_value()))
+                    .or_else(|| wire_outs.get(entity).map(|wire_out| wire_out.get_value()))
+            );
+            if let Some(text) = texts.get_mut(label) {
+                text.set_text(&make_value_text(name, value));
+            }
+        }
+    }
+}
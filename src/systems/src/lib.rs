@@ -13,14 +13,23 @@ extern crate utils;
 extern crate components as comps;
 extern crate math;
 extern crate art;
+extern crate netlist;
 
+pub mod animator;
+pub mod console;
 pub mod control;
+pub mod hud;
 pub mod render;
 pub mod link_connector;
 pub mod mapper;
+pub mod router;
+pub mod spawn;
 pub mod wire_flow;
 
+pub use self::animator::System as Animator;
+pub use self::console::System as Console;
 pub use self::control::System as Control;
+pub use self::hud::System as Hud;
 pub use self::render::System as Render;
 pub use self::link_connector::System as LinkConnector;
 pub use self::mapper::System as Mapper;
@@ -31,37 +31,39 @@ impl specs::System<Delta> for System {
         );
 
         for mut wire in (&mut wires).iter() {
-            if wire.get_output().get_fast().is_none() {
+            if wire.get_output().get_fast().is_none() && !wire.get_output().is_floating() {
                 let location = wire.get_output().get_slow().clone();
                 *wire.get_mut_output().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
             }
-            if wire.get_input().get_fast().is_none() {
+            if wire.get_input().get_fast().is_none() && !wire.get_input().is_floating() {
                 let location = wire.get_input().get_slow().clone();
                 *wire.get_mut_input().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
             }
-            if wire.get_me().get_fast().is_none() {
+            if wire.get_me().get_fast().is_none() && !wire.get_me().is_floating() {
                 let location = wire.get_me().get_slow().clone();
                 *wire.get_mut_me().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
             }
         }
 
         for mut wire_in in (&mut wires_in).iter() {
-            if wire_in.get_output().get_fast().is_none() {
-                let location = wire_in.get_output().get_slow().clone();
-                *wire_in.get_mut_output().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
+            for output in wire_in.get_mut_outputs().iter_mut() {
+                if output.get_fast().is_none() && !output.is_floating() {
+                    let location = output.get_slow().clone();
+                    *output.get_mut_fast() = map.get_map().get(&location).map(|e| *e);
+                }
             }
-            if wire_in.get_me().get_fast().is_none() {
+            if wire_in.get_me().get_fast().is_none() && !wire_in.get_me().is_floating() {
                 let location = wire_in.get_me().get_slow().clone();
                 *wire_in.get_mut_me().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
             }
         }
 
         for mut wire_out in (&mut wires_out).iter() {
-            if wire_out.get_input().get_fast().is_none() {
+            if wire_out.get_input().get_fast().is_none() && !wire_out.get_input().is_floating() {
                 let location = wire_out.get_input().get_slow().clone();
                 *wire_out.get_mut_input().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
             }
-            if wire_out.get_me().get_fast().is_none() {
+            if wire_out.get_me().get_fast().is_none() && !wire_out.get_me().is_floating() {
                 let location = wire_out.get_me().get_slow().clone();
                 *wire_out.get_mut_me().get_mut_fast() = map.get_map().get(&location).map(|e| *e);
             }
@@ -1,8 +1,9 @@
-use std::sync::mpsc::{Sender, Receiver};
+use std::sync::mpsc::{Sender, Receiver, TryRecvError};
 use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
 
 use gfx::traits::{Factory, FactoryExt};
-use gfx::{Encoder, Primitive};
+use gfx::{Encoder, PipelineState, BufferRole, Bind};
 use gfx::handle::{ShaderResourceView, RenderTargetView, DepthStencilView};
 use gfx::state::{Rasterizer};
 use gfx::tex::{SamplerInfo, FilterMethod, WrapMode};
@@ -10,16 +11,25 @@ use gfx::tex::{SamplerInfo, FilterMethod, WrapMode};
 use gfx_device_gl::{Resources, CommandBuffer};
 use gfx_device_gl::Factory as GLFactory;
 
-use specs::RunArg;
+use specs::{RunArg, Entity, World};
 use specs;
 
 use graphics::{ColorFormat, DepthFormat, ProjectionData};
-use graphics::spritesheet::{Vertex, Index, make_shaders, Bundle, Packet, TextureData, pipe};
+use graphics::spritesheet::{Vertex, Index, Instance, MAX_INSTANCES, make_shaders, make_pipeline, VERTEX_SHADER, FRAGMENT_SHADER, Bundle, Packet, pipe};
+use graphics::grid;
+use graphics::backend::{Backend, Gpu};
+use graphics::software::{self, Canvas};
+use graphics::text::{Font, glyph_model};
 use graphics::Shaders;
 
-use utils::Delta;
+use math::{Point2I, Rect};
 
-use comps::{RenderId, Transform, Camera, RenderData};
+use utils::{Delta, CoordI};
+
+use comps::{RenderId, Transform, Camera, RenderData, Text, Overlay};
+use comps::non_components::{Map, Selection};
+
+use art;
 
 pub type Channel = (
     Sender<SendEvent>,
@@ -34,15 +44,156 @@ pub enum SendEvent {
 pub enum RecvEvent {
     Encoder(Encoder<Resources, CommandBuffer>),
     GraphicsData(RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>),
+    //rebuilt on the main thread since only it has the factory
+    ReloadTexture(RenderId, ShaderResourceView<Resources, [f32; 4]>, Canvas),
+    ReloadSpritesheetPipeline(RenderId, PipelineState<Resources, pipe::Meta>),
+    ReloadGridPipeline(PipelineState<Resources, grid::pipe::Meta>),
+    //a camera target remade at a new size, from make_camera_target on the main thread
+    ResizeCameraTarget(RenderId, (u16, u16), ShaderResourceView<Resources, [f32; 4]>, RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>),
+    //draws the next frame on the cpu as well and sends back the window's image
+    RenderSoftware(Sender<Canvas>),
+    //draws the world inside the rect on the cpu into an image of the given size, without the overlay,
+    //one tile a frame so a big image never stalls the game, and sends it back once every tile is in
+    RenderRegion(Rect, (u32, u32), Sender<Canvas>),
     Exit,
 }
 
-pub struct System {
-    channel: Channel,
+//text is drawn through a spritesheet bundle holding the font texture
+struct TextRender {
+    font: Font,
+    render_id: RenderId,
+}
+
+//a camera drawn into its own texture, which a spritesheet bundle then shows in the camera's viewport
+struct CameraTarget {
+    render_id: RenderId,
+    size: (u16, u16),
+}
+
+//an image being drawn a tile at a time, left to right then top to bottom from the top left
+struct RegionRequest {
+    rect: Rect,
+    image: Canvas,
+    next: (u32, u32),
+    sender: Sender<Canvas>,
+}
+
+//a world sprite gathered once per frame and drawn by every camera that can see it,
+//bounds of none are never culled and board sprites are culled by their cell instead
+struct Sprite {
+    render_id: usize,
+    layer: u8,
+    bounds: Option<(f64, f64, f64, f64)>,
+    instance: Instance,
+}
+
+//what a visible camera draws with, copied out so the frame can be drawn by more than one backend
+struct View {
+    is_main: bool,
+    is_minimap: bool,
+    render_target: Option<RenderId>,
+    viewport: Rect,
+    view_rect: Rect,
+    projection_data: ProjectionData,
+    inv_view_proj: [[f32; 4]; 4],
+}
+
+impl View {
+    fn new(camera: &Camera) -> View {
+        View {
+            is_main: camera.is_main(),
+            is_minimap: camera.is_minimap(),
+            render_target: camera.get_render_target(),
+            viewport: camera.get_viewport().clone(),
+            view_rect: camera.get_view_rect(),
+            projection_data: ProjectionData {
+                view: camera.get_view(),
+                proj: camera.get_proj(),
+            },
+            inv_view_proj: camera.get_inverse_view_proj(),
+        }
+    }
+
+    //looks straight at the rect so it exactly fills the output
+    fn new_region(rect: Rect) -> View {
+        let (min, max) = (rect.get_bot_left(), rect.get_top_right());
+        let (x0, y0, x1, y1) = (min.get_x() as f32, min.get_y() as f32, max.get_x() as f32, max.get_y() as f32);
+        let (width, height) = (x1 - x0, y1 - y0);
+
+        View {
+            is_main: false,
+            is_minimap: false,
+            render_target: None,
+            viewport: Rect::new_from_coords(0.0, 0.0, 1.0, 1.0),
+            view_rect: rect,
+            projection_data: ProjectionData {
+                view: [
+                    [1.0, 0.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                    [0.0, 0.0, 0.0, 1.0],
+                ],
+                proj: [
+                    [2.0 / width, 0.0, 0.0, 0.0],
+                    [0.0, 2.0 / height, 0.0, 0.0],
+                    [0.0, 0.0, -1.0, 0.0],
+                    [-(x0 + x1) / width, -(y0 + y1) / height, 0.0, 1.0],
+                ],
+            },
+            inv_view_proj: [
+                [width / 2.0, 0.0, 0.0, 0.0],
+                [0.0, height / 2.0, 0.0, 0.0],
+                [0.0, 0.0, -1.0, 0.0],
+                [(x0 + x1) / 2.0, (y0 + y1) / 2.0, 0.0, 1.0],
+            ],
+        }
+    }
+}
+
+struct Frame {
+    views: Vec<View>,
+    main_view: Rect,
+    selected: [f32; 4],
+    sprites: Vec<Sprite>,
+    //every layer of a board cell together, filed from the map so views only look up the cells they can see
+    board: HashMap<(CoordI, CoordI), Vec<Sprite>>,
+    overlay_instances: Vec<Vec<(u8, Instance)>>,
+}
+
+//the window's targets and the gfx side of every bundle, only made when there is a gl context to draw with
+struct Gl {
     out_color: RenderTargetView<Resources, ColorFormat>,
     out_depth: DepthStencilView<Resources, DepthFormat>,
     bundles: Arc<Vec<Bundle>>,
     shaders: Shaders,
+    grid: Option<grid::Bundle>,
+    //the targets of every camera target's bundle
+    targets: Vec<(RenderId, RenderTargetView<Resources, ColorFormat>, DepthStencilView<Resources, DepthFormat>)>,
+}
+
+impl Gl {
+    fn get_mut_bundle(&mut self, render_id: RenderId) -> Option<&mut Bundle> {
+        match Arc::get_mut(&mut self.bundles) {
+            Some(bundles) => bundles.get_mut(render_id.id),
+            None => panic!("get mut bundle get mut bundles was none"),
+        }
+    }
+}
+
+pub struct System {
+    channel: Channel,
+    //none for a system that only ever draws on the cpu
+    gl: Option<Gl>,
+    //none until a grid is added
+    grid_bounds: Option<[f32; 4]>,
+    text: Option<TextRender>,
+    camera_targets: Vec<CameraTarget>,
+    //mirrors every bundle so a frame can also be drawn on the cpu
+    software: software::Renderer,
+    //the window size without gl, the software window is resized for every region tile
+    software_size: (u32, u32),
+    software_requests: Vec<Sender<Canvas>>,
+    region_requests: Vec<RegionRequest>,
     exited: bool,
 }
 
@@ -59,22 +210,161 @@ impl System {
             Err(err) => panic!("new channel 1 rect error: {}", err),
         } ;
 
-        System {
-            channel: channel,
+        let mut system = System::new_software(channel, 1, 1);
+        system.gl = Some(Gl {
             out_color: out_color,
             out_depth: out_depth,
             bundles: Arc::new(Vec::new()),
             shaders: make_shaders(),
+            grid: None,
+            targets: vec!(),
+        });
+        system
+    }
+
+    //draws only with the software renderer into a window of the given size, so no gl context or encoder is needed,
+    //everything is added through the add software functions and images are sent back from the next run that has a request
+    pub fn new_software(
+        channel: Channel,
+        width: u32,
+        height: u32
+    ) -> System
+    {
+        System {
+            channel: channel,
+            gl: None,
+            grid_bounds: None,
+            text: None,
+            camera_targets: vec!(),
+            software: software::Renderer::new(width, height),
+            software_size: (width, height),
+            software_requests: vec!(),
+            region_requests: vec!(),
             exited: false,
         }
     }
 
+    fn get_mut_gl(&mut self) -> &mut Gl {
+        match self.gl {
+            Some(ref mut gl) => gl,
+            None => panic!("render system was made without gl"),
+        }
+    }
+
+    //draws the empty board behind everything, bounds are min inclusive and max exclusive cells
+    pub fn add_render_grid(&mut self, factory: &mut GLFactory, bounds: Option<(Point2I, Point2I)>) {
+        {
+            let gl = self.get_mut_gl();
+            let shaders = grid::make_shaders();
+
+            let pso = match grid::make_pipeline(factory, &shaders) {
+                Ok(pso) => pso,
+                Err(err) => {
+                    error!("add render grid error: {}", err);
+                    let embedded = match Shaders::embedded(grid::VERTEX_SHADER, grid::FRAGMENT_SHADER) {
+                        Some(embedded) => embedded,
+                        None => panic!("add render grid has no embedded shaders to fall back on"),
+                    };
+                    match grid::make_pipeline(factory, &embedded) {
+                        Ok(pso) => pso,
+                        Err(err) => panic!("add render grid embedded shaders error: {}", err),
+                    }
+                },
+            };
+
+            let (vertices, indices) = grid::make_screen_quad();
+            let (vbuf, slice) = factory.create_vertex_buffer_with_slice(vertices.as_slice(), indices.as_slice());
+            let data = grid::pipe::Data {
+                vbuf: vbuf,
+                grid_cb: factory.create_constant_buffer(1),
+                out_color: gl.out_color.clone(),
+            };
+
+            gl.grid = Some(grid::Bundle::new(slice, pso, data));
+        }
+
+        self.add_software_grid(bounds);
+    }
+
+    pub fn add_software_grid(&mut self, bounds: Option<(Point2I, Point2I)>) {
+        self.grid_bounds = Some(match bounds {
+            Some((min, max)) => [min.get_x() as f32, min.get_y() as f32, max.get_x() as f32, max.get_y() as f32],
+            None => [1.0, 1.0, 0.0, 0.0],
+        });
+    }
+
+    //canvas is the texture's pixels for the software renderer
     pub fn add_render_spritesheet(&mut self,
         factory: &mut GLFactory,
         packet: &Packet,
-        texture: ShaderResourceView<Resources, [f32; 4]>
+        texture: ShaderResourceView<Resources, [f32; 4]>,
+        canvas: Canvas
+    ) -> RenderId {
+        let render_id = self.add_render_spritesheet_raw(factory, packet.get_vertices(), packet.get_indices(), packet.get_rasterizer(), texture);
+        let software_id = self.software.add_spritesheet(packet, canvas);
+        if software_id != render_id.id {
+            panic!("add render spritesheet software id {} does not match render id {}", software_id, render_id.id);
+        }
+        render_id
+    }
+
+    //a system with gl would lose track of which gfx bundle goes with the id, so only one made with new software can
+    pub fn add_software_spritesheet(&mut self, packet: &Packet, canvas: Canvas) -> RenderId {
+        if self.gl.is_some() {
+            panic!("add software spritesheet on a render system with gl");
+        }
+        RenderId {
+            id: self.software.add_spritesheet(packet, canvas),
+        }
+    }
+
+    pub fn add_render_text(&mut self,
+        factory: &mut GLFactory,
+        packet: &Packet,
+        texture: ShaderResourceView<Resources, [f32; 4]>,
+        canvas: Canvas,
+        font: Font
+    ) {
+        let render_id = self.add_render_spritesheet(factory, packet, texture, canvas);
+        self.text = Some(TextRender {
+            font: font,
+            render_id: render_id,
+        });
+    }
+
+    pub fn add_software_text(&mut self, packet: &Packet, canvas: Canvas, font: Font) {
+        let render_id = self.add_software_spritesheet(packet, canvas);
+        self.text = Some(TextRender {
+            font: font,
+            render_id: render_id,
+        });
+    }
+
+    //an offscreen target for a camera with its own viewport, the returned id goes on the camera
+    pub fn add_camera_target(&mut self,
+        factory: &mut GLFactory,
+        packet: &Packet,
+        size: (u16, u16)
     ) -> RenderId {
-        self.add_render_spritesheet_raw(factory, packet.get_vertices(), packet.get_indices(), packet.get_rasterizer(), texture)
+        let (texture, out_color, out_depth) = make_camera_target(factory, size);
+
+        let canvas = Canvas::new(size.0 as u32, size.1 as u32);
+        let render_id = self.add_render_spritesheet(factory, packet, texture, canvas);
+        self.get_mut_gl().targets.push((render_id, out_color, out_depth));
+        self.camera_targets.push(CameraTarget {
+            render_id: render_id,
+            size: size,
+        });
+        render_id
+    }
+
+    pub fn add_software_camera_target(&mut self, packet: &Packet, size: (u16, u16)) -> RenderId {
+        let render_id = self.add_software_spritesheet(packet, Canvas::new(size.0 as u32, size.1 as u32));
+        self.camera_targets.push(CameraTarget {
+            render_id: render_id,
+            size: size,
+        });
+        render_id
     }
 
     fn add_render_spritesheet_raw(&mut self,
@@ -84,24 +374,25 @@ impl System {
         rasterizer: Rasterizer,
         spritesheet: ShaderResourceView<Resources, [f32; 4]>
     ) -> RenderId {
-        let shader_set = match factory.create_shader_set(self.shaders.get_vertex_shader(), self.shaders.get_fragment_shader()) {
-            Ok(shaders) => shaders,
-            Err(err) => panic!("add render type spritesheet raw create shader set error: {}", err),
-        };
-
-        let program  = match factory.create_program(&shader_set) {
-            Ok(program) => program,
-            Err(err) => panic!("add render type spritesheet raw create program error: {}", err),
-        };
+        let gl = self.get_mut_gl();
 
-        let pso = match factory.create_pipeline_from_program(
-            &program,
-            Primitive::TriangleList,
-            rasterizer,
-            pipe::new()
-        ) {
+        let pso = match make_pipeline(factory, &gl.shaders, rasterizer) {
             Ok(pso) => pso,
-            Err(err) => panic!("add render type spritesheet raw create pipeline error: {}", err),
+            Err(err) => {
+                //a broken override should not stop the game, later bundles go straight to the embedded shaders
+                error!("add render type spritesheet raw error: {}", err);
+                if gl.shaders.is_embedded() {
+                    panic!("add render type spritesheet raw embedded shaders failed");
+                }
+                gl.shaders = match Shaders::embedded(VERTEX_SHADER, FRAGMENT_SHADER) {
+                    Some(embedded) => embedded,
+                    None => panic!("add render type spritesheet raw has no embedded shaders to fall back on"),
+                };
+                match make_pipeline(factory, &gl.shaders, rasterizer) {
+                    Ok(pso) => pso,
+                    Err(err) => panic!("add render type spritesheet raw embedded shaders error: {}", err),
+                }
+            },
         };
 
         let sampler_info = SamplerInfo::new(
@@ -109,120 +400,285 @@ impl System {
             WrapMode::Mirror
         );
 
+        let instances = match factory.create_buffer_dynamic(MAX_INSTANCES, BufferRole::Vertex, Bind::empty()) {
+            Ok(instances) => instances,
+            Err(err) => panic!("add render type spritesheet raw create instance buffer error: {:?}", err),
+        };
+
         let (vbuf, slice) = factory.create_vertex_buffer_with_slice(vertices, indices);
         let data = pipe::Data {
             vbuf: vbuf,
+            instances: instances,
             spritesheet: (spritesheet, factory.create_sampler(sampler_info)),
-            texture_data: factory.create_constant_buffer(1),
             projection_cb: factory.create_constant_buffer(1),
-            out_color: self.out_color.clone(),
-            out_depth: self.out_depth.clone(),
+            out_color: gl.out_color.clone(),
+            out_depth: gl.out_depth.clone(),
         };
 
-        let id = self.bundles.len();
-        let mut bundles = match Arc::get_mut(&mut self.bundles) {
+        let id = gl.bundles.len();
+        let mut bundles = match Arc::get_mut(&mut gl.bundles) {
             Some(bundles) => bundles,
             None => panic!("add render type spritesheet raw get mut bundles was none"),
         };
-        bundles.push(Bundle::new(slice, pso, data));
+        bundles.push(Bundle::new(slice, pso, data, MAX_INSTANCES));
         RenderId {
             id: id,
         }
     }
 
-    fn render(&mut self, arg: &RunArg, mut encoder: Encoder<Resources, CommandBuffer>) {
-        use specs::Join;
+    fn get_target_sizes(&self) -> Vec<(RenderId, (u16, u16))> {
+        self.camera_targets.iter().map(|target| (target.render_id, target.size)).collect()
+    }
 
-        let (draw, transform, mut camera, mut render_data) = arg.fetch(|w|
-            (
-                w.read::<RenderId>(),
-                w.read::<Transform>(),
-                w.write::<Camera>(),
-                w.write::<RenderData>()
-            )
-        );
+    fn render(&mut self, arg: &RunArg, mut encoder: Encoder<Resources, CommandBuffer>) {
+        let frame = arg.fetch(|w| self.gather(w));
+        let grid_bounds = self.grid_bounds;
+        let target_sizes = self.get_target_sizes();
+
+        {
+            let gl = self.get_mut_gl();
+            let targets = gl.targets.iter().map(|&(render_id, ref out_color, ref out_depth)| (render_id.id, out_color.clone(), out_depth.clone())).collect();
+            let bundles = match Arc::get_mut(&mut gl.bundles) {
+                Some(bundles) => bundles,
+                None => panic!("render get mut bundles was none"),
+            };
+            let mut gpu = Gpu::new(&mut encoder, bundles, gl.grid.as_mut(), (gl.out_color.clone(), gl.out_depth.clone()), targets);
+            draw_frame(&mut gpu, &frame, grid_bounds, &target_sizes);
+        }
 
-        encoder.clear(&self.out_color, [1.0, 1.0, 1.0, 1.0]);
-        encoder.clear_depth(&self.out_depth, 1.0);
+        self.send_images(&frame);
 
-        let (view, proj, dirty_cam) = {
-            let mut camera = {
-                let mut camera_opt = None;
+        match self.channel.0.send(SendEvent::Encoder(encoder)) {
+            Ok(()) => (),
+            Err(err) => panic!("render channel 0 send error: {}", err),
+        }
+    }
 
-                for c in (&mut camera).iter() {
-                    camera_opt = Some(c);
+    //the same frame again on the cpu for anyone who asked for an image of it, at the window's size when there is one
+    fn send_images(&mut self, frame: &Frame) {
+        let grid_bounds = self.grid_bounds;
+
+        if !self.software_requests.is_empty() {
+            let (width, height) = match self.gl {
+                Some(ref gl) => {
+                    let (width, height, _, _) = gl.out_color.get_dimensions();
+                    (width as u32, height as u32)
+                },
+                None => self.software_size,
+            };
+            self.software.set_window_size(width, height);
+            let target_sizes = self.get_target_sizes();
+            draw_frame(&mut self.software, frame, grid_bounds, &target_sizes);
+
+            let image = self.software.to_image();
+            for sender in self.software_requests.drain(..) {
+                if let Err(err) = sender.send(image.clone()) {
+                    warn!("render software image send error: {}", err);
                 }
+            }
+        }
 
-                match camera_opt {
-                    Some(camera) => camera,
-                    None => panic!("render camera opt was none"),
-                }
-            };
+        //only the oldest region moves on, by one tile
+        let finished = match self.region_requests.first_mut() {
+            Some(request) => draw_region_tile(&mut self.software, frame, grid_bounds, request),
+            None => false,
+        };
+        if finished {
+            let request = self.region_requests.remove(0);
+            if let Err(err) = request.sender.send(request.image) {
+                warn!("render region image send error: {}", err);
+            }
+        }
+    }
+
+    //without gl no encoder paces the frames, so only what has already been sent is handled and images are drawn straight away
+    fn render_software(&mut self, arg: &RunArg) {
+        if self.software_requests.is_empty() && self.region_requests.is_empty() {
+            arg.fetch(|_| ());
+            return;
+        }
+
+        let frame = arg.fetch(|w| self.gather(w));
+        self.send_images(&frame);
+    }
+
+    //everything a backend needs to draw the frame, read from the world once
+    fn gather(&self, w: &World) -> Frame {
+        use specs::Join;
+
+        let (entities, draw, transform, camera, render_data, texts, overlays, selection, map) = (
+            w.entities(),
+            w.read::<RenderId>(),
+            w.read::<Transform>(),
+            w.read::<Camera>(),
+            w.read::<RenderData>(),
+            w.read::<Text>(),
+            w.read::<Overlay>(),
+            w.read_resource::<Selection>(),
+            w.read_resource::<Map>()
+        );
 
-            (camera.get_view(), camera.get_proj(), camera.take_dirty())
+        let views: Vec<View> = (&camera).iter().filter(|camera| camera.is_visible()).map(View::new).collect();
+
+        let main_view = match views.iter().find(|view| view.is_main) {
+            Some(view) => view.view_rect.clone(),
+            None => panic!("render camera opt was none"),
         };
 
-        let mut data = vec!();
+        let selected = match selection.get_cell() {
+            Some(cell) => [cell.get_x() as f32, cell.get_y() as f32, 1.0, 0.0],
+            None => [0.0, 0.0, 0.0, 0.0],
+        };
 
-        for (d, t, mut rd) in (&draw, &transform, &mut render_data).iter() {
-            let mut projection_data = None;
+        let make_instance = |t: &Transform, rd: &RenderData| Instance::new(
+            t.get_model(),
+            rd.get_tint(),
+            rd.get_spritesheet_rect(),
+            rd.get_spritesheet_size(),
+            rd.get_mirror_x(),
+            rd.get_mirror_y()
+        );
 
-            if dirty_cam {
-                projection_data = Some(ProjectionData {
-                    model: t.get_model(),
-                    view: view,
-                    proj: proj,
+        let mut board: HashMap<(CoordI, CoordI), Vec<Sprite>> = HashMap::new();
+        let mut on_board: HashSet<Entity> = HashSet::new();
+        for (location, &entity) in map.get_map() {
+            if overlays.get(entity).is_some() {
+                continue;
+            }
+            if let (Some(d), Some(t), Some(rd)) = (draw.get(entity), transform.get(entity), render_data.get(entity)) {
+                board.entry((location.get_x(), location.get_y())).or_insert(vec!()).push(Sprite {
+                    render_id: d.id,
+                    layer: rd.get_layer(),
+                    bounds: None,
+                    instance: make_instance(t, rd),
                 });
+                on_board.insert(entity);
             }
+        }
+
+        let mut sprites = vec!();
+        //overlay instances under their bundle with their layer
+        let mut overlay_instances: Vec<Vec<(u8, Instance)>> = (0..self.software.get_bundle_count()).map(|_| vec!()).collect();
 
-            let mut texture_data = None;
+        for (entity, d, t, rd) in (&entities, &draw, &transform, &render_data).iter() {
+            if on_board.contains(&entity) {
+                continue;
+            }
 
-            if rd.take_dirty() {
-                texture_data = Some(TextureData {
-                    tint: rd.get_tint(),
-                    spritesheet_rect: rd.get_spritesheet_rect(),
-                    spritesheet_size: rd.get_spritesheet_size(),
-                    mirror_x: rd.get_mirror_x(),
-                    mirror_y: rd.get_mirror_y(),
+            let instance = make_instance(t, rd);
+
+            if overlays.get(entity).is_some() {
+                overlay_instances[d.id].push((rd.get_layer(), instance));
+            } else {
+                //sprites span from their position to position plus scale
+                let (pos, scale) = (t.get_pos(), t.get_scale());
+                sprites.push(Sprite {
+                    render_id: d.id,
+                    layer: rd.get_layer(),
+                    bounds: Some((
+                        pos.get_x().min(pos.get_x() + scale.get_x()),
+                        pos.get_y().min(pos.get_y() + scale.get_y()),
+                        pos.get_x().max(pos.get_x() + scale.get_x()),
+                        pos.get_y().max(pos.get_y() + scale.get_y())
+                    )),
+                    instance: instance,
                 });
             }
+        }
 
-            data.push((d.id, rd.get_layer(), texture_data, projection_data));
+        if let Some(ref text_render) = self.text {
+            let texture_size = text_render.font.get_texture_size();
+            for (entity, t, text) in (&entities, &transform, &texts).iter() {
+                let overlay = overlays.get(entity).is_some();
+                let model = t.get_model();
+                //the square packet mirrors u so glyphs are mirrored back to read left to right
+                for glyph in text_render.font.layout(text.get_text()) {
+                    let instance = Instance::new(
+                        glyph_model(model, &glyph),
+                        text.get_color(),
+                        glyph.rect,
+                        texture_size,
+                        true,
+                        false
+                    );
+                    if overlay {
+                        overlay_instances[text_render.render_id.id].push((text.get_layer(), instance));
+                    } else {
+                        sprites.push(Sprite {
+                            render_id: text_render.render_id.id,
+                            layer: text.get_layer(),
+                            bounds: None,
+                            instance: instance,
+                        });
+                    }
+                }
+            }
         }
 
-        data.sort_by_key(|k| k.1);
+        Frame {
+            views: views,
+            main_view: main_view,
+            selected: selected,
+            sprites: sprites,
+            board: board,
+            overlay_instances: overlay_instances,
+        }
+    }
 
-        for data in data {
-            let b = &self.bundles[data.0];
+    //the next frame's backend points the bundles at the new window targets
+    fn set_graphics_data(&mut self, out_color: RenderTargetView<Resources, ColorFormat>, out_depth: DepthStencilView<Resources, DepthFormat>) {
+        match self.gl {
+            Some(ref mut gl) => {
+                gl.out_color = out_color;
+                gl.out_depth = out_depth;
+            },
+            None => warn!("set graphics data on a render system without gl"),
+        }
+    }
 
-            if let Some(texture_data) = data.2 {
-                encoder.update_constant_buffer(&b.data.texture_data, &texture_data);
+    fn reload_texture(&mut self, render_id: RenderId, texture: ShaderResourceView<Resources, [f32; 4]>, canvas: Canvas) {
+        if let Some(ref mut gl) = self.gl {
+            match gl.get_mut_bundle(render_id) {
+                Some(bundle) => bundle.data.spritesheet.0 = texture,
+                None => warn!("reload texture render id {} has no bundle", render_id.id),
             }
+        }
+        self.software.set_texture(render_id.id, canvas);
+    }
 
-            if let Some(projection_data) = data.3 {
-                encoder.update_constant_buffer(&b.data.projection_cb, &projection_data);
+    fn resize_camera_target(&mut self,
+        render_id: RenderId,
+        size: (u16, u16),
+        texture: ShaderResourceView<Resources, [f32; 4]>,
+        out_color: RenderTargetView<Resources, ColorFormat>,
+        out_depth: DepthStencilView<Resources, DepthFormat>
+    ) {
+        match self.camera_targets.iter_mut().find(|target| target.render_id == render_id) {
+            Some(target) => target.size = size,
+            None => {
+                warn!("resize camera target render id {} has no target", render_id.id);
+                return;
+            },
+        }
+        if let Some(ref mut gl) = self.gl {
+            if let Some(target) = gl.targets.iter_mut().find(|target| target.0 == render_id) {
+                target.1 = out_color;
+                target.2 = out_depth;
             }
-
-            b.encode(&mut encoder);
         }
+        self.reload_texture(render_id, texture, Canvas::new(size.0 as u32, size.1 as u32));
+    }
 
-
-        match self.channel.0.send(SendEvent::Encoder(encoder)) {
-            Ok(()) => (),
-            Err(err) => panic!("render channel 0 send error: {}", err),
+    fn reload_spritesheet_pipeline(&mut self, render_id: RenderId, pso: PipelineState<Resources, pipe::Meta>) {
+        match self.gl.as_mut().and_then(|gl| gl.get_mut_bundle(render_id)) {
+            Some(bundle) => bundle.set_pso(pso),
+            None => warn!("reload spritesheet pipeline render id {} has no bundle", render_id.id),
         }
     }
 
-    fn set_graphics_data(&mut self, out_color: RenderTargetView<Resources, ColorFormat>, out_depth: DepthStencilView<Resources, DepthFormat>) {
-        self.out_color = out_color;
-        self.out_depth = out_depth;
-
-        for bundle in match Arc::get_mut(&mut self.bundles) {
-            Some(bundle) => bundle,
-            None => panic!("set graphics data get mut texture bundles was none"),
-        }  {
-            bundle.data.out_color = self.out_color.clone();
-            bundle.data.out_depth = self.out_depth.clone();
+    fn reload_grid_pipeline(&mut self, pso: PipelineState<Resources, grid::pipe::Meta>) {
+        if let Some(grid) = self.gl.as_mut().and_then(|gl| gl.grid.as_mut()) {
+            grid.set_pso(pso);
         }
     }
 
@@ -242,6 +698,35 @@ impl System {
                 self.set_graphics_data(out_color, out_depth);
                 true
             },
+            RecvEvent::ReloadTexture(render_id, texture, canvas) => {
+                self.reload_texture(render_id, texture, canvas);
+                true
+            },
+            RecvEvent::ReloadSpritesheetPipeline(render_id, pso) => {
+                self.reload_spritesheet_pipeline(render_id, pso);
+                true
+            },
+            RecvEvent::ReloadGridPipeline(pso) => {
+                self.reload_grid_pipeline(pso);
+                true
+            },
+            RecvEvent::ResizeCameraTarget(render_id, size, texture, out_color, out_depth) => {
+                self.resize_camera_target(render_id, size, texture, out_color, out_depth);
+                true
+            },
+            RecvEvent::RenderSoftware(sender) => {
+                self.software_requests.push(sender);
+                true
+            },
+            RecvEvent::RenderRegion(rect, size, sender) => {
+                self.region_requests.push(RegionRequest {
+                    rect: rect,
+                    image: Canvas::new(size.0.max(1), size.1.max(1)),
+                    next: (0, 0),
+                    sender: sender,
+                });
+                true
+            },
             RecvEvent::Exit => {
                 self.exit(arg);
                 match self.channel.0.send(SendEvent::Exited) {
@@ -262,6 +747,20 @@ impl specs::System<::utils::Delta> for System {
             return;
         }
 
+        if self.gl.is_none() {
+            loop {
+                match self.channel.1.try_recv() {
+                    Ok(event) => if !self.process_event(&arg, event) {
+                        return;
+                    },
+                    Err(TryRecvError::Empty) => break,
+                    Err(err) => panic!("run channel 1 try recv error: {}", err),
+                }
+            }
+            self.render_software(&arg);
+            return;
+        }
+
         let mut event = match self.channel.1.recv() {
             Ok(event) => event,
             Err(err) => panic!("run channel 1 recv error: {}", err),
@@ -274,3 +773,248 @@ impl specs::System<::utils::Delta> for System {
         }
     }
 }
+
+//a texture to show a camera through and the targets to draw it into
+pub fn make_camera_target(factory: &mut GLFactory, size: (u16, u16)) -> (
+    ShaderResourceView<Resources, [f32; 4]>,
+    RenderTargetView<Resources, ColorFormat>,
+    DepthStencilView<Resources, DepthFormat>
+) {
+    let (_, texture, out_color) = match factory.create_render_target::<ColorFormat>(size.0, size.1) {
+        Ok(target) => target,
+        Err(err) => panic!("make camera target create render target error: {:?}", err),
+    };
+    let out_depth = match factory.create_depth_stencil_view_only::<DepthFormat>(size.0, size.1) {
+        Ok(out_depth) => out_depth,
+        Err(err) => panic!("make camera target create depth stencil error: {:?}", err),
+    };
+    (texture, out_color, out_depth)
+}
+
+//draws a gathered frame through either backend, grid bounds of none skip the grid
+fn draw_frame<B: Backend>(backend: &mut B, frame: &Frame, grid_bounds: Option<[f32; 4]>, target_sizes: &[(RenderId, (u16, u16))]) {
+    backend.set_output(None);
+    let (width, height) = backend.get_output_size();
+
+    //one batch per view with the overlay last, all uploaded before anything is drawn
+    let mut batches: Vec<Vec<Vec<(u8, Instance)>>> = frame.views.iter().map(|view| collect_view_instances(view, frame)).collect();
+    let overlay_batch = batches.len();
+    batches.push(collect_overlay_instances(frame, target_sizes, width, height));
+    backend.upload_instances(batches);
+
+    //cameras with their own target draw first so the window can show them
+    for (batch, view) in frame.views.iter().enumerate() {
+        let render_id = match view.render_target {
+            Some(render_id) => render_id,
+            None => continue,
+        };
+        if !target_sizes.iter().any(|&(target, _)| target == render_id) {
+            warn!("render camera target {:?} was never added", render_id);
+            continue;
+        }
+
+        backend.set_output(Some(render_id.id));
+        backend.clear(art::camera::CLEAR_COLOR);
+        backend.clear_depth();
+
+        let outline = if view.is_minimap {
+            Some(&frame.main_view)
+        } else {
+            None
+        };
+        draw_view(backend, view, batch, outline, grid_bounds, frame.selected);
+    }
+
+    backend.set_output(None);
+    backend.clear(art::camera::CLEAR_COLOR);
+    backend.clear_depth();
+
+    for (batch, view) in frame.views.iter().enumerate() {
+        if view.render_target.is_none() {
+            draw_view(backend, view, batch, None, grid_bounds, frame.selected);
+        }
+    }
+
+    backend.clear_depth();
+    backend.draw_batch(overlay_batch, &make_screen_projection_data(width, height));
+}
+
+//the overlay goes over the whole world, starting with the camera targets in their viewports
+fn collect_overlay_instances(frame: &Frame, target_sizes: &[(RenderId, (u16, u16))], width: u32, height: u32) -> Vec<Vec<(u8, Instance)>> {
+    let mut overlay_instances = frame.overlay_instances.clone();
+    for view in frame.views.iter() {
+        let render_id = match view.render_target {
+            Some(render_id) => render_id,
+            None => continue,
+        };
+        let size = match target_sizes.iter().find(|&&(target, _)| target == render_id) {
+            Some(&(_, size)) => [size.0 as f32, size.1 as f32],
+            None => continue,
+        };
+        if render_id.id >= overlay_instances.len() {
+            continue;
+        }
+
+        let (min, max) = (view.viewport.get_bot_left(), view.viewport.get_top_right());
+        let (x, y) = (min.get_x() as f32 * width as f32, min.get_y() as f32 * height as f32);
+        let (w, h) = ((max.get_x() - min.get_x()) as f32 * width as f32, (max.get_y() - min.get_y()) as f32 * height as f32);
+
+        //the square packet mirrors u, and render targets are stored bottom row first so v is mirrored too
+        overlay_instances[render_id.id].push((0, Instance::new(
+            [
+                [w, 0.0, 0.0, 0.0],
+                [0.0, h, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [x, y, 0.0, 1.0],
+            ],
+            [1.0, 1.0, 1.0, 1.0],
+            [0.0, 0.0, size[0], size[1]],
+            size,
+            true,
+            true
+        )));
+    }
+    overlay_instances
+}
+
+//one software render of the request's next tile so huge images never need a window sized canvas,
+//returns true once the last tile is in
+fn draw_region_tile(software: &mut software::Renderer, frame: &Frame, grid_bounds: Option<[f32; 4]>, request: &mut RegionRequest) -> bool {
+    let (min, max) = (request.rect.get_bot_left(), request.rect.get_top_right());
+    let (width, height) = (request.image.get_width(), request.image.get_height());
+    let world_per_pixel = ((max.get_x() - min.get_x()) / width as f64, (max.get_y() - min.get_y()) / height as f64);
+    let tile_size = art::camera::EXPORT_TILE_SIZE;
+
+    let (left, top) = request.next;
+    let (right, bottom) = ((left + tile_size).min(width), (top + tile_size).min(height));
+    let tile = Rect::new_from_coords(
+        min.get_x() + left as f64 * world_per_pixel.0,
+        max.get_y() - bottom as f64 * world_per_pixel.1,
+        min.get_x() + right as f64 * world_per_pixel.0,
+        max.get_y() - top as f64 * world_per_pixel.1
+    );
+    software.set_window_size(right - left, bottom - top);
+    draw_region(software, frame, &View::new_region(tile), grid_bounds);
+    request.image.blit(&software.to_image(), left, top);
+
+    request.next = if right < width {
+        (right, top)
+    } else {
+        (0, bottom)
+    };
+    bottom >= height && right >= width
+}
+
+//just the world as one view sees it, with nothing selected and no overlay
+fn draw_region<B: Backend>(backend: &mut B, frame: &Frame, view: &View, grid_bounds: Option<[f32; 4]>) {
+    backend.set_output(None);
+    backend.clear(art::camera::CLEAR_COLOR);
+    backend.clear_depth();
+    backend.upload_instances(vec!(collect_view_instances(view, frame)));
+    draw_view(backend, view, 0, None, grid_bounds, [0.0, 0.0, 0.0, 0.0]);
+}
+
+//draws the grid and then the view's uploaded batch into the backend's output
+fn draw_view<B: Backend>(
+    backend: &mut B,
+    view: &View,
+    batch: usize,
+    outline: Option<&Rect>,
+    grid_bounds: Option<[f32; 4]>,
+    selected: [f32; 4]
+) {
+    if let Some(bounds) = grid_bounds {
+        let outline = match outline {
+            Some(rect) => [
+                rect.get_bot_left().get_x() as f32,
+                rect.get_bot_left().get_y() as f32,
+                rect.get_top_right().get_x() as f32,
+                rect.get_top_right().get_y() as f32,
+            ],
+            None => [1.0, 1.0, 0.0, 0.0],
+        };
+
+        backend.draw_grid(&grid::GridData {
+            inv_view_proj: view.inv_view_proj,
+            line_color: art::grid::LINE_COLOR,
+            cell_color: art::grid::CELL_COLOR,
+            outside_color: art::grid::OUTSIDE_COLOR,
+            selected_color: art::grid::SELECTED_COLOR,
+            bounds: bounds,
+            selected: selected,
+            outline: outline,
+            outline_color: art::grid::OUTLINE_COLOR,
+        });
+    }
+
+    backend.draw_batch(batch, &view.projection_data);
+}
+
+//every sprite the camera can see under its bundle with its layer
+fn collect_view_instances(view: &View, frame: &Frame) -> Vec<Vec<(u8, Instance)>> {
+    //skip any sprite that misses the view
+    let (view_min, view_max) = (view.view_rect.get_bot_left(), view.view_rect.get_top_right());
+
+    let mut instances: Vec<Vec<(u8, Instance)>> = vec!();
+    {
+        let mut push = |sprite: &Sprite| {
+            while instances.len() <= sprite.render_id {
+                instances.push(vec!());
+            }
+            instances[sprite.render_id].push((sprite.layer, sprite.instance));
+        };
+
+        for sprite in &frame.sprites {
+            if let Some((x0, y0, x1, y1)) = sprite.bounds {
+                if x1 < view_min.get_x() || x0 > view_max.get_x() || y1 < view_min.get_y() || y0 > view_max.get_y() {
+                    continue;
+                }
+            }
+            push(sprite);
+        }
+
+        //cells span one tile from their location so the one left of and below the view can still show,
+        //zoomed far out it is cheaper to walk the board than every cell in view
+        let (x0, y0) = (view_min.get_x().floor() as CoordI - 1, view_min.get_y().floor() as CoordI - 1);
+        let (x1, y1) = (view_max.get_x().floor() as CoordI, view_max.get_y().floor() as CoordI);
+        let cells = (x1 - x0 + 1) as u64 * (y1 - y0 + 1) as u64;
+        if cells <= frame.board.len() as u64 {
+            for x in x0..x1 + 1 {
+                for y in y0..y1 + 1 {
+                    if let Some(cell) = frame.board.get(&(x, y)) {
+                        for sprite in cell {
+                            push(sprite);
+                        }
+                    }
+                }
+            }
+        } else {
+            for (&(x, y), cell) in &frame.board {
+                if x >= x0 && x <= x1 && y >= y0 && y <= y1 {
+                    for sprite in cell {
+                        push(sprite);
+                    }
+                }
+            }
+        }
+    }
+    instances
+}
+
+//pixels from the bottom left of the output
+fn make_screen_projection_data(width: u32, height: u32) -> ProjectionData {
+    ProjectionData {
+        view: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+        proj: [
+            [2.0 / width as f32, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / height as f32, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [-1.0, -1.0, 0.0, 1.0],
+        ],
+    }
+}
@@ -0,0 +1,72 @@
+use comps::non_components::{Map};
+
+use math::{Point2I, Point3I, Router};
+
+use netlist::level::{Level};
+use netlist::save::{Item};
+
+use utils::{CoordI};
+
+//*************************************************************************************************
+
+pub const LAYERS: (CoordI, CoordI) = (0, 3);
+
+//cells a route may stray past what is already placed, enough to walk around its edge
+const MARGIN: CoordI = 2;
+
+//a path over free cells from one end to the other, both ends included
+pub fn find_path(from: &Point3I, to: &Point3I, map: &Map, level: Option<&Level>) -> Option<Vec<Point3I>> {
+    let mut router = Router::new_layered(LAYERS.0, LAYERS.1);
+    router.set_bounds(Some(make_bounds(from, to, map, level)));
+
+    let is_blocked = |location: &Point3I| {
+        map.get_map().contains_key(location) || level.map_or(false, |level| !level.contains(location))
+    };
+    router.route(from.clone(), to.clone(), is_blocked)
+}
+
+//the level's board, or the occupied cells and a margin without one, so a search with nowhere to go stops
+pub fn make_bounds(from: &Point3I, to: &Point3I, map: &Map, level: Option<&Level>) -> (Point2I, Point2I) {
+    let (min, max, margin) = match level {
+        Some(level) => {
+            let (width, height) = level.get_size();
+            (Point2I::new(0, 0), Point2I::new(width - 1, height - 1), 0)
+        },
+        None => match map.get_bounds() {
+            Some((min, max)) => (min, max, MARGIN),
+            None => (Point2I::new(from.get_x(), from.get_y()), Point2I::new(from.get_x(), from.get_y()), MARGIN),
+        },
+    };
+
+    //the ends are always inside
+    (
+        Point2I::new(min.get_x().min(from.get_x()).min(to.get_x()) - margin, min.get_y().min(from.get_y()).min(to.get_y()) - margin),
+        Point2I::new(max.get_x().max(from.get_x()).max(to.get_x()) + margin, max.get_y().max(from.get_y()).max(to.get_y()) + margin)
+    )
+}
+
+//the item at the start of a route leading into cell, none when it has no output to lead
+pub fn connect_output(item: &Item, cell: &Point3I, map: &Map) -> Option<Item> {
+    match *item {
+        Item::Wire(ref me, ref input, _) => Some(Item::Wire(me.clone(), input.clone(), cell.clone())),
+        Item::WireIn(ref me, ref outputs, ref name) => Some(Item::WireIn(me.clone(), add_output(outputs, cell, map), name.clone())),
+        Item::Gate(kind, ref me, ref outputs, ref inputs) => Some(Item::Gate(kind, me.clone(), add_output(outputs, cell, map), inputs.clone())),
+        Item::WireOut(..) => None,
+    }
+}
+
+//the item at the end of a route fed from cell, none when it has no input to feed
+pub fn connect_input(item: &Item, cell: &Point3I) -> Option<Item> {
+    match *item {
+        Item::Wire(ref me, _, ref output) => Some(Item::Wire(me.clone(), cell.clone(), output.clone())),
+        Item::WireOut(ref me, _, ref name) => Some(Item::WireOut(me.clone(), cell.clone(), name.clone())),
+        Item::WireIn(..) | Item::Gate(..) => None,
+    }
+}
+
+//a driver keeps every output that already leads somewhere, placeholders pointing at empty cells are dropped
+fn add_output(outputs: &[Point3I], cell: &Point3I, map: &Map) -> Vec<Point3I> {
+    let mut outputs: Vec<Point3I> = outputs.iter().filter(|&output| output != cell && map.get_map().contains_key(output)).cloned().collect();
+    outputs.push(cell.clone());
+    outputs
+}
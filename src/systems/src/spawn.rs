@@ -0,0 +1,109 @@
+use specs::{World, Entity};
+
+use nalgebra;
+
+//*************************************************************************************************
+
+use comps::{RenderId, Transform, RenderData, Wire, WireIn, WireOut, Gate};
+use comps::non_components::{Map};
+
+use math::{Point3I};
+
+use netlist::save::{Item};
+
+use utils::{GfxCoord};
+
+use art::{layers, wires, gates};
+
+//*************************************************************************************************
+
+//creates the entity for a saved item at the end of the frame and maps its location straight away
+pub fn spawn_item(world: &World, item: &Item, wires_render: RenderId) -> Entity {
+    let location = item.get_location().clone();
+
+    let entity = world.create_later();
+
+    world.write::<RenderId>().insert(entity, wires_render);
+    world.write::<Transform>().insert(entity, make_tile_transform(&location));
+
+    match *item {
+        Item::Wire(ref me, ref input, ref output) => {
+            world.write::<RenderData>().insert(entity, RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE));
+            world.write::<Wire>().insert(entity, Wire::new_from_points(input.clone(), output.clone(), me.clone()));
+        },
+        Item::WireIn(ref me, ref outputs, ref name) => {
+            world.write::<RenderData>().insert(entity, RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE));
+            world.write::<WireIn>().insert(entity, match *name {
+                Some(ref name) => WireIn::new_named(outputs.clone(), me.clone(), name),
+                None => WireIn::new_from_points(outputs.clone(), me.clone()),
+            });
+        },
+        Item::WireOut(ref me, ref input, ref name) => {
+            world.write::<RenderData>().insert(entity, RenderData::new(layers::WIRES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE));
+            world.write::<WireOut>().insert(entity, match *name {
+                Some(ref name) => WireOut::new_named(input.clone(), me.clone(), name),
+                None => WireOut::new_from_points(input.clone(), me.clone()),
+            });
+        },
+        Item::Gate(kind, ref me, ref outputs, ref inputs) => {
+            world.write::<RenderData>().insert(entity, RenderData::new(layers::GATES, gates::DEFAULT_TINT, gates::RECT, gates::SIZE));
+            world.write::<Gate>().insert(entity, Gate::new_from_points(kind, inputs.clone(), outputs.clone(), me.clone()));
+        },
+    }
+
+    world.write_resource::<Map>().get_mut_map().insert(location, entity);
+
+    entity
+}
+
+//removes the entity at location and clears every fast link that pointed at it
+pub fn despawn_at(world: &World, location: &Point3I) -> Option<Entity> {
+    use specs::Join;
+
+    let entity = match world.write_resource::<Map>().get_mut_map().remove(location) {
+        Some(entity) => entity,
+        None => return None,
+    };
+
+    let clear = |fast: &mut Option<Entity>| if *fast == Some(entity) {
+        *fast = None;
+    };
+
+    for wire in (&mut world.write::<Wire>()).iter() {
+        clear(wire.get_mut_input().get_mut_fast());
+        clear(wire.get_mut_output().get_mut_fast());
+    }
+
+    for wire_in in (&mut world.write::<WireIn>()).iter() {
+        for output in wire_in.get_mut_outputs().iter_mut() {
+            clear(output.get_mut_fast());
+        }
+    }
+
+    for wire_out in (&mut world.write::<WireOut>()).iter() {
+        clear(wire_out.get_mut_input().get_mut_fast());
+    }
+
+    for gate in (&mut world.write::<Gate>()).iter() {
+        for input in gate.get_mut_inputs().iter_mut() {
+            clear(input.get_mut_fast());
+        }
+        for output in gate.get_mut_outputs().iter_mut() {
+            clear(output.get_mut_fast());
+        }
+    }
+
+    world.delete_later(entity);
+
+    Some(entity)
+}
+
+pub fn make_tile_transform(location: &Point3I) -> Transform {
+    Transform::new(
+        nalgebra::Isometry3::new(
+            nalgebra::Vector3::new(location.get_x() as GfxCoord, location.get_y() as GfxCoord, 1.0),
+            nalgebra::Vector3::new(0.0, 0.0, 0.0)
+        ),
+        nalgebra::Vector3::new(1.0, 1.0, 1.0)
+    )
+}
@@ -0,0 +1,138 @@
+extern crate components;
+extern crate math;
+extern crate systems;
+
+use components::non_components::{Console};
+
+use math::{Rect};
+
+use systems::console::{History, complete, parse_word, make_export_size};
+use systems::hud::{make_console_text};
+
+//*************************************************************************************************
+
+#[test]
+fn completes_a_unique_command_with_a_space() {
+    assert_eq!(complete("he"), ("help ".to_string(), vec!("help")));
+    assert_eq!(complete("sp"), ("split ".to_string(), vec!("split")));
+}
+
+#[test]
+fn completes_to_the_common_prefix_of_several_commands() {
+    let (input, options) = complete("ex");
+
+    assert_eq!(input, "export");
+    assert_eq!(options, vec!("export", "export_board"));
+}
+
+#[test]
+fn empty_input_lists_every_command() {
+    let (input, options) = complete("");
+
+    assert_eq!(input, "");
+    assert!(options.contains(&"help"));
+    assert!(options.contains(&"quit"));
+}
+
+#[test]
+fn completes_place_kinds_after_place() {
+    assert_eq!(complete("place w"), ("place wire ".to_string(), vec!("wire")));
+    assert_eq!(complete("place xo"), ("place xor ".to_string(), vec!("xor")));
+
+    let (input, options) = complete("place n");
+    assert_eq!(input, "place n");
+    assert_eq!(options.len(), 3);
+    assert!(options.contains(&"not") && options.contains(&"nand") && options.contains(&"nor"));
+}
+
+#[test]
+fn unmatched_or_unknown_words_are_left_alone() {
+    assert_eq!(complete("xyz"), ("xyz".to_string(), vec!()));
+    assert_eq!(complete("zoom 1"), ("zoom 1".to_string(), vec!()));
+    assert_eq!(complete("place and 1"), ("place and 1".to_string(), vec!()));
+}
+
+#[test]
+fn history_walks_back_and_stops_at_the_oldest() {
+    let mut history = History::new();
+    history.push("fit".to_string());
+    history.push("zoom 2".to_string());
+
+    assert_eq!(history.prev(), Some("zoom 2".to_string()));
+    assert_eq!(history.prev(), Some("fit".to_string()));
+    assert_eq!(history.prev(), Some("fit".to_string()));
+}
+
+#[test]
+fn history_walks_forward_to_an_empty_input() {
+    let mut history = History::new();
+    history.push("fit".to_string());
+    history.push("zoom 2".to_string());
+
+    assert_eq!(history.next(), None);
+    history.prev();
+    history.prev();
+    assert_eq!(history.next(), Some("zoom 2".to_string()));
+    assert_eq!(history.next(), Some(String::new()));
+    assert_eq!(history.next(), None);
+}
+
+#[test]
+fn history_skips_blank_lines_and_restarts_on_submit() {
+    let mut history = History::new();
+    assert_eq!(history.prev(), None);
+
+    history.push("fit".to_string());
+    history.push("   ".to_string());
+    assert_eq!(history.prev(), Some("fit".to_string()));
+
+    history.push("undo".to_string());
+    assert_eq!(history.prev(), Some("undo".to_string()));
+}
+
+#[test]
+fn parse_word_reads_the_indexed_word() {
+    let words = vec!("goto", "3", "-4", "x");
+
+    assert_eq!(parse_word::<i32>(&words, 1, "goto x y"), Ok(3));
+    assert_eq!(parse_word::<i32>(&words, 2, "goto x y"), Ok(-4));
+}
+
+#[test]
+fn parse_word_reports_usage_when_missing_or_malformed() {
+    let words = vec!("goto", "3", "x");
+
+    assert_eq!(parse_word::<i32>(&words, 2, "goto x y"), Err("usage: goto x y".to_string()));
+    assert_eq!(parse_word::<i32>(&words, 3, "goto x y"), Err("usage: goto x y".to_string()));
+}
+
+#[test]
+fn console_text_shows_recent_lines_and_the_input_while_open() {
+    let mut console = Console::new();
+    for index in 0..5 {
+        console.push_line(format!("line {}", index));
+    }
+    console.get_mut_input().push_str("fi");
+
+    assert_eq!(make_console_text(&console, 2), "");
+
+    *console.get_mut_open() = true;
+    assert_eq!(make_console_text(&console, 2), "line 3\nline 4\n> fi");
+    assert_eq!(make_console_text(&console, 10), "line 0\nline 1\nline 2\nline 3\nline 4\n> fi");
+}
+
+#[test]
+fn export_size_is_the_rect_at_pixels_per_tile() {
+    let rect = Rect::new_from_coords(-1.0, -1.0, 5.0, 3.0);
+
+    assert_eq!(make_export_size(&rect, 32), Some((192, 128)));
+    assert_eq!(make_export_size(&rect, 1), Some((6, 4)));
+}
+
+#[test]
+fn export_size_that_overflows_is_none() {
+    let rect = Rect::new_from_coords(0.0, 0.0, 70000.0, 2.0);
+
+    assert_eq!(make_export_size(&rect, 70000), None);
+    assert_eq!(make_export_size(&Rect::new_from_coords(0.0, 0.0, 2.0, 70000.0), 70000), None);
+}
@@ -0,0 +1,30 @@
+extern crate components;
+extern crate math;
+extern crate systems;
+
+use components::non_components::{Selection};
+
+use math::{Point2I};
+
+use systems::hud::{make_mode_text, make_value_text};
+
+//*************************************************************************************************
+
+#[test]
+fn mode_text_shows_the_layer_and_a_waiting_route() {
+    let mut selection = Selection::new();
+    assert_eq!(make_mode_text(&selection), "layer 0");
+
+    selection.set_layer(2);
+    selection.set_route_start(Some(Point2I::new(3, -4)));
+    assert_eq!(make_mode_text(&selection), "layer 2, routing from 3, -4");
+
+    selection.set_route_start(None);
+    assert_eq!(make_mode_text(&selection), "layer 2");
+}
+
+#[test]
+fn value_text_follows_the_name() {
+    assert_eq!(make_value_text("carry", Some(1)), "carry 1");
+    assert_eq!(make_value_text("carry", None), "carry -");
+}
@@ -0,0 +1,220 @@
+extern crate specs;
+extern crate nalgebra;
+extern crate art;
+extern crate components;
+extern crate graphics;
+extern crate math;
+extern crate utils;
+extern crate systems;
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Sender, Receiver, TryRecvError};
+
+use specs::{Planner, World};
+
+use art::{layers, wires, gates, make_square_render};
+
+use components::{RenderId, Transform, Camera, RenderData, Text, Overlay};
+use components::non_components::{Map, Selection};
+
+use graphics::software::Canvas;
+
+use math::{OrthographicHelper, Point3I, Rect};
+
+use utils::{Delta, GfxCoord};
+
+use systems::render::{self, RecvEvent, SendEvent};
+use systems::spawn::make_tile_transform;
+
+//*************************************************************************************************
+
+//four tiles by three at sixteen pixels a tile
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+const VALUE_TINT: [f32; 4] = [0.9, 0.2, 0.1, 1.0];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+//rounding in the blend may move a channel by one
+const TOLERANCE: u8 = 2;
+
+fn make_golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(name)
+}
+
+//red and green over blue and yellow, so any mirroring shows
+fn make_gate_texture() -> Canvas {
+    let pixels = vec!(
+        255, 0, 0, 255, 0, 255, 0, 255,
+        0, 0, 255, 255, 255, 255, 0, 255,
+    );
+    Canvas::from_pixels(2, 2, pixels).expect("gate texture pixels did not fit")
+}
+
+fn make_sprite_transform(x: GfxCoord, y: GfxCoord) -> Transform {
+    Transform::new(
+        nalgebra::Isometry3::new(nalgebra::Vector3::new(x, y, 1.0), nalgebra::Vector3::new(0.0, 0.0, 0.0)),
+        nalgebra::Vector3::new(1.0, 1.0, 1.0)
+    )
+}
+
+fn make_render_data(layer: u8, tint: [f32; 4], rect: [f32; 4], size: [f32; 2], mirror_x: bool, mirror_y: bool) -> RenderData {
+    let mut render_data = RenderData::new(layer, tint, rect, size);
+    render_data.set_mirror_x(mirror_x);
+    render_data.set_mirror_y(mirror_y);
+    render_data
+}
+
+fn make_world() -> World {
+    let mut world = World::new();
+    world.register::<RenderId>();
+    world.register::<Transform>();
+    world.register::<Camera>();
+    world.register::<RenderData>();
+    world.register::<Text>();
+    world.register::<Overlay>();
+    world.add_resource(Map::new());
+    world.add_resource(Selection::new());
+
+    //framed so the four by three tiles from the origin exactly fill the window
+    let ortho_helper = OrthographicHelper::new(WIDTH as GfxCoord / HEIGHT as GfxCoord, std::f32::consts::PI / 2.0, 0.0, 10.0);
+    let mut camera = Camera::new_from_ortho_helper(
+        nalgebra::Point3::new(0.0, 0.0, 2.0),
+        nalgebra::Point3::new(0.0, 0.0, 0.0),
+        nalgebra::Vector3::new(0.0, 1.0, 0.0),
+        &ortho_helper,
+        true
+    );
+    camera.zoom_to_fit(&Rect::new_from_coords(0.0, 0.0, 4.0, 3.0));
+    world.create_now().with(camera).build();
+
+    world
+}
+
+fn add_board(world: &mut World, render_id: RenderId, location: Point3I, render_data: RenderData) {
+    let entity = world.create_now()
+        .with(render_id)
+        .with(make_tile_transform(&location))
+        .with(render_data)
+        .build();
+    world.write_resource::<Map>().get_mut_map().insert(location, entity);
+}
+
+fn add_sprite(world: &mut World, render_id: RenderId, x: GfxCoord, y: GfxCoord, render_data: RenderData) {
+    world.create_now()
+        .with(render_id)
+        .with(make_sprite_transform(x, y))
+        .with(render_data)
+        .build();
+}
+
+//a software only render system alone in a planner, with the ends of its channel the game would hold
+fn make_planner(world: World, add: &Fn(&mut render::System) -> ()) -> (Planner<Delta>, Sender<RecvEvent>, Receiver<SendEvent>) {
+    let (send_to_game, from_render) = channel();
+    let (send_to_render, from_game) = channel();
+
+    let mut render = render::System::new_software((send_to_game, from_game), WIDTH, HEIGHT);
+    add(&mut render);
+
+    let mut planner = Planner::<Delta>::new(world, 1);
+    planner.add_system(render, "renderer", 10);
+    (planner, send_to_render, from_render)
+}
+
+fn run_once(planner: &mut Planner<Delta>) {
+    planner.dispatch(0.0);
+    planner.wait();
+}
+
+//draws one frame of the world
+fn render_world(world: World, add: &Fn(&mut render::System) -> ()) -> Canvas {
+    let (mut planner, send_to_render, _from_render) = make_planner(world, add);
+
+    let (send_image, image) = channel();
+    send_to_render.send(RecvEvent::RenderSoftware(send_image)).expect("render request send failed");
+    run_once(&mut planner);
+
+    image.recv().expect("render sent no image")
+}
+
+#[test]
+fn gates_and_wires_match_the_golden_image() {
+    let wires_texture = Canvas::load(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets").join(wires::NAME)).expect("wires texture is missing");
+
+    let (wires_render, gates_render) = (RenderId { id: 0 }, RenderId { id: 1 });
+    let gate_rect = [0.0, 0.0, 2.0, 2.0];
+    let gate_size = [2.0, 2.0];
+
+    let mut world = make_world();
+
+    //a row of wires on the board, the last one tinted with its value
+    for x in 0..4 {
+        let tint = if x == 3 { VALUE_TINT } else { wires::DEFAULT_TINT };
+        add_board(&mut world, wires_render, Point3I::new(x, 1, 0), make_render_data(layers::WIRES, tint, wires::RECT, wires::SIZE, false, false));
+    }
+
+    //gates over the wires, plain, mirrored across and mirrored up
+    add_board(&mut world, gates_render, Point3I::new(1, 1, 1), make_render_data(layers::GATES, gates::DEFAULT_TINT, gate_rect, gate_size, false, false));
+    add_board(&mut world, gates_render, Point3I::new(2, 1, 1), make_render_data(layers::GATES, gates::DEFAULT_TINT, gate_rect, gate_size, true, false));
+    add_board(&mut world, gates_render, Point3I::new(3, 0, 1), make_render_data(layers::GATES, WHITE, gate_rect, gate_size, false, true));
+    add_board(&mut world, gates_render, Point3I::new(0, 2, 1), make_render_data(layers::GATES, WHITE, gate_rect, gate_size, false, false));
+
+    //a higher layer wins over a later bundle, and on the same layer the later bundle wins
+    add_sprite(&mut world, wires_render, 0.5, 2.0, make_render_data(layers::TEXT, wires::DEFAULT_TINT, wires::RECT, wires::SIZE, false, false));
+    add_board(&mut world, wires_render, Point3I::new(3, 2, 0), make_render_data(layers::GATES, wires::DEFAULT_TINT, wires::RECT, wires::SIZE, false, false));
+    add_sprite(&mut world, gates_render, 2.5, 2.0, make_render_data(layers::GATES, WHITE, gate_rect, gate_size, false, false));
+
+    let image = render_world(world, &|render| {
+        assert_eq!(render.add_software_spritesheet(&make_square_render(), wires_texture.clone()), wires_render);
+        assert_eq!(render.add_software_spritesheet(&make_square_render(), make_gate_texture()), gates_render);
+    });
+
+    //run with UPDATE_GOLDEN set to write the image out again after a deliberate change
+    let path = make_golden_path("scene.png");
+    if env::var("UPDATE_GOLDEN").is_ok() {
+        image.save(&path).expect("golden save failed");
+    }
+
+    let golden = Canvas::load(&path).expect("golden image is missing");
+    let differences = image.count_differences(&golden, TOLERANCE);
+    if differences != Some(0) {
+        let actual = env::temp_dir().join("inception-render-scene.png");
+        image.save(&actual).expect("actual image save failed");
+        panic!("{:?} pixels differ from {}, the frame drawn is at {}", differences, path.display(), actual.display());
+    }
+}
+
+#[test]
+fn an_empty_world_is_cleared_to_the_clear_color() {
+    let image = render_world(make_world(), &|_| ());
+    let clear = art::camera::CLEAR_COLOR;
+
+    assert_eq!((image.get_width(), image.get_height()), (WIDTH, HEIGHT));
+    let expected: Vec<u8> = clear.iter().map(|&channel| (channel * 255.0 + 0.5) as u8).collect();
+    assert_eq!(image.get_pixel(0, 0).to_vec(), expected);
+    assert_eq!(image.get_pixel(WIDTH - 1, HEIGHT - 1).to_vec(), expected);
+}
+
+#[test]
+fn regions_are_drawn_a_tile_a_frame_and_keep_the_window_size() {
+    let (mut planner, send_to_render, _from_render) = make_planner(make_world(), &|_| ());
+    let tile_size = art::camera::EXPORT_TILE_SIZE;
+
+    //three tiles across, the last one only a pixel wide
+    let (send_region, region) = channel();
+    let rect = Rect::new_from_coords(0.0, 0.0, 4.0, 1.0);
+    send_to_render.send(RecvEvent::RenderRegion(rect, (tile_size * 2 + 1, 2), send_region)).expect("region request send failed");
+
+    run_once(&mut planner);
+    assert_eq!(region.try_recv().err(), Some(TryRecvError::Empty));
+    run_once(&mut planner);
+    assert_eq!(region.try_recv().err(), Some(TryRecvError::Empty));
+    run_once(&mut planner);
+    let image = region.try_recv().expect("region was not finished after its last tile");
+    assert_eq!((image.get_width(), image.get_height()), (tile_size * 2 + 1, 2));
+
+    let (send_image, window) = channel();
+    send_to_render.send(RecvEvent::RenderSoftware(send_image)).expect("render request send failed");
+    run_once(&mut planner);
+    let image = window.recv().expect("render sent no image");
+    assert_eq!((image.get_width(), image.get_height()), (WIDTH, HEIGHT));
+}
@@ -0,0 +1,65 @@
+extern crate specs;
+extern crate components;
+extern crate math;
+extern crate netlist;
+extern crate systems;
+
+use specs::World;
+
+use components::non_components::{Map};
+
+use math::{Point2I, Point3I};
+
+use netlist::save::{Item};
+
+use systems::router::{find_path, make_bounds, connect_output, connect_input};
+
+//*************************************************************************************************
+
+fn make_map(world: &mut World, locations: &[Point3I]) -> Map {
+    let mut map = Map::new();
+    for location in locations {
+        let entity = world.create_now().build();
+        map.get_mut_map().insert(location.clone(), entity);
+    }
+    map
+}
+
+#[test]
+fn bounds_hold_the_placed_cells_and_both_ends_with_a_margin() {
+    let mut world = World::new();
+    let map = make_map(&mut world, &[Point3I::new(0, 0, 0), Point3I::new(4, 1, 0)]);
+
+    let bounds = make_bounds(&Point3I::new(0, 0, 0), &Point3I::new(6, -1, 0), &map, None);
+
+    assert_eq!(bounds, (Point2I::new(-2, -3), Point2I::new(8, 3)));
+}
+
+#[test]
+fn an_enclosed_end_is_not_found() {
+    let mut world = World::new();
+    let walls = [Point3I::new(4, 0, 0), Point3I::new(2, 0, 0), Point3I::new(3, 1, 0), Point3I::new(3, -1, 0)];
+    let mut locations: Vec<Point3I> = walls.iter().cloned().collect();
+    //every layer above is walled off too
+    for z in 1..4 {
+        locations.push(Point3I::new(3, 0, z));
+    }
+    let map = make_map(&mut world, &locations);
+
+    assert_eq!(find_path(&Point3I::new(0, 0, 0), &Point3I::new(3, 0, 0), &map, None), None);
+}
+
+#[test]
+fn route_ends_are_rewired_into_the_path() {
+    let mut world = World::new();
+    let map = make_map(&mut world, &[Point3I::new(0, 0, 0), Point3I::new(0, 1, 0)]);
+    let cell = Point3I::new(1, 0, 0);
+
+    //placeholders pointing at empty cells are dropped, outputs that lead somewhere are kept
+    let wire_in = Item::WireIn(Point3I::new(0, 0, 0), vec!(Point3I::new(0, 1, 0), Point3I::new(-1, 0, 0)), None);
+    assert_eq!(connect_output(&wire_in, &cell, &map), Some(Item::WireIn(Point3I::new(0, 0, 0), vec!(Point3I::new(0, 1, 0), cell.clone()), None)));
+
+    let wire_out = Item::WireOut(Point3I::new(2, 0, 0), Point3I::new(3, 0, 0), Some("y".to_string()));
+    assert_eq!(connect_input(&wire_out, &cell), Some(Item::WireOut(Point3I::new(2, 0, 0), cell.clone(), Some("y".to_string()))));
+    assert_eq!(connect_output(&wire_out, &cell, &map), None);
+}
@@ -3,6 +3,7 @@ use ::Delta;
 pub struct FpsCounter {
     current_delta: Delta,
     frames: u32,
+    fps: u32,
 }
 
 impl FpsCounter {
@@ -10,6 +11,7 @@ impl FpsCounter {
         FpsCounter {
             current_delta: 0.0,
             frames: 0,
+            fps: 0,
         }
     }
 
@@ -25,7 +27,13 @@ impl FpsCounter {
             } else {
                 info!("FPS: {}", self.frames);
             }
+            self.fps = self.frames;
             self.frames = 0;
         }
     }
+
+    //frames counted over the last full second
+    pub fn get_fps(&self) -> u32 {
+        self.fps
+    }
 }